@@ -0,0 +1,19 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use wasm_game_of_life::UniverseBuilder;
+
+fn tick(c: &mut Criterion) {
+    for &size in [64u32, 256].iter() {
+        let mut universe = UniverseBuilder::new()
+            .width(size)
+            .height(size)
+            .seed(42)
+            .build()
+            .unwrap();
+        c.bench_function(&format!("tick {}x{}", size, size), |b| {
+            b.iter(|| universe.tick())
+        });
+    }
+}
+
+criterion_group!(benches, tick);
+criterion_main!(benches);
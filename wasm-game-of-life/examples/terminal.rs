@@ -0,0 +1,189 @@
+//! 在终端中运行生命游戏，方便不打开浏览器就调试规则：
+//!
+//! ```text
+//! cargo run --example terminal -- --width 60 --height 30 --rule B36/S23 --fps 20
+//! cargo run --example terminal -- --pattern glider.rle --generations 200
+//! ```
+//!
+//! 每一代都以 `Display` 的格式输出（通过 `render_into` 复用缓冲区）并把光标移回左上角，原地刷新；
+//! 所有 Cell 不再变化或全部死亡时退出，也可以随时按 Ctrl-C 退出。
+//!
+//! `--compare-engines N` 不显示动画，而是用每个引擎演化同一锅汤 N 代，
+//! 输出各自的速度（最终状态不一致时 panic），可以在 CI 中跟踪性能：
+//!
+//! ```text
+//! cargo run --release --example terminal -- --width 256 --height 256 --compare-engines 200
+//! ```
+
+use std::env;
+use std::fs;
+use std::process;
+use std::thread;
+use std::time::Duration;
+
+use wasm_game_of_life::{compare_engines, parse_rle, TickStatus, Universe, UniverseBuilder};
+
+const USAGE: &str = "usage: terminal [--width N] [--height N] [--seed N] [--density P] \
+[--rule B3/S23] [--pattern FILE.rle] [--fps N] [--generations N] [--compare-engines N]";
+
+struct Options {
+    width: u32,
+    height: u32,
+    seed: Option<u64>,
+    density: Option<f64>,
+    rule: Option<String>,
+    pattern: Option<String>,
+    fps: u32,
+    generations: Option<u64>,
+    compare_engines: Option<u32>,
+}
+
+fn parse_args() -> Result<Options, String> {
+    let mut options = Options {
+        width: 64,
+        height: 32,
+        seed: None,
+        density: None,
+        rule: None,
+        pattern: None,
+        fps: 10,
+        generations: None,
+        compare_engines: None,
+    };
+
+    let mut args = env::args().skip(1);
+    while let Some(flag) = args.next() {
+        if flag == "-h" || flag == "--help" {
+            println!("{}", USAGE);
+            process::exit(0);
+        }
+        let value = args
+            .next()
+            .ok_or_else(|| format!("missing value for {}", flag))?;
+        let invalid = || format!("invalid value for {}: '{}'", flag, value);
+        match flag.as_str() {
+            "--width" => options.width = value.parse().map_err(|_| invalid())?,
+            "--height" => options.height = value.parse().map_err(|_| invalid())?,
+            "--seed" => options.seed = Some(value.parse().map_err(|_| invalid())?),
+            "--density" => options.density = Some(value.parse().map_err(|_| invalid())?),
+            "--rule" => options.rule = Some(value),
+            "--pattern" => options.pattern = Some(value),
+            "--fps" => options.fps = value.parse().map_err(|_| invalid())?,
+            "--generations" => options.generations = Some(value.parse().map_err(|_| invalid())?),
+            "--compare-engines" => {
+                options.compare_engines = Some(value.parse().map_err(|_| invalid())?)
+            }
+            _ => return Err(format!("unknown option {}", flag)),
+        }
+    }
+
+    if options.fps == 0 {
+        return Err("--fps must be positive".to_string());
+    }
+    Ok(options)
+}
+
+fn build(options: &Options) -> Result<Universe, String> {
+    let mut builder = UniverseBuilder::new()
+        .width(options.width)
+        .height(options.height);
+    if let Some(ref rule) = options.rule {
+        builder = builder.rule(rule);
+    }
+    if let Some(seed) = options.seed {
+        builder = builder.seed(seed);
+    }
+
+    let pattern = match options.pattern {
+        Some(ref path) => {
+            Some(fs::read_to_string(path).map_err(|err| format!("{}: {}", path, err))?)
+        }
+        None => None,
+    };
+    // 放置图案时其余 Cell 都是死亡的
+    builder = match (pattern.is_some(), options.density) {
+        (true, _) => builder.density(0.0),
+        (false, Some(density)) => builder.density(density),
+        (false, None) => builder,
+    };
+    let mut universe = builder.build().map_err(|err| err.to_string())?;
+
+    if let Some(ref rle) = pattern {
+        // 把图案放在中间
+        let size = parse_rle(rle).map_err(|err| err.to_string())?;
+        let row = options.height.saturating_sub(size.height) / 2;
+        let column = options.width.saturating_sub(size.width) / 2;
+        universe
+            .stamp_rle(rle, row, column)
+            .map_err(|err| err.to_string())?;
+    }
+    Ok(universe)
+}
+
+/// 每行一个引擎：名称、代数、耗时和每秒代数，当前构建中不可用的引擎标记为 skipped
+fn run_compare_engines(options: &Options, generations: u32) -> Result<(), String> {
+    let results = compare_engines(
+        options.width,
+        options.height,
+        options.density.unwrap_or(0.5),
+        options.seed.unwrap_or(42),
+        generations,
+    )
+    .map_err(|err| err.to_string())?;
+    for result in results {
+        if result.skipped {
+            println!("{:<12} skipped", result.engine.name());
+            continue;
+        }
+        println!(
+            "{:<12} {:>8} generations {:>8} ms {:>12.1} gen/s",
+            result.engine.name(),
+            result.generations,
+            result.elapsed_ms,
+            result.generations_per_sec
+        );
+    }
+    Ok(())
+}
+
+fn main() {
+    let options = parse_args().unwrap_or_else(|err| {
+        eprintln!("{}\n{}", err, USAGE);
+        process::exit(2);
+    });
+    if let Some(generations) = options.compare_engines {
+        run_compare_engines(&options, generations).unwrap_or_else(|err| {
+            eprintln!("{}", err);
+            process::exit(1);
+        });
+        return;
+    }
+    let mut universe = build(&options).unwrap_or_else(|err| {
+        eprintln!("{}", err);
+        process::exit(1);
+    });
+
+    let frame = Duration::from_millis(1000 / options.fps as u64);
+    let mut buf = String::new();
+    let mut stopped = None;
+    // 清屏一次，之后每一帧只把光标移回左上角
+    print!("\x1b[2J");
+    loop {
+        universe.render_into(&mut buf);
+        println!(
+            "\x1b[H{}generation {}  population {}{}\x1b[K",
+            buf,
+            universe.generation(),
+            universe.population(),
+            stopped.map_or(String::new(), |status| format!("  ({:?})", status))
+        );
+
+        if stopped.is_some() || options.generations == Some(universe.generation()) {
+            break;
+        }
+        match universe.tick_status() {
+            TickStatus::Changed => thread::sleep(frame),
+            status => stopped = Some(status),
+        }
+    }
+}
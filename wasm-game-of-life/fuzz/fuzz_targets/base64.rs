@@ -0,0 +1,16 @@
+//! `decode_state` 的 base64 格式：不能 panic；解码成功后重新编码必须得到同样的状态
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use wasm_game_of_life::Universe;
+
+fuzz_target!(|data: &str| {
+    let universe = match Universe::decode_state(data) {
+        Ok(universe) => universe,
+        Err(_) => return,
+    };
+    let reparsed = Universe::decode_state(&universe.encode_state()).unwrap();
+    assert_eq!(reparsed.width(), universe.width());
+    assert_eq!(reparsed.height(), universe.height());
+    assert_eq!(reparsed.get_cells(), universe.get_cells());
+});
@@ -0,0 +1,19 @@
+//! Macrocell：不能 panic；能解析的图案必须能完整地放进同样大小的 Universe
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use wasm_game_of_life::{parse_macrocell, Universe};
+
+fuzz_target!(|data: &str| {
+    let pattern = match parse_macrocell(data) {
+        Ok(pattern) => pattern,
+        Err(_) => return,
+    };
+    let mut universe = match Universe::new_with_size(pattern.width, pattern.height) {
+        Ok(universe) => universe,
+        // 空图案没有对应的 Universe
+        Err(_) => return,
+    };
+    universe.stamp_pattern(&pattern, 0, 0).unwrap();
+    assert_eq!(universe.population() as usize, pattern.cells.len());
+});
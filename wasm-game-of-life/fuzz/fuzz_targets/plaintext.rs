@@ -0,0 +1,16 @@
+//! `FromStr` 的纯文本格式：不能 panic；能解析的内容经 `Display` 输出后再解析必须相同
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use wasm_game_of_life::Universe;
+
+fuzz_target!(|data: &str| {
+    let universe: Universe = match data.parse() {
+        Ok(universe) => universe,
+        Err(_) => return,
+    };
+    let reparsed: Universe = universe.to_string().parse().unwrap();
+    assert_eq!(reparsed.width(), universe.width());
+    assert_eq!(reparsed.height(), universe.height());
+    assert_eq!(reparsed.get_cells(), universe.get_cells());
+});
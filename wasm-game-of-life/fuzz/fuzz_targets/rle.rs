@@ -0,0 +1,21 @@
+//! RLE：不能 panic；能解析的图案导出后再解析必须得到同样的图案
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use wasm_game_of_life::{parse_rle, Universe};
+
+fuzz_target!(|data: &str| {
+    let pattern = match parse_rle(data) {
+        Ok(pattern) => pattern,
+        Err(_) => return,
+    };
+    let mut universe = match Universe::new_with_size(pattern.width, pattern.height) {
+        Ok(universe) => universe,
+        // 空图案没有对应的 Universe
+        Err(_) => return,
+    };
+    universe.set_cells(&pattern.cells);
+
+    let rle = universe.to_rle();
+    assert_eq!(parse_rle(&rle).unwrap(), pattern);
+});
@@ -0,0 +1,17 @@
+//! `from_compressed_snapshot`：不能 panic；恢复成功后重新压缩必须得到同样的状态
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use wasm_game_of_life::Universe;
+
+fuzz_target!(|data: &[u8]| {
+    let universe = match Universe::from_compressed_snapshot(data) {
+        Ok(universe) => universe,
+        Err(_) => return,
+    };
+    let reparsed = Universe::from_compressed_snapshot(&universe.compress_snapshot()).unwrap();
+    assert_eq!(reparsed.width(), universe.width());
+    assert_eq!(reparsed.height(), universe.height());
+    assert_eq!(reparsed.generation(), universe.generation());
+    assert_eq!(reparsed.get_cells(), universe.get_cells());
+});
@@ -0,0 +1,487 @@
+use std::collections::HashMap;
+use std::hash::Hasher;
+
+use siphasher::sip128::{Hasher128, SipHasher13};
+
+#[cfg(feature = "web")]
+use wasm_bindgen::prelude::*;
+
+use crate::{Boundary, Cell, Universe, UniverseError};
+
+/// 二元熵：H(p) = -p log2(p) - (1-p) log2(1-p)，约定 0 log2(0) = 0
+pub(crate) fn binary_entropy(p: f64) -> f64 {
+    if p <= 0.0 || p >= 1.0 {
+        return 0.0;
+    }
+    -p * p.log2() - (1.0 - p) * (1.0 - p).log2()
+}
+
+/// 飞船移动的对角方向，North 表示行号减小，West 表示列号减小
+#[cfg_attr(feature = "web", wasm_bindgen)]
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Direction {
+    NorthEast = 0,
+    NorthWest = 1,
+    SouthEast = 2,
+    SouthWest = 3,
+}
+
+/// 存活 Cell 相对于包围盒左上角的坐标
+pub(crate) type Shape = Vec<(u32, u32)>;
+
+/// 检测飞船时最多模拟的代数，滑翔机的周期为 4
+const SPACESHIP_MAX_PERIOD: u32 = 4;
+
+/// 指纹由两个不同密钥的 128 位 SipHash 拼接而成，密钥固定，保证指纹在不同版本间可以比较
+const FINGERPRINT_KEYS: [(u64, u64); 2] = [(0, 0), (0x6c69_6665, 0x6761_6d65)];
+
+/// 包围盒在一个维度上的起点，`occupied[i]` 表示第 i 行（列）是否有存活 Cell；
+/// 环形时跳过最长的一段循环空白，起点是这段空白之后的第一行（列）
+pub(crate) fn bounding_start(occupied: &[bool], toroidal: bool) -> u32 {
+    let first = occupied.iter().position(|&o| o).unwrap_or(0);
+    if !toroidal {
+        return first as u32;
+    }
+
+    let n = occupied.len();
+    let (mut best_gap, mut best_start) = (0, first);
+    let mut gap = 0;
+    // 从第一个被占用的位置开始绕一圈，这样每段空白都是完整的
+    for step in 1..=n {
+        let i = (first + step) % n;
+        if occupied[i] {
+            if gap > best_gap {
+                best_gap = gap;
+                best_start = i;
+            }
+            gap = 0;
+        } else {
+            gap += 1;
+        }
+    }
+    best_start as u32
+}
+
+/// `explain_cell` 的结果：一个 Cell 为什么会存活或死亡
+#[cfg_attr(feature = "web", wasm_bindgen)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CellExplanation {
+    state: Cell,
+    live_neighbors: u32,
+    next_state: Cell,
+    wall: bool,
+}
+
+#[cfg_attr(feature = "web", wasm_bindgen)]
+impl CellExplanation {
+    /// 当前状态
+    pub fn state(&self) -> Cell {
+        self.state
+    }
+
+    /// 存活的邻居数量
+    pub fn live_neighbors(&self) -> u32 {
+        self.live_neighbors
+    }
+
+    /// 下一次 tick 之后的状态
+    pub fn next_state(&self) -> Cell {
+        self.next_state
+    }
+
+    /// 是否为墙（墙的状态不会改变）
+    pub fn is_wall(&self) -> bool {
+        self.wall
+    }
+}
+
+#[cfg_attr(feature = "web", wasm_bindgen)]
+impl Universe {
+    /// 解释 (row, column) 在下一代的变化：当前状态、存活的邻居数以及当前规则下的下一个状态
+    pub fn explain_cell(&self, row: u32, column: u32) -> Result<CellExplanation, UniverseError> {
+        self.check_bounds(row, column)?;
+        let index = self.get_index(row, column);
+        Ok(CellExplanation {
+            state: self.cells[index],
+            live_neighbors: self.live_neighbor_count(row, column),
+            next_state: self.next_cell(self.current_rule(), row, column),
+            wall: self.is_wall_index(index),
+        })
+    }
+
+    /// 下一次 tick 会出生的 Cell（当前死亡、下一代存活），展开为 [row0, col0, row1, col1, ...]，
+    /// 按行优先排序。与 tick 使用同样的规则（包括规则表、规则序列和墙），不会修改状态
+    pub fn compute_birth_locations_if_tick(&self) -> Vec<u32> {
+        let rule = self.current_rule();
+        let mut births = vec![];
+        for row in 0..self.height {
+            for col in 0..self.width {
+                if self.cells[self.get_index(row, col)] == Cell::Dead
+                    && self.next_cell(rule, row, col) == Cell::Alive
+                {
+                    births.push(row);
+                    births.push(col);
+                }
+            }
+        }
+        births
+    }
+
+    /// 下一次 tick 会死亡的 Cell（当前存活、下一代死亡），格式与 `compute_birth_locations_if_tick` 相同；
+    /// 两者合起来就是下一代的完整变化，不会修改状态
+    pub fn compute_death_locations_if_tick(&self) -> Vec<u32> {
+        let rule = self.current_rule();
+        let mut deaths = vec![];
+        for row in 0..self.height {
+            for col in 0..self.width {
+                if self.cells[self.get_index(row, col)] == Cell::Alive
+                    && self.next_cell(rule, row, col) == Cell::Dead
+                {
+                    deaths.push(row);
+                    deaths.push(col);
+                }
+            }
+        }
+        deaths
+    }
+
+    /// 每一行存活比例的二元熵，长度为 height
+    /// 接近 1 表示该行很“嘈杂”，接近 0 表示该行几乎全死或全活
+    pub fn compute_entropy_per_row(&self) -> Vec<f64> {
+        (0..self.height)
+            .map(|row| {
+                let alive = (0..self.width)
+                    .filter(|&column| self.cells[self.get_index(row, column)] == Cell::Alive)
+                    .count();
+                binary_entropy(alive as f64 / self.width as f64)
+            })
+            .collect()
+    }
+
+    /// 当前状态与 `lag` 代之前状态之间的互信息 I(X_t; X_{t-lag}) = H(X_t) + H(X_{t-lag}) - H(X_t, X_{t-lag})
+    /// 需要先通过 `set_snapshot_capacity` 保留至少 `lag` 个快照并 tick 至少 `lag` 次；
+    /// `lag` 为 0 或者还没有 `lag` 代之前的快照时返回错误
+    pub fn compute_mutual_information(&self, lag: u32) -> Result<f64, UniverseError> {
+        if lag == 0 {
+            return Err(UniverseError::InvalidArgument(
+                "mutual information needs a lag of at least 1".to_string(),
+            ));
+        }
+        let past = self.snapshots.get(lag as usize - 1).ok_or_else(|| {
+            UniverseError::InvalidArgument(format!(
+                "no snapshot from {} ticks ago: {} kept, capacity {}",
+                lag,
+                self.snapshots.len(),
+                self.snapshot_capacity
+            ))
+        })?;
+
+        // joint[now][past] 统计四种组合出现的次数
+        let mut joint = [[0usize; 2]; 2];
+        for (&now, &then) in self.cells.iter().zip(past.iter()) {
+            joint[now as usize][then as usize] += 1;
+        }
+
+        let total = self.cells.len() as f64;
+        let p_now = (joint[1][0] + joint[1][1]) as f64 / total;
+        let p_past = (joint[0][1] + joint[1][1]) as f64 / total;
+        let joint_entropy: f64 = joint
+            .iter()
+            .flatten()
+            .map(|&count| count as f64 / total)
+            .filter(|&p| p > 0.0)
+            .map(|p| -p * p.log2())
+            .sum();
+
+        Ok(binary_entropy(p_now) + binary_entropy(p_past) - joint_entropy)
+    }
+
+    /// 在副本上模拟最多 4 代，比较存活 Cell 的形状与包围盒位置，推断滑翔机移动的对角方向；
+    /// 没有存活 Cell、形状没有复原、没有沿对角线移动，或者包围盒跨越了环面边界时返回 None
+    pub fn spaceship_direction(&self) -> Option<Direction> {
+        let (origin, shape) = self.live_shape()?;
+        let mut universe = self.detached_clone();
+        for _ in 0..SPACESHIP_MAX_PERIOD {
+            universe.tick();
+            let (next_origin, next_shape) = universe.live_shape()?;
+            if next_shape != shape {
+                continue;
+            }
+            let d_row = next_origin.0 as i64 - origin.0 as i64;
+            let d_col = next_origin.1 as i64 - origin.1 as i64;
+            if d_row == 0 || d_row.abs() != d_col.abs() {
+                return None;
+            }
+            return Some(match (d_row < 0, d_col > 0) {
+                (true, true) => Direction::NorthEast,
+                (true, false) => Direction::NorthWest,
+                (false, true) => Direction::SouthEast,
+                (false, false) => Direction::SouthWest,
+            });
+        }
+        None
+    }
+
+    /// 图案指纹（32 字节）：先把存活 Cell 平移到左上角并裁剪到包围盒，再计算哈希，
+    /// 因此同一个图案放在不同位置得到相同的指纹，可以用来在图案库中查找。
+    /// 跨越环面边界的图案会得到不同的指纹，可以先调用 `normalize_position`
+    pub fn encode_pattern_fingerprint(&self) -> Vec<u8> {
+        let shape = self
+            .live_shape()
+            .map(|(_, shape)| shape)
+            .unwrap_or_default();
+        let mut fingerprint = Vec::with_capacity(32);
+        for &(k0, k1) in FINGERPRINT_KEYS.iter() {
+            let mut hasher = SipHasher13::new_with_keys(k0, k1);
+            hasher.write_usize(shape.len());
+            for &(row, col) in shape.iter() {
+                hasher.write_u32(row);
+                hasher.write_u32(col);
+            }
+            fingerprint.extend_from_slice(&hasher.finish128().as_bytes());
+        }
+        fingerprint
+    }
+
+    /// 把所有存活 Cell 平移到左上角：包围盒的左上角移动到 (0, 0)，得到与位置无关的标准形式。
+    /// 环形宇宙中包围盒可以跨越边界（取最紧的那一个），因此绕过边界的飞船也能正确归位；
+    /// 包围盒总是不大于网格本身，所以不需要改变尺寸
+    pub fn normalize_position(&mut self) {
+        let live: Vec<(u32, u32)> = (0..self.height)
+            .flat_map(|row| (0..self.width).map(move |col| (row, col)))
+            .filter(|&(row, col)| self.cells[self.get_index(row, col)] == Cell::Alive)
+            .collect();
+        if live.is_empty() {
+            return;
+        }
+
+        let toroidal = self.boundary == Boundary::Toroidal;
+        let mut rows = vec![false; self.height as usize];
+        let mut cols = vec![false; self.width as usize];
+        for &(row, col) in live.iter() {
+            rows[row as usize] = true;
+            cols[col as usize] = true;
+        }
+        let row0 = bounding_start(&rows, toroidal);
+        let col0 = bounding_start(&cols, toroidal);
+
+        let shifted: Vec<(u32, u32)> = live
+            .iter()
+            .map(|&(row, col)| {
+                (
+                    (row + self.height - row0) % self.height,
+                    (col + self.width - col0) % self.width,
+                )
+            })
+            .collect();
+        for cell in self.cells.iter_mut() {
+            *cell = Cell::Dead;
+        }
+        for (row, col) in shifted {
+            let index = self.get_index(row, col);
+            self.cells[index] = Cell::Alive;
+        }
+        self.commit_edit();
+    }
+
+    /// 当前出现过的不同 3x3 邻域（共 2^9 = 512 种）所占的比例，范围 [0, 1]：
+    /// 随机的 Universe 几乎覆盖全部 512 种，静物只有很少几种，
+    /// 随时间记录可以观察到演化收敛到低复杂度状态的过程。
+    /// 有限网格中网格外的 Cell 按照 Boundary 视为死亡或存活
+    pub fn compute_neighborhood_variety(&self) -> f64 {
+        let outside = (self.boundary == Boundary::Alive) as usize;
+        let mut seen = [false; 512];
+        for row in 0..self.height {
+            for col in 0..self.width {
+                let mut pattern = 0;
+                for dr in -1..=1 {
+                    for dc in -1..=1 {
+                        let bit = match self.offset_index(row, col, dr, dc) {
+                            Some(index) => self.cells[index] as usize,
+                            None => outside,
+                        };
+                        pattern = pattern << 1 | bit;
+                    }
+                }
+                seen[pattern] = true;
+            }
+        }
+        seen.iter().filter(|&&s| s).count() as f64 / 512.0
+    }
+
+    /// 整个 Universe 的周期，最多模拟 `max_period` 代，不会修改当前状态
+    pub fn compute_period(&self, max_period: u32) -> Option<u32> {
+        self.compute_period_in_region(0, 0, self.width, self.height, max_period)
+            .unwrap_or(None)
+    }
+
+    /// 只观察以 (row, col) 为左上角、宽 w 高 h 的区域，在一个副本上模拟最多 `max_period` 代，
+    /// 返回区域内状态进入循环时的周期；区域之外的变化不影响结果
+    pub fn compute_period_in_region(
+        &self,
+        row: u32,
+        col: u32,
+        w: u32,
+        h: u32,
+        max_period: u32,
+    ) -> Result<Option<u32>, UniverseError> {
+        if w == 0 || h == 0 {
+            return Err(UniverseError::InvalidArgument(format!(
+                "region size {}x{} must be nonzero",
+                w, h
+            )));
+        }
+        if row as u64 + h as u64 > self.height as u64 || col as u64 + w as u64 > self.width as u64 {
+            return Err(UniverseError::OutOfBounds {
+                row: row.saturating_add(h - 1),
+                col: col.saturating_add(w - 1),
+                width: self.width,
+                height: self.height,
+            });
+        }
+
+        let mut universe = self.detached_clone();
+        let mut seen: HashMap<Vec<Cell>, u32> = HashMap::new();
+        for step in 0..=max_period {
+            let region = universe.region_cells(row, col, w, h);
+            if let Some(first) = seen.insert(region, step) {
+                return Ok(Some(step - first));
+            }
+            universe.tick();
+        }
+
+        Ok(None)
+    }
+}
+
+impl Universe {
+    /// 静物候选：在副本上 tick 两次，自身和八个邻居在这三代中都没有变化的存活 Cell
+    /// 按 8 连通分组，返回每组的包围盒 `(row, column, height, width)`，按左上角排序。
+    /// 振荡器和飞船附近的 Cell 都会发生变化，因此不会被包括在内
+    pub fn detect_still_lifes(&self) -> Vec<(u32, u32, u32, u32)> {
+        let mut universe = self.detached_clone();
+        let mut generations = vec![universe.cells.clone()];
+        for _ in 0..2 {
+            universe.tick();
+            generations.push(universe.cells.clone());
+        }
+        let unchanged = |index: usize| generations.iter().all(|g| g[index] == self.cells[index]);
+
+        let stable = |row: u32, col: u32| {
+            let index = self.get_index(row, col);
+            if self.cells[index] != Cell::Alive || !unchanged(index) {
+                return false;
+            }
+            (-1..=1).all(|dr| {
+                (-1..=1).all(|dc| self.offset_index(row, col, dr, dc).is_none_or(&unchanged))
+            })
+        };
+        self.components(1, stable)
+            .iter()
+            .map(|component| bounding_box(component))
+            .collect()
+    }
+
+    /// 所有存活 Cell 的平均 (row, column)，即质心；没有存活 Cell 时返回 None。
+    /// 自动跟随的镜头可以用它来平移视口
+    pub fn activity_centroid(&self) -> Option<(f64, f64)> {
+        let (mut count, mut rows, mut cols) = (0u64, 0u64, 0u64);
+        for row in 0..self.height {
+            for col in 0..self.width {
+                if self.cells[self.get_index(row, col)] == Cell::Alive {
+                    count += 1;
+                    rows += row as u64;
+                    cols += col as u64;
+                }
+            }
+        }
+        if count == 0 {
+            return None;
+        }
+        Some((rows as f64 / count as f64, cols as f64 / count as f64))
+    }
+
+    /// 存活 Cell 包围盒的左上角，以及各存活 Cell 相对于它的坐标（按行优先排序）
+    pub(crate) fn live_shape(&self) -> Option<((u32, u32), Shape)> {
+        let live: Vec<(u32, u32)> = (0..self.height)
+            .flat_map(|row| (0..self.width).map(move |col| (row, col)))
+            .filter(|&(row, col)| self.cells[self.get_index(row, col)] == Cell::Alive)
+            .collect();
+        let min_row = live.iter().map(|&(row, _)| row).min()?;
+        let min_col = live.iter().map(|&(_, col)| col).min()?;
+        let shape = live
+            .into_iter()
+            .map(|(row, col)| (row - min_row, col - min_col))
+            .collect();
+        Some(((min_row, min_col), shape))
+    }
+
+    /// 满足 `include` 的 Cell 分组（不跨越环形边界）：切比雪夫距离不超过 `radius` 的 Cell 属于同一组，
+    /// radius 为 1 时就是 8 连通。各组按第一个 Cell 的行优先顺序排列，组内也按行优先排序
+    pub(crate) fn components(
+        &self,
+        radius: usize,
+        include: impl Fn(u32, u32) -> bool,
+    ) -> Vec<Vec<(u32, u32)>> {
+        let (width, height) = (self.width as usize, self.height as usize);
+        let mut included = vec![false; width * height];
+        for row in 0..self.height {
+            for col in 0..self.width {
+                included[row as usize * width + col as usize] = include(row, col);
+            }
+        }
+
+        let mut components = vec![];
+        let mut seen = vec![false; width * height];
+        for start in 0..width * height {
+            if !included[start] || seen[start] {
+                continue;
+            }
+            seen[start] = true;
+            let mut stack = vec![start];
+            let mut component = vec![];
+            while let Some(i) = stack.pop() {
+                let (row, col) = (i / width, i % width);
+                component.push((row as u32, col as u32));
+                for r in row.saturating_sub(radius)..(row + radius + 1).min(height) {
+                    for c in col.saturating_sub(radius)..(col + radius + 1).min(width) {
+                        let j = r * width + c;
+                        if included[j] && !seen[j] {
+                            seen[j] = true;
+                            stack.push(j);
+                        }
+                    }
+                }
+            }
+            component.sort_unstable();
+            components.push(component);
+        }
+        components
+    }
+
+    /// 按行取出一个矩形区域内的 Cell，调用方负责保证区域在范围内
+    fn region_cells(&self, row: u32, col: u32, w: u32, h: u32) -> Vec<Cell> {
+        let mut cells = Vec::with_capacity((w * h) as usize);
+        for r in row..row + h {
+            for c in col..col + w {
+                cells.push(self.cells[self.get_index(r, c)]);
+            }
+        }
+        cells
+    }
+}
+
+/// 一组 Cell 的包围盒 `(row, column, height, width)`，cells 不能为空
+pub(crate) fn bounding_box(cells: &[(u32, u32)]) -> (u32, u32, u32, u32) {
+    let min_row = cells.iter().map(|&(row, _)| row).min().unwrap();
+    let max_row = cells.iter().map(|&(row, _)| row).max().unwrap();
+    let min_col = cells.iter().map(|&(_, col)| col).min().unwrap();
+    let max_col = cells.iter().map(|&(_, col)| col).max().unwrap();
+    (
+        min_row,
+        min_col,
+        max_row - min_row + 1,
+        max_col - min_col + 1,
+    )
+}
@@ -0,0 +1,155 @@
+#[cfg(feature = "web")]
+use wasm_bindgen::prelude::*;
+
+use rand::prelude::*;
+
+use crate::{rle, Boundary, Rule, Universe, UniverseError};
+
+#[derive(Clone, Debug)]
+enum Stamp {
+    Named(String, u32, u32),
+    Rle(String),
+}
+
+/// 链式构造 Universe，所有参数在 `build` 时统一校验：
+/// ```
+/// # use wasm_game_of_life::UniverseBuilder;
+/// let universe = UniverseBuilder::new()
+///     .width(16)
+///     .height(16)
+///     .rule("B36/S23")
+///     .pattern("glider", 1, 1)
+///     .build()
+///     .unwrap();
+/// assert_eq!(universe.rule(), "B36/S23");
+/// ```
+/// 不设置任何参数时与 `Universe::new()` 相同：64x64，随机一半的 Cell 存活
+#[cfg_attr(feature = "web", wasm_bindgen)]
+#[derive(Clone, Debug)]
+pub struct UniverseBuilder {
+    width: u32,
+    height: u32,
+    rule: Option<String>,
+    boundary: Boundary,
+    seed: Option<u64>,
+    density: Option<f64>,
+    stamps: Vec<Stamp>,
+}
+
+impl Default for UniverseBuilder {
+    fn default() -> UniverseBuilder {
+        UniverseBuilder {
+            width: 64,
+            height: 64,
+            rule: None,
+            boundary: Boundary::Toroidal,
+            seed: None,
+            density: None,
+            stamps: vec![],
+        }
+    }
+}
+
+#[cfg_attr(feature = "web", wasm_bindgen)]
+impl UniverseBuilder {
+    #[cfg_attr(feature = "web", wasm_bindgen(constructor))]
+    pub fn new() -> UniverseBuilder {
+        UniverseBuilder::default()
+    }
+
+    pub fn width(mut self, width: u32) -> UniverseBuilder {
+        self.width = width;
+        self
+    }
+
+    pub fn height(mut self, height: u32) -> UniverseBuilder {
+        self.height = height;
+        self
+    }
+
+    /// B/S 记法的规则，例如 `B3/S23`
+    pub fn rule(mut self, rule: &str) -> UniverseBuilder {
+        self.rule = Some(rule.to_string());
+        self
+    }
+
+    pub fn boundary(mut self, boundary: Boundary) -> UniverseBuilder {
+        self.boundary = boundary;
+        self
+    }
+
+    /// Universe 随机数生成器的种子，相同的种子得到相同的初始状态，之后的随机操作也可以复现
+    pub fn seed(mut self, seed: u64) -> UniverseBuilder {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// 随机填充时每个 Cell 存活的概率，范围 [0, 1]，默认 0.5
+    pub fn density(mut self, density: f64) -> UniverseBuilder {
+        self.density = Some(density);
+        self
+    }
+
+    /// 以 (row, column) 为左上角放置一个内置图案，例如 `glider`
+    pub fn pattern(mut self, name: &str, row: u32, column: u32) -> UniverseBuilder {
+        self.stamps
+            .push(Stamp::Named(name.to_string(), row, column));
+        self
+    }
+
+    /// 以左上角为原点放置一个 RLE 格式的图案
+    pub fn cells_from_rle(mut self, rle: &str) -> UniverseBuilder {
+        self.stamps.push(Stamp::Rle(rle.to_string()));
+        self
+    }
+
+    /// 校验所有参数并创建 Universe；
+    /// 放置了图案时其余 Cell 均为死亡，不能与 `density` 同时使用
+    pub fn build(self) -> Result<Universe, UniverseError> {
+        Universe::validate_dimensions(self.width, self.height)?;
+        let rule: Rule = match self.rule {
+            Some(ref rule) => rule.parse()?,
+            None => Rule::default(),
+        };
+        if let Some(density) = self.density {
+            if !(0.0..=1.0).contains(&density) {
+                return Err(UniverseError::InvalidArgument(format!(
+                    "density {} is out of range [0, 1]",
+                    density
+                )));
+            }
+            if !self.stamps.is_empty() {
+                return Err(UniverseError::InvalidArgument(
+                    "density cannot be combined with explicit cells".to_string(),
+                ));
+            }
+        }
+
+        let mut universe = Universe::with_size(self.width, self.height);
+        if let Some(seed) = self.seed {
+            universe.set_seed(seed);
+        }
+        universe.rule = rule;
+        universe.boundary = self.boundary;
+
+        if !self.stamps.is_empty() {
+            for stamp in self.stamps.iter() {
+                match stamp {
+                    Stamp::Named(name, row, column) => {
+                        universe.load_pattern(name, *row, *column)?
+                    }
+                    Stamp::Rle(rle) => universe.stamp_pattern(&rle::parse_rle(rle)?, 0, 0)?,
+                }
+            }
+        } else if self.seed.is_some() || self.density.is_some() {
+            let density = self.density.unwrap_or(0.5);
+            let probs = vec![density as f32; universe.cells.len()];
+            let seed = universe.rng.gen();
+            universe.fill_from_mask(&probs, seed)?;
+        } else {
+            universe.start();
+        }
+
+        Ok(universe)
+    }
+}
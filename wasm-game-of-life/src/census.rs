@@ -0,0 +1,189 @@
+use std::collections::{BTreeMap, HashMap};
+
+use crate::analysis::{bounding_box, bounding_start, Shape};
+use crate::rule::totalistic_rule;
+use crate::{parse_rle, Boundary, Cell, Rule, Universe};
+
+/// `census` 的结果：各已知物体的数量（只包含出现过的），以及无法识别的簇的包围盒
+/// `(row, column, height, width)`，按左上角排序
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Census {
+    pub counts: BTreeMap<String, u32>,
+    pub unidentified: Vec<(u32, u32, u32, u32)>,
+}
+
+/// 与其它存活 Cell 的切比雪夫距离不超过 2 的 Cell 属于同一个簇：
+/// 距离为 2 的 Cell 有共同的邻居，下一代就会互相影响
+pub(crate) const CLUSTER_RADIUS: usize = 2;
+
+/// 求标准形式时模拟的代数，覆盖周期不超过 16 的物体的所有相位
+const CENSUS_PHASES: u32 = 16;
+
+/// B3/S23 中最常见的物体（参考 Catagolue 的统计），任意相位、旋转和镜像都能识别
+const KNOWN_OBJECTS: [(&str, &str); 20] = [
+    ("block", "2o$2o!"),
+    ("blinker", "3o!"),
+    ("beehive", "b2o$o2bo$b2o!"),
+    ("glider", "bo$2bo$3o!"),
+    ("loaf", "b2o$o2bo$bobo$2bo!"),
+    ("boat", "2o$obo$bo!"),
+    ("tub", "bo$obo$bo!"),
+    ("pond", "b2o$o2bo$o2bo$b2o!"),
+    ("ship", "2o$obo$b2o!"),
+    ("long_boat", "2o$obo$bobo$2bo!"),
+    ("toad", "b3o$3o!"),
+    ("beacon", "2o$2o$2b2o$2b2o!"),
+    ("barge", "bo$obo$bobo$2bo!"),
+    ("mango", "b2o$o2bo$bo2bo$2b2o!"),
+    ("long_barge", "bo$obo$bobo$2bobo$3bo!"),
+    ("eater_1", "2o$obo$2bo$2b2o!"),
+    ("aircraft_carrier", "2o$o2bo$2b2o!"),
+    ("snake", "2obo$ob2o!"),
+    ("lwss", "bo2bo$o$o3bo$4o!"),
+    (
+        "pulsar",
+        "2b3o3b3o2$o4bobo4bo$o4bobo4bo$o4bobo4bo$2b3o3b3o2$2b3o3b3o$o4bobo4bo$\
+         o4bobo4bo$o4bobo4bo2$2b3o3b3o!",
+    ),
+];
+
+thread_local! {
+    /// `KNOWN_OBJECTS` 的标准形式，只在第一次 `census` 时计算
+    static KNOWN: HashMap<Shape, &'static str> = KNOWN_OBJECTS
+        .iter()
+        .map(|&(name, rle)| {
+            let pattern = parse_rle(rle).unwrap();
+            (canonical_form(&pattern.cells), name)
+        })
+        .collect();
+}
+
+impl Universe {
+    /// 统计稳定之后的物体，类似 Catagolue 的结果：把存活 Cell 分成簇，
+    /// 在隔离的副本中模拟每个簇，取所有相位、旋转和镜像中最小的形状作为标准形式，
+    /// 再与内置的常见物体比较。物体的名称和形状都按 B3/S23 定义，当前规则（包括规则序列和
+    /// `set_rule_table` 的查找表）不是 B3/S23 时所有簇都归入 unidentified。
+    ///
+    /// 相距不超过 2 格的物体会被当成一个簇，通常归入 unidentified。
+    /// 环形宇宙中先把最长的空白行（列）移到接缝处，因此跨越接缝的物体也能识别；
+    /// 没有空白行（列）时接缝会把物体切开，被切开的部分按各自的形状统计
+    pub fn census(&self) -> Census {
+        let (row0, col0) = self.seam_origin();
+        let unshift = |row: u32, col: u32| ((row + row0) % self.height, (col + col0) % self.width);
+
+        let named = self.is_conway();
+        let mut census = Census::default();
+        let clusters = self.components(CLUSTER_RADIUS, |row, col| {
+            let (row, col) = unshift(row, col);
+            self.cells[self.get_index(row, col)] == Cell::Alive
+        });
+        for cluster in clusters {
+            let (top, left, height, width) = bounding_box(&cluster);
+            let shape: Shape = cluster
+                .iter()
+                .map(|&(row, col)| (row - top, col - left))
+                .collect();
+            let name = if named {
+                let canonical = canonical_form(&shape);
+                KNOWN.with(|known| known.get(&canonical).copied())
+            } else {
+                None
+            };
+            match name {
+                Some(name) => *census.counts.entry(name.to_string()).or_insert(0) += 1,
+                None => {
+                    let (row, col) = unshift(top, left);
+                    census.unidentified.push((row, col, height, width));
+                }
+            }
+        }
+        census.unidentified.sort_unstable();
+        census
+    }
+
+    /// 每一代都按 B3/S23 演化：规则序列中的每条规则都是 B3/S23，或者查找表与 B3/S23 等价
+    fn is_conway(&self) -> bool {
+        if !self.rule_table.is_empty() {
+            return totalistic_rule(&self.rule_table) == Some(Rule::CONWAY);
+        }
+        if self.rule_schedule.is_empty() {
+            return self.rule == Rule::CONWAY;
+        }
+        self.rule_schedule.iter().all(|&rule| rule == Rule::CONWAY)
+    }
+
+    /// 分簇时使用的原点：环形宇宙中是最长的空白行（列）之后的第一行（列），
+    /// 以它为 (0, 0) 时跨越接缝的物体不会被切开；有限网格中是 (0, 0)
+    pub(crate) fn seam_origin(&self) -> (u32, u32) {
+        if self.boundary != Boundary::Toroidal {
+            return (0, 0);
+        }
+        let mut rows = vec![false; self.height as usize];
+        let mut cols = vec![false; self.width as usize];
+        for row in 0..self.height {
+            for col in 0..self.width {
+                if self.cells[self.get_index(row, col)] == Cell::Alive {
+                    rows[row as usize] = true;
+                    cols[col as usize] = true;
+                }
+            }
+        }
+        (bounding_start(&rows, true), bounding_start(&cols, true))
+    }
+}
+
+/// 在足够大的空白网格中按 B3/S23 模拟 `CENSUS_PHASES` 代，返回所有相位在 8 种朝向下最小的形状；
+/// 物体在任何相位、位置和朝向下都得到同样的结果
+fn canonical_form(shape: &[(u32, u32)]) -> Shape {
+    let height = shape.iter().map(|&(row, _)| row).max().unwrap_or(0) + 1;
+    let width = shape.iter().map(|&(_, col)| col).max().unwrap_or(0) + 1;
+    // 飞船最快每 2 代移动一格，留出足够的空白避免碰到边界
+    let pad = CENSUS_PHASES;
+    let mut universe = Universe::new_with_size(width + 2 * pad, height + 2 * pad).unwrap();
+    universe.set_boundary(Boundary::Dead);
+    let cells: Vec<(u32, u32)> = shape
+        .iter()
+        .map(|&(row, col)| (row + pad, col + pad))
+        .collect();
+    universe.set_cells(&cells);
+
+    let mut best: Option<Shape> = None;
+    for _ in 0..CENSUS_PHASES {
+        let (_, phase) = match universe.live_shape() {
+            Some(phase) => phase,
+            None => break,
+        };
+        for orientation in orientations(&phase) {
+            if best.as_ref().is_none_or(|b| orientation < *b) {
+                best = Some(orientation);
+            }
+        }
+        universe.tick();
+    }
+    best.unwrap_or_default()
+}
+
+/// 形状的 8 种旋转和镜像，每种都平移到左上角并按行优先排序：
+/// 依次由是否翻转行、是否翻转列、是否交换行列组合而成
+pub(crate) fn orientations(shape: &[(u32, u32)]) -> Vec<Shape> {
+    let height = shape.iter().map(|&(row, _)| row).max().unwrap_or(0);
+    let width = shape.iter().map(|&(_, col)| col).max().unwrap_or(0);
+    (0..8)
+        .map(|flags| {
+            let mut oriented: Shape = shape
+                .iter()
+                .map(|&(row, col)| {
+                    let row = if flags & 1 != 0 { height - row } else { row };
+                    let col = if flags & 2 != 0 { width - col } else { col };
+                    if flags & 4 != 0 {
+                        (col, row)
+                    } else {
+                        (row, col)
+                    }
+                })
+                .collect();
+            oriented.sort_unstable();
+            oriented
+        })
+        .collect()
+}
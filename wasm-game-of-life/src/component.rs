@@ -0,0 +1,181 @@
+use std::collections::HashSet;
+
+#[cfg(feature = "web")]
+use wasm_bindgen::prelude::*;
+
+use crate::{Cell, Universe, UniverseError};
+
+/// 一个 8 连通的存活 Cell 簇：`id` 是簇中行优先顺序第一个 Cell 的编号 `row * width + column`，
+/// 只要这个簇不变 id 就不变；包围盒为 `(row, column, height, width)`，环形宇宙中可以跨越接缝（此时 row + height 或
+/// column + width 超过网格，超出的部分绕回另一侧）；`centroid` 是各 Cell 的平均 (row, column)，
+/// 同样按跨越接缝之后的连续坐标计算，再绕回网格之内
+#[derive(Clone, Debug, PartialEq)]
+pub struct Component {
+    pub id: u32,
+    pub cell_count: u32,
+    pub row: u32,
+    pub column: u32,
+    pub height: u32,
+    pub width: u32,
+    pub centroid: (f64, f64),
+}
+
+#[cfg_attr(feature = "web", wasm_bindgen)]
+impl Universe {
+    /// (row, column) 所在簇的 id，该 Cell 死亡时返回 None。
+    /// 只遍历这一个簇，id 可以随后传给 `erase_component`
+    pub fn component_at(&self, row: u32, column: u32) -> Result<Option<u32>, UniverseError> {
+        self.check_bounds(row, column)?;
+        if self.cells[self.get_index(row, column)] != Cell::Alive {
+            return Ok(None);
+        }
+        Ok(Some(self.component_id(&self.cluster_cells(row, column))))
+    }
+
+    /// 杀死簇 `id` 中的所有 Cell，id 来自 `component_at` 或 `live_components`，
+    /// 簇在此期间发生变化时返回错误；只遍历这一个簇
+    pub fn erase_component(&mut self, id: u32) -> Result<(), UniverseError> {
+        let missing = || UniverseError::InvalidArgument(format!("component {} does not exist", id));
+        let (row, column) = (id / self.width, id % self.width);
+        if row >= self.height || self.cells[self.get_index(row, column)] != Cell::Alive {
+            return Err(missing());
+        }
+        let cells = self.cluster_cells(row, column);
+        if self.component_id(&cells) != id {
+            return Err(missing());
+        }
+        let edits: Vec<(usize, Cell)> = cells
+            .into_iter()
+            .map(|(index, _, _)| (index, Cell::Dead))
+            .collect();
+        self.commit_cells_edit(&edits);
+        Ok(())
+    }
+}
+
+impl Universe {
+    /// 所有 8 连通的存活 Cell 簇，按 id（即各簇第一个 Cell 的行优先顺序）排列。
+    /// 环形宇宙中跨越接缝的簇被当作一个整体；耗时与存活 Cell 的数量成正比，与网格大小无关
+    pub fn live_components(&self) -> Vec<Component> {
+        let (height, width) = (self.height as i64, self.width as i64);
+        self.component_cells()
+            .iter()
+            .map(|cells| {
+                let (mut min_r, mut max_r, mut min_c, mut max_c) =
+                    (i64::MAX, i64::MIN, i64::MAX, i64::MIN);
+                let (mut sum_r, mut sum_c) = (0i64, 0i64);
+                for &(_, r, c) in cells {
+                    min_r = min_r.min(r);
+                    max_r = max_r.max(r);
+                    min_c = min_c.min(c);
+                    max_c = max_c.max(c);
+                    sum_r += r;
+                    sum_c += c;
+                }
+                let n = cells.len() as f64;
+                Component {
+                    id: self.component_id(cells),
+                    cell_count: cells.len() as u32,
+                    row: min_r.rem_euclid(height) as u32,
+                    column: min_c.rem_euclid(width) as u32,
+                    // 绕了一整圈的簇最多与网格一样大
+                    height: (max_r - min_r + 1).min(height) as u32,
+                    width: (max_c - min_c + 1).min(width) as u32,
+                    centroid: (
+                        (sum_r as f64 / n).rem_euclid(height as f64),
+                        (sum_c as f64 / n).rem_euclid(width as f64),
+                    ),
+                }
+            })
+            .collect()
+    }
+
+    /// 每个簇的 Cell，按簇的 id 排列，见 `cluster_cells`。
+    /// 只从 `live` 中的下标出发做洪水填充，不遍历整个网格
+    fn component_cells(&self) -> Vec<Vec<(usize, i64, i64)>> {
+        let mut seen = HashSet::new();
+        let mut components = vec![];
+        for &index in self.live.iter() {
+            let index = index as usize;
+            if self.cells[index] == Cell::Alive && !seen.contains(&index) {
+                let (row, column) = self.position_of(index);
+                let cells = self.cluster_cells(row, column);
+                seen.extend(cells.iter().map(|&(index, _, _)| index));
+                components.push((self.component_id(&cells), cells));
+            }
+        }
+        components.sort_unstable_by_key(|&(id, _)| id);
+        components.into_iter().map(|(_, cells)| cells).collect()
+    }
+
+    /// 整体改写 self.cells 之后调用：同步 `_cells`，并重新收集 `live`
+    pub(crate) fn sync_cells(&mut self) {
+        self._cells.clone_from(&self.cells);
+        self.collect_live();
+    }
+
+    fn collect_live(&mut self) {
+        self.live.clear();
+        for (index, &cell) in self.cells.iter().enumerate() {
+            if cell == Cell::Alive {
+                self.live.push(index as u32);
+            }
+        }
+    }
+
+    /// 单个 Cell 变为存活之后调用。`live` 中死亡或重复的下标只会被跳过，不必立即删除，
+    /// 累积得比网格还大时再重新收集
+    pub(crate) fn note_alive(&mut self, index: u32) {
+        self.live.push(index);
+        if self.live.len() > self.cells.len() {
+            self.collect_live();
+        }
+    }
+
+    /// 存活 Cell (row, column) 所在簇的 Cell：`(self.cells 的下标, row, column)`，
+    /// 其中 row、column 是从 (row, column) 出发、跨越接缝时不取模的连续坐标
+    fn cluster_cells(&self, row: u32, column: u32) -> Vec<(usize, i64, i64)> {
+        let start = self.get_index(row, column);
+        let mut seen = HashSet::new();
+        seen.insert(start);
+        let mut cells = vec![];
+        let mut stack = vec![(start, row, column, row as i64, column as i64)];
+        while let Some((index, row, column, r, c)) = stack.pop() {
+            cells.push((index, r, c));
+            for dr in -1..=1 {
+                for dc in -1..=1 {
+                    let neighbor = match self.offset_index(row, column, dr, dc) {
+                        Some(neighbor) if neighbor != index => neighbor,
+                        _ => continue,
+                    };
+                    if self.cells[neighbor] == Cell::Alive && seen.insert(neighbor) {
+                        let (nr, nc) = self.position_of(neighbor);
+                        stack.push((neighbor, nr, nc, r + dr, c + dc));
+                    }
+                }
+            }
+        }
+        cells
+    }
+
+    /// 簇的 id：簇中各 Cell 编号 `row * width + column` 的最小值
+    fn component_id(&self, cells: &[(usize, i64, i64)]) -> u32 {
+        cells
+            .iter()
+            .map(|&(index, _, _)| {
+                let (row, column) = self.position_of(index);
+                row * self.width + column
+            })
+            .min()
+            .unwrap()
+    }
+
+    /// `get_index` 的逆运算：self.cells 的下标对应的 (row, column)
+    fn position_of(&self, index: usize) -> (u32, u32) {
+        let (row, column) = (index as u32 / self.width, index as u32 % self.width);
+        (
+            (row + self.height - self.row_offset) % self.height,
+            (column + self.width - self.col_offset) % self.width,
+        )
+    }
+}
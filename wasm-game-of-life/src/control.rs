@@ -0,0 +1,115 @@
+#[cfg(feature = "web")]
+use wasm_bindgen::prelude::*;
+
+use crate::Universe;
+
+/// 模拟循环的运行状态：
+/// - Stopped: 未开始或已停止（默认）
+/// - Running: 每次驱动都前进一代
+/// - Paused: 暂停，保留当前代数
+/// - SingleStepping: 只再前进一代，之后自动变为 Paused
+#[cfg_attr(feature = "web", wasm_bindgen)]
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum RunState {
+    #[default]
+    Stopped = 0,
+    Running = 1,
+    Paused = 2,
+    SingleStepping = 3,
+}
+
+/// 模拟循环的控制器，例如放在 Worker 中由主线程发来的消息驱动：
+/// ```
+/// # use wasm_game_of_life::{SimulationControl, Universe};
+/// let mut universe = Universe::new_with_size(8, 8).unwrap();
+/// let mut control = SimulationControl::new();
+/// control.play();
+/// assert!(control.tick(&mut universe));
+/// control.pause();
+/// assert!(!control.tick(&mut universe));
+/// assert_eq!(universe.generation(), 1);
+/// ```
+/// 每次状态改变时 `control_epoch` 加一，消息可以带上发送时的 epoch，
+/// 与当前值不同时说明它来自之前的会话，可以直接丢弃
+#[cfg_attr(feature = "web", wasm_bindgen)]
+#[derive(Clone, Debug, Default)]
+pub struct SimulationControl {
+    state: RunState,
+    epoch: u32,
+}
+
+#[cfg_attr(feature = "web", wasm_bindgen)]
+impl SimulationControl {
+    #[cfg_attr(feature = "web", wasm_bindgen(constructor))]
+    pub fn new() -> SimulationControl {
+        SimulationControl::default()
+    }
+
+    /// 开始或继续运行
+    pub fn play(&mut self) {
+        self.transition(RunState::Running);
+    }
+
+    /// 暂停；已经停止时没有效果
+    pub fn pause(&mut self) {
+        if self.state != RunState::Stopped {
+            self.transition(RunState::Paused);
+        }
+    }
+
+    /// 停止并把 Universe 的代数归零，Cell 保持不变
+    pub fn stop(&mut self, universe: &mut Universe) {
+        self.transition(RunState::Stopped);
+        universe.generation = 0;
+    }
+
+    /// 在停止或暂停时只前进一代；正在运行时忽略，返回是否被接受
+    pub fn step_once(&mut self) -> bool {
+        if self.state == RunState::Running {
+            return false;
+        }
+        self.transition(RunState::SingleStepping);
+        true
+    }
+
+    pub fn run_state(&self) -> RunState {
+        self.state
+    }
+
+    /// 当前状态的名称：`stopped`、`running`、`paused` 或 `single-stepping`
+    pub fn state(&self) -> String {
+        match self.state {
+            RunState::Stopped => "stopped",
+            RunState::Running => "running",
+            RunState::Paused => "paused",
+            RunState::SingleStepping => "single-stepping",
+        }
+        .to_string()
+    }
+
+    pub fn control_epoch(&self) -> u32 {
+        self.epoch
+    }
+
+    /// 驱动循环每一代调用一次：当前状态允许时让 Universe 前进一代并返回 true。
+    /// 每一代之前都会检查状态，因此 `pause` 会在下一代之前生效
+    pub fn tick(&mut self, universe: &mut Universe) -> bool {
+        match self.state {
+            RunState::Running => {}
+            RunState::SingleStepping => self.transition(RunState::Paused),
+            RunState::Stopped | RunState::Paused => return false,
+        }
+        universe.tick();
+        true
+    }
+}
+
+impl SimulationControl {
+    fn transition(&mut self, state: RunState) {
+        if self.state != state {
+            self.state = state;
+            self.epoch = self.epoch.wrapping_add(1);
+        }
+    }
+}
@@ -0,0 +1,192 @@
+#[cfg(feature = "web")]
+use wasm_bindgen::prelude::*;
+
+use crate::{Cell, Universe, UniverseError};
+
+const BASE64_CHARS: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+pub(crate) fn base64_encode(bytes: &[u8]) -> String {
+    let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let b = [
+            chunk[0],
+            chunk.get(1).cloned().unwrap_or(0),
+            chunk.get(2).cloned().unwrap_or(0),
+        ];
+        let n = (b[0] as u32) << 16 | (b[1] as u32) << 8 | b[2] as u32;
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(BASE64_CHARS[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+pub(crate) fn base64_decode(s: &str) -> Result<Vec<u8>, String> {
+    let s = s.trim_end_matches('=');
+    let mut out = Vec::with_capacity(s.len() * 3 / 4);
+    let mut n = 0u32;
+    let mut bits = 0;
+    for c in s.bytes() {
+        let v = match BASE64_CHARS.iter().position(|&x| x == c) {
+            Some(v) => v as u32,
+            None => return Err(format!("invalid base64 character '{}'", c as char)),
+        };
+        n = n << 6 | v;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            out.push((n >> bits & 0xff) as u8);
+        }
+    }
+    Ok(out)
+}
+
+#[cfg_attr(feature = "web", wasm_bindgen)]
+impl Universe {
+    /// 将当前状态编码为紧凑的字符串：`{width}x{height}:{base64}`
+    /// 每个 Cell 占一个 bit，按 cells 缓冲区的顺序排列
+    pub fn encode_state(&self) -> String {
+        let mut bytes = vec![0u8; self.cells.len().div_ceil(8)];
+        for (i, &cell) in self.cells.iter().enumerate() {
+            if cell == Cell::Alive {
+                bytes[i / 8] |= 1 << (i % 8);
+            }
+        }
+        format!("{}x{}:{}", self.width, self.height, base64_encode(&bytes))
+    }
+
+    /// 从 `encode_state` 生成的字符串恢复 Universe
+    pub fn decode_state(s: &str) -> Result<Universe, UniverseError> {
+        let invalid = |message: String| UniverseError::InvalidEncoding(message);
+        let (size, data) = s
+            .split_once(':')
+            .ok_or_else(|| invalid("missing ':' separator".to_string()))?;
+        let (width, height) = size
+            .split_once('x')
+            .ok_or_else(|| invalid("missing 'x' in dimensions".to_string()))?;
+        let width: u32 = width
+            .parse()
+            .map_err(|_| invalid(format!("invalid width '{}'", width)))?;
+        let height: u32 = height
+            .parse()
+            .map_err(|_| invalid(format!("invalid height '{}'", height)))?;
+        Universe::validate_dimensions(width, height)?;
+        let len = (width * height) as usize;
+
+        let bytes = base64_decode(data).map_err(invalid)?;
+        if bytes.len() != len.div_ceil(8) {
+            return Err(invalid(format!(
+                "expected {} bytes of cell data, got {}",
+                len.div_ceil(8),
+                bytes.len()
+            )));
+        }
+
+        let mut universe = Universe::with_size(width, height);
+        for (i, cell) in universe.cells.iter_mut().enumerate() {
+            if bytes[i / 8] >> (i % 8) & 1 == 1 {
+                *cell = Cell::Alive;
+            }
+        }
+        universe.sync_cells();
+
+        Ok(universe)
+    }
+
+    /// 将当前状态压缩为游程编码的字节串：开头是小端的 width(u32)、height(u32)、generation(u64)，
+    /// 之后按 cells 缓冲区的顺序交替记录死亡、存活 Cell 的连续个数（从死亡开始），
+    /// 每个数使用 LEB128 变长编码；稀疏的图案通常只需要几十个字节
+    pub fn compress_snapshot(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(16);
+        out.extend_from_slice(&self.width.to_le_bytes());
+        out.extend_from_slice(&self.height.to_le_bytes());
+        out.extend_from_slice(&self.generation.to_le_bytes());
+
+        let mut state = Cell::Dead;
+        let mut run = 0u64;
+        for &cell in self.cells.iter() {
+            if cell != state {
+                push_varint(&mut out, run);
+                state = cell;
+                run = 0;
+            }
+            run += 1;
+        }
+        push_varint(&mut out, run);
+        out
+    }
+
+    /// 从 `compress_snapshot` 的结果恢复 Universe（包括代数，不包括规则等设置）
+    pub fn from_compressed_snapshot(data: &[u8]) -> Result<Universe, UniverseError> {
+        let invalid = |message: &str| UniverseError::InvalidEncoding(message.to_string());
+        if data.len() < 16 {
+            return Err(invalid("snapshot header is truncated"));
+        }
+        let width = u32::from_le_bytes([data[0], data[1], data[2], data[3]]);
+        let height = u32::from_le_bytes([data[4], data[5], data[6], data[7]]);
+        let mut generation = [0u8; 8];
+        generation.copy_from_slice(&data[8..16]);
+        Universe::validate_dimensions(width, height)?;
+
+        let mut universe = Universe::with_size(width, height);
+        universe.generation = u64::from_le_bytes(generation);
+        let mut rest = &data[16..];
+        let mut state = Cell::Dead;
+        let mut index = 0usize;
+        while !rest.is_empty() {
+            let run = read_varint(&mut rest).ok_or_else(|| invalid("truncated run length"))?;
+            let end = (index as u64)
+                .checked_add(run)
+                .filter(|&end| end <= universe.cells.len() as u64)
+                .ok_or_else(|| invalid("runs exceed the number of cells"))?;
+            for cell in universe.cells[index..end as usize].iter_mut() {
+                *cell = state;
+            }
+            index = end as usize;
+            state = !state;
+        }
+        if index != universe.cells.len() {
+            return Err(invalid("runs do not cover every cell"));
+        }
+        universe.sync_cells();
+
+        Ok(universe)
+    }
+}
+
+impl Universe {
+    /// 执行 n_ticks 次 tick，返回每一代之后的 `compress_snapshot`，用于回放和拖动进度条
+    pub fn simulate_and_record(&mut self, n_ticks: u32) -> Vec<Vec<u8>> {
+        (0..n_ticks)
+            .map(|_| {
+                self.tick();
+                self.compress_snapshot()
+            })
+            .collect()
+    }
+}
+
+fn push_varint(out: &mut Vec<u8>, mut n: u64) {
+    while n >= 0x80 {
+        out.push((n & 0x7f) as u8 | 0x80);
+        n >>= 7;
+    }
+    out.push(n as u8);
+}
+
+fn read_varint(data: &mut &[u8]) -> Option<u64> {
+    let mut n = 0u64;
+    for shift in (0..64).step_by(7) {
+        let (&byte, rest) = data.split_first()?;
+        *data = rest;
+        n |= ((byte & 0x7f) as u64) << shift;
+        if byte & 0x80 == 0 {
+            return Some(n);
+        }
+    }
+    None
+}
@@ -0,0 +1,117 @@
+use crate::{utils, Universe, UniverseBuilder, UniverseError};
+
+/// tick 的一种实现。目前的两种都由 `step` 驱动，区别在于计算下一个状态的方式，
+/// 邻居的计数和绕回各自独立实现，可以互相校验
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Engine {
+    /// 数出存活邻居的数量，再按 B/S 规则决定下一个状态
+    Counting,
+    /// 由八个邻居组成位掩码，在 `set_rule_table` 的 512 项查找表中查出下一个状态
+    RuleTable,
+}
+
+impl Engine {
+    /// 所有引擎，包括当前构建中因为 feature 没有开启而不可用的
+    pub const ALL: [Engine; 2] = [Engine::Counting, Engine::RuleTable];
+
+    /// 当前构建中是否可用。依赖 feature 的引擎在这里返回 `cfg!(feature = "...")`，
+    /// 不可用的引擎在 `compare_engines` 中被标记为跳过
+    pub fn is_available(self) -> bool {
+        match self {
+            Engine::Counting | Engine::RuleTable => true,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Engine::Counting => "counting",
+            Engine::RuleTable => "rule-table",
+        }
+    }
+
+    /// 让 `universe` 之后的 tick 使用这个引擎，规则保持不变
+    fn select(self, universe: &mut Universe) {
+        match self {
+            Engine::Counting => universe.rule_table.clear(),
+            Engine::RuleTable => universe.rule_table = universe.get_rule_table(),
+        }
+    }
+}
+
+/// `compare_engines` 中一个引擎的结果；`skipped` 为 true 时该引擎在当前构建中不可用，
+/// 没有运行，其余字段都为 0
+#[derive(Clone, Debug, PartialEq)]
+pub struct EngineResult {
+    pub engine: Engine,
+    pub skipped: bool,
+    pub generations: u32,
+    pub elapsed_ms: u64,
+    /// 耗时不足 1 毫秒时按 1 毫秒计算
+    pub generations_per_sec: f64,
+    /// 最终状态的 `state_hash`，所有引擎都相同
+    pub state_hash: u64,
+}
+
+/// 用每个可用的引擎从同一锅随机汤（尺寸和密度按照 `UniverseBuilder` 的规则校验）
+/// 演化 `generations` 代，返回各自的速度，不可用的引擎也有一项，标记为跳过。这同时也是正确性检查：
+/// 任何两个引擎的最终状态不同都说明某个实现有 bug（例如绕回的边界情况），此时直接 panic
+pub fn compare_engines(
+    width: u32,
+    height: u32,
+    density: f64,
+    seed: u64,
+    generations: u32,
+) -> Result<Vec<EngineResult>, UniverseError> {
+    let soup = UniverseBuilder::new()
+        .width(width)
+        .height(height)
+        .density(density)
+        .seed(seed)
+        .build()?;
+
+    let mut reference: Option<(Engine, Universe)> = None;
+    let mut results = vec![];
+    for &engine in Engine::ALL.iter() {
+        if !engine.is_available() {
+            results.push(EngineResult {
+                engine,
+                skipped: true,
+                generations: 0,
+                elapsed_ms: 0,
+                generations_per_sec: 0.0,
+                state_hash: 0,
+            });
+            continue;
+        }
+        let mut universe = soup.clone();
+        engine.select(&mut universe);
+        let started_ms = utils::now_ms();
+        for _ in 0..generations {
+            universe.tick();
+        }
+        let elapsed_ms = utils::now_ms().saturating_sub(started_ms);
+
+        match reference {
+            Some((first, ref expected)) => assert!(
+                universe.get_cells() == expected.get_cells(),
+                "engines {} and {} disagree after {} generations of a {}x{} soup (seed {})",
+                first.name(),
+                engine.name(),
+                generations,
+                width,
+                height,
+                seed
+            ),
+            None => reference = Some((engine, universe.clone())),
+        }
+        results.push(EngineResult {
+            engine,
+            skipped: false,
+            generations,
+            elapsed_ms,
+            generations_per_sec: generations as f64 * 1000.0 / elapsed_ms.max(1) as f64,
+            state_hash: universe.state_hash(),
+        });
+    }
+    Ok(results)
+}
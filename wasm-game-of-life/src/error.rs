@@ -0,0 +1,75 @@
+use std::error::Error;
+use std::fmt;
+
+use crate::Universe;
+
+/// Universe 对外 API 的错误类型
+///
+/// 约定：任何可以由用户输入触发的错误（坐标、尺寸、规则、图案、缓冲区长度等）
+/// 都通过 `Result<_, UniverseError>` 返回，在 JS 中表现为抛出的 `Error`；
+/// 只有内部不变量被破坏时才会 panic。`set_cells` 系列是批量编辑的便捷方法，
+/// 越界坐标会被忽略（verbose 模式下输出警告），而不是返回错误；
+/// 写入精确状态的 `set_cells_state` 例外，越界时返回错误。
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum UniverseError {
+    /// 坐标超出了 Universe 的范围
+    OutOfBounds {
+        row: u32,
+        col: u32,
+        width: u32,
+        height: u32,
+    },
+    /// 宽或高为 0，或者 Cell 总数超过 `Universe::MAX_CELLS`
+    InvalidDimensions { width: u32, height: u32 },
+    /// 图案（RLE 等）解析失败，line 从 1 开始
+    ParseError { line: usize, message: String },
+    /// 规则字符串无法解析
+    InvalidRule { rule: String, message: String },
+    /// 调用方提供的缓冲区长度不正确
+    LengthMismatch { expected: usize, actual: usize },
+    /// 不存在的内置图案
+    UnknownPattern(String),
+    /// 编码后的状态字符串无法解析
+    InvalidEncoding(String),
+    /// 其它不合法的参数
+    InvalidArgument(String),
+}
+
+impl fmt::Display for UniverseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            UniverseError::OutOfBounds {
+                row,
+                col,
+                width,
+                height,
+            } => write!(
+                f,
+                "cell ({}, {}) is out of bounds for a {}x{} universe",
+                row, col, width, height
+            ),
+            UniverseError::InvalidDimensions { width, height } => {
+                write!(f, "invalid dimensions {}x{}: ", width, height)?;
+                if *width == 0 || *height == 0 {
+                    write!(f, "width and height must be nonzero")
+                } else {
+                    write!(f, "at most {} cells are supported", Universe::MAX_CELLS)
+                }
+            }
+            UniverseError::ParseError { line, message } => write!(f, "line {}: {}", line, message),
+            UniverseError::InvalidRule { rule, message } => {
+                write!(f, "invalid rule '{}': {}", rule, message)
+            }
+            UniverseError::LengthMismatch { expected, actual } => write!(
+                f,
+                "buffer length {} does not match expected length {}",
+                actual, expected
+            ),
+            UniverseError::UnknownPattern(name) => write!(f, "unknown pattern '{}'", name),
+            UniverseError::InvalidEncoding(message) => write!(f, "invalid encoding: {}", message),
+            UniverseError::InvalidArgument(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl Error for UniverseError {}
@@ -0,0 +1,89 @@
+#[cfg(feature = "web")]
+use wasm_bindgen::prelude::*;
+
+// 没有 `web` 特性时没有回调，事件只会被构造而不会被检查
+#[cfg_attr(not(feature = "web"), allow(dead_code))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum EventKind {
+    Extinct,
+    Stabilized,
+    PopulationThreshold(u32),
+    GenerationReached(u64),
+}
+
+/// Universe 在 tick 之后可能触发的事件：
+/// - Extinct: 存活数量降为 0
+/// - Stabilized: 一次 tick 中没有任何 Cell 发生变化，而上一次 tick 中有变化（或者之后编辑过）；
+///   保持不变的后续 tick 不会再次触发
+/// - PopulationThreshold(n): 存活数量跨越 n（从下方达到 n 或从 n 及以上跌破 n）
+/// - GenerationReached(g): 到达第 g 代
+#[cfg_attr(feature = "web", wasm_bindgen)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct UniverseEvent {
+    kind: EventKind,
+}
+
+#[cfg_attr(feature = "web", wasm_bindgen)]
+impl UniverseEvent {
+    pub fn extinct() -> UniverseEvent {
+        UniverseEvent {
+            kind: EventKind::Extinct,
+        }
+    }
+
+    pub fn stabilized() -> UniverseEvent {
+        UniverseEvent {
+            kind: EventKind::Stabilized,
+        }
+    }
+
+    pub fn population_threshold(population: u32) -> UniverseEvent {
+        UniverseEvent {
+            kind: EventKind::PopulationThreshold(population),
+        }
+    }
+
+    pub fn generation_reached(generation: u64) -> UniverseEvent {
+        UniverseEvent {
+            kind: EventKind::GenerationReached(generation),
+        }
+    }
+}
+
+/// `tick_status` 的返回值：
+/// - Changed: 至少有一个 Cell 发生了变化
+/// - Stable: 没有任何 Cell 发生变化
+/// - Extinct: 存活数量为 0（优先于 Stable）
+#[cfg_attr(feature = "web", wasm_bindgen)]
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TickStatus {
+    Changed = 0,
+    Stable = 1,
+    Extinct = 2,
+}
+
+/// 一次 tick 前后的状态摘要，用于判断哪些事件需要触发
+#[cfg_attr(not(feature = "web"), allow(dead_code))]
+pub(crate) struct TickSummary {
+    pub(crate) generation: u64,
+    pub(crate) prev_population: u32,
+    pub(crate) population: u32,
+    pub(crate) changed: bool,
+    /// tick 之前已经稳定：上一次 tick 没有变化，并且之后没有编辑
+    pub(crate) was_stable: bool,
+}
+
+#[cfg(feature = "web")]
+impl UniverseEvent {
+    pub(crate) fn triggered_by(&self, summary: &TickSummary) -> bool {
+        match self.kind {
+            EventKind::Extinct => summary.population == 0 && summary.prev_population != 0,
+            EventKind::Stabilized => !summary.changed && !summary.was_stable,
+            EventKind::PopulationThreshold(n) => {
+                (summary.prev_population < n) != (summary.population < n)
+            }
+            EventKind::GenerationReached(g) => summary.generation == g,
+        }
+    }
+}
@@ -0,0 +1,121 @@
+#[cfg(feature = "web")]
+use wasm_bindgen::prelude::*;
+
+use crate::analysis::bounding_start;
+use crate::viewport::Viewport;
+use crate::{Boundary, Cell, Universe};
+
+/// 自动扩展时每一边增加的格数
+const AUTO_EXPAND_MARGIN: u32 = 16;
+
+#[cfg_attr(feature = "web", wasm_bindgen)]
+impl Universe {
+    /// 打开后每次 tick 之后，只要有存活的 Cell 位于最外一圈，网格的宽和高就各增加
+    /// 2 * 16 格（不超过 `Universe::AUTO_EXPAND_MAX_SIDE`），并把存活 Cell 的包围盒移到新网格的中央，
+    /// 让有限的网格用起来像无限的一样。代数和规则保持不变；与 `resize` 一样，
+    /// 扩展时墙、历史快照和视口会被重置，`cells()` 的地址也会变化
+    pub fn set_auto_expand(&mut self, enabled: bool) {
+        self.auto_expand = enabled;
+    }
+
+    pub fn auto_expand(&self) -> bool {
+        self.auto_expand
+    }
+}
+
+impl Universe {
+    /// 自动扩展时宽和高的上限，达到之后不再扩展，避免失控的图案耗尽内存
+    pub const AUTO_EXPAND_MAX_SIDE: u32 = 4096;
+
+    /// tick 之后检查最外一圈，需要时扩展网格
+    pub(crate) fn expand_if_touching_edge(&mut self) {
+        if !self.auto_expand {
+            return;
+        }
+        let width = (self.width + 2 * AUTO_EXPAND_MARGIN)
+            .min(Universe::AUTO_EXPAND_MAX_SIDE.max(self.width));
+        let height = (self.height + 2 * AUTO_EXPAND_MARGIN)
+            .min(Universe::AUTO_EXPAND_MAX_SIDE.max(self.height));
+        if (width, height) == (self.width, self.height)
+            || Universe::validate_dimensions(width, height).is_err()
+            || !self.touches_edge()
+        {
+            return;
+        }
+
+        let mut live = vec![];
+        let mut rows = vec![false; self.height as usize];
+        let mut cols = vec![false; self.width as usize];
+        for row in 0..self.height {
+            for col in 0..self.width {
+                let index = self.get_index(row, col);
+                if self.cells[index] == Cell::Alive {
+                    live.push((row, col, index));
+                    rows[row as usize] = true;
+                    cols[col as usize] = true;
+                }
+            }
+        }
+        // 环形宇宙中包围盒可以跨越接缝，与 `normalize_position` 相同
+        let toroidal = self.boundary == Boundary::Toroidal;
+        let (row0, col0) = (
+            bounding_start(&rows, toroidal),
+            bounding_start(&cols, toroidal),
+        );
+        let shifted = |row: u32, col: u32| {
+            (
+                (row + self.height - row0) % self.height,
+                (col + self.width - col0) % self.width,
+            )
+        };
+        let box_height = live
+            .iter()
+            .map(|&(r, c, _)| shifted(r, c).0)
+            .max()
+            .unwrap_or(0)
+            + 1;
+        let box_width = live
+            .iter()
+            .map(|&(r, c, _)| shifted(r, c).1)
+            .max()
+            .unwrap_or(0)
+            + 1;
+        let (top, left) = ((height - box_height) / 2, (width - box_width) / 2);
+
+        let mut cells = vec![Cell::Dead; (width * height) as usize];
+        #[cfg(feature = "cell-ages")]
+        let mut ages = vec![0; cells.len()];
+        for &(row, col, _index) in live.iter() {
+            let (r, c) = shifted(row, col);
+            let new_index = ((top + r) * width + left + c) as usize;
+            cells[new_index] = Cell::Alive;
+            #[cfg(feature = "cell-ages")]
+            {
+                ages[new_index] = self.ages.get(_index).cloned().unwrap_or(0);
+            }
+        }
+
+        self.width = width;
+        self.height = height;
+        self.cells = cells;
+        self.sync_cells();
+        #[cfg(feature = "cell-ages")]
+        {
+            self.ages = ages;
+        }
+        self.snapshots.clear();
+        self.walls.clear();
+        self.rewind.clear();
+        self.undo.clear();
+        self.viewport = Viewport::full(width, height);
+        self.row_offset = 0;
+        self.col_offset = 0;
+    }
+
+    /// 最外一圈（第一行、最后一行、第一列、最后一列）是否有存活的 Cell
+    fn touches_edge(&self) -> bool {
+        let alive = |row, col| self.cells[self.get_index(row, col)] == Cell::Alive;
+        (0..self.width).any(|col| alive(0, col) || alive(self.height - 1, col))
+            || (0..self.height).any(|row| alive(row, 0) || alive(row, self.width - 1))
+    }
+}
@@ -0,0 +1,70 @@
+#[cfg(feature = "web")]
+use wasm_bindgen::prelude::*;
+
+use crate::{Cell, Universe};
+
+/// 缩放比例的范围，防止捏合手势把视图缩得过小或过大
+const MIN_SCALE: f64 = 0.1;
+const MAX_SCALE: f64 = 10.0;
+
+/// 两个触点之间的距离
+#[cfg_attr(feature = "web", wasm_bindgen)]
+pub fn pointer_distance(x0: f64, y0: f64, x1: f64, y1: f64) -> f64 {
+    ((x1 - x0).powi(2) + (y1 - y0).powi(2)).sqrt()
+}
+
+/// 捏合手势的缩放比例：当前两指距离与手势开始时距离之比
+#[cfg_attr(feature = "web", wasm_bindgen)]
+pub fn pinch_scale(start_distance: f64, current_distance: f64) -> f64 {
+    if start_distance <= 0.0 {
+        return 1.0;
+    }
+    (current_distance / start_distance).clamp(MIN_SCALE, MAX_SCALE)
+}
+
+/// Bresenham 直线算法：返回从 (r0, c0) 到 (r1, c1) 经过的所有格子（包含两端），
+/// 用于在两次 move 事件之间插值，避免快速滑动时画出的线断开
+pub fn line_cells(r0: i32, c0: i32, r1: i32, c1: i32) -> Vec<(i32, i32)> {
+    let dr = (r1 - r0).abs();
+    let dc = (c1 - c0).abs();
+    let step_r = if r0 < r1 { 1 } else { -1 };
+    let step_c = if c0 < c1 { 1 } else { -1 };
+
+    let mut cells = Vec::with_capacity((dr.max(dc) + 1) as usize);
+    let (mut r, mut c) = (r0, c0);
+    let mut err = dc - dr;
+    loop {
+        cells.push((r, c));
+        if r == r1 && c == c1 {
+            break;
+        }
+        let e2 = 2 * err;
+        if e2 > -dr {
+            err -= dr;
+            c += step_c;
+        }
+        if e2 < dc {
+            err += dc;
+            r += step_r;
+        }
+    }
+    cells
+}
+
+#[cfg_attr(feature = "web", wasm_bindgen)]
+impl Universe {
+    /// 将从 (r0, c0) 到 (r1, c1) 的直线上的 Cell 设置为存活，超出范围的部分会被忽略
+    pub fn paint_line(&mut self, r0: i32, c0: i32, r1: i32, c1: i32) {
+        let edits: Vec<(usize, Cell)> = line_cells(r0, c0, r1, c1)
+            .into_iter()
+            .filter(|&(row, column)| {
+                row >= 0
+                    && column >= 0
+                    && (row as u32) < self.height
+                    && (column as u32) < self.width
+            })
+            .map(|(row, column)| (self.get_index(row as u32, column as u32), Cell::Alive))
+            .collect();
+        self.commit_cells_edit(&edits);
+    }
+}
@@ -0,0 +1,136 @@
+use std::collections::HashMap;
+
+use crate::analysis::{bounding_box, Direction, Shape};
+use crate::census::{orientations, CLUSTER_RADIUS};
+use crate::{parse_rle, Boundary, Cell, Rule, Universe};
+
+/// `detect_gliders` 找到的一架滑翔机：3x3 包围盒左上角的位置、相位和移动方向。
+/// 相位 0 是 `bo$2bo$3o` 及其旋转，之后每一代加 1，第 4 代沿对角线移动一格后回到相位 0；
+/// 滑翔机每两代是一次滑移反射，所以镜像的滑翔机按与它形状相同的旋转计算相位
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Glider {
+    pub row: u32,
+    pub column: u32,
+    pub phase: u8,
+    pub heading: Direction,
+}
+
+/// 滑翔机的周期，也是确认时在副本上模拟的代数
+const GLIDER_PERIOD: u32 = 4;
+
+/// 相位 0、向东南移动的滑翔机
+const GLIDER: &str = "bo$2bo$3o!";
+
+/// `orientations` 中的四种旋转：不变、旋转 180°，以及两种旋转 90°
+const ROTATIONS: [usize; 4] = [0, 3, 5, 6];
+
+/// 候选滑翔机：左上角的 (row, column)、形状、相位和每个周期的位移
+type Candidate<'a> = (u32, u32, &'a Shape, u8, (i64, i64));
+
+impl Universe {
+    /// 找出所有孤立的滑翔机：按 B3/S23 定义，周围两格以内没有其它存活 Cell
+    /// （因此混在灰烬中形状相同的碎片不会被误认）。每个候选还要在副本上模拟 4 代，
+    /// 确认它沿对角线移动了一格并且形状复原，即将发生碰撞的滑翔机不会被报告。
+    /// 结果按位置排序
+    pub fn detect_gliders(&self) -> Vec<Glider> {
+        let known = glider_phases();
+        let (row0, col0) = self.seam_origin();
+        let unshift = |row: u32, col: u32| ((row + row0) % self.height, (col + col0) % self.width);
+
+        let clusters = self.components(CLUSTER_RADIUS, |row, col| {
+            let (row, col) = unshift(row, col);
+            self.cells[self.get_index(row, col)] == Cell::Alive
+        });
+        let candidates: Vec<Candidate> = clusters
+            .iter()
+            .filter_map(|cluster| {
+                let (top, left, _, _) = bounding_box(cluster);
+                let shape: Shape = cluster
+                    .iter()
+                    .map(|&(row, col)| (row - top, col - left))
+                    .collect();
+                let (shape, &(phase, heading)) = known.get_key_value(&shape)?;
+                let (row, col) = unshift(top, left);
+                Some((row, col, shape, phase, heading))
+            })
+            .filter(|&(row, col, shape, _, _)| self.shape_at(row, col, (0, 0), 2, shape))
+            .collect();
+        if candidates.is_empty() {
+            return vec![];
+        }
+
+        let mut future = self.detached_clone();
+        for _ in 0..GLIDER_PERIOD {
+            future.tick();
+        }
+        let mut gliders: Vec<Glider> = candidates
+            .into_iter()
+            .filter(|&(row, col, shape, _, heading)| future.shape_at(row, col, heading, 1, shape))
+            .map(|(row, column, _, phase, (dr, dc))| Glider {
+                row,
+                column,
+                phase,
+                heading: match (dr < 0, dc > 0) {
+                    (true, true) => Direction::NorthEast,
+                    (true, false) => Direction::NorthWest,
+                    (false, true) => Direction::SouthEast,
+                    (false, false) => Direction::SouthWest,
+                },
+            })
+            .collect();
+        gliders.sort_unstable_by_key(|glider| (glider.row, glider.column));
+        gliders
+    }
+
+    /// 以 (row, col) 偏移 `offset` 为左上角、四周各扩展 `pad` 格的窗口内，存活的 Cell 是否恰好是 `shape`；
+    /// 有限网格之外的 Cell 按照 Boundary 视为死亡或存活
+    fn shape_at(&self, row: u32, col: u32, offset: (i64, i64), pad: i64, shape: &Shape) -> bool {
+        let height = shape.iter().map(|&(r, _)| r).max().unwrap_or(0) as i64 + 1;
+        let width = shape.iter().map(|&(_, c)| c).max().unwrap_or(0) as i64 + 1;
+        let outside = self.boundary == Boundary::Alive;
+        (-pad..height + pad).all(|r| {
+            (-pad..width + pad).all(|c| {
+                let expected = r >= 0 && c >= 0 && shape.contains(&(r as u32, c as u32));
+                let alive = match self.offset_index(row, col, offset.0 + r, offset.1 + c) {
+                    Some(index) => self.cells[index] == Cell::Alive,
+                    None => outside,
+                };
+                alive == expected
+            })
+        })
+    }
+}
+
+/// 滑翔机全部 16 种形状（4 个相位 × 4 种旋转）对应的相位和每个周期的位移 (d_row, d_col)；
+/// 镜像得到的形状都已经包含在内
+fn glider_phases() -> HashMap<Shape, (u8, (i64, i64))> {
+    let mut universe = Universe::new_with_size(8, 8).unwrap();
+    universe.set_boundary(Boundary::Dead);
+    universe.rule = Rule::CONWAY;
+    let cells: Vec<(u32, u32)> = parse_rle(GLIDER)
+        .unwrap()
+        .cells
+        .iter()
+        .map(|&(row, col)| (row + 1, col + 1))
+        .collect();
+    universe.set_cells(&cells);
+
+    let mut phases = HashMap::new();
+    for phase in 0..GLIDER_PERIOD as u8 {
+        let (_, shape) = universe.live_shape().unwrap();
+        let oriented = orientations(&shape);
+        for &flags in ROTATIONS.iter() {
+            // 与 `orientations` 相同的变换作用在向东南的位移 (1, 1) 上
+            let d_row = if flags & 1 != 0 { -1 } else { 1 };
+            let d_col = if flags & 2 != 0 { -1 } else { 1 };
+            let heading = if flags & 4 != 0 {
+                (d_col, d_row)
+            } else {
+                (d_row, d_col)
+            };
+            phases.insert(oriented[flags].clone(), (phase, heading));
+        }
+        universe.tick();
+    }
+    phases
+}
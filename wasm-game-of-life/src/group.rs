@@ -0,0 +1,146 @@
+#[cfg(feature = "web")]
+use wasm_bindgen::prelude::*;
+
+use crate::{Cell, Universe, UniverseBuilder, UniverseError};
+
+/// 一组从同一个种子、同样大小创建的 Universe，用于对比不同规则：
+/// ```
+/// # use wasm_game_of_life::UniverseGroup;
+/// let mut group = UniverseGroup::new(2, 32, 32, 7).unwrap();
+/// group.set_rule(1, "B36/S23").unwrap();
+/// for _ in 0..50 {
+///     group.tick_all();
+/// }
+/// assert!(group.first_divergence_generation().is_some());
+/// ```
+/// 所有成员的尺寸始终相同，只能通过 `resize` 一起修改
+#[cfg_attr(feature = "web", wasm_bindgen)]
+pub struct UniverseGroup {
+    members: Vec<Universe>,
+    first_divergence: Option<(u64, Vec<u32>)>,
+}
+
+#[cfg_attr(feature = "web", wasm_bindgen)]
+impl UniverseGroup {
+    /// 创建 `count` 个相同的 Universe：以 `seed` 随机填充，规则都是 B3/S23
+    #[cfg_attr(feature = "web", wasm_bindgen(constructor))]
+    pub fn new(
+        count: u32,
+        width: u32,
+        height: u32,
+        seed: u64,
+    ) -> Result<UniverseGroup, UniverseError> {
+        if count == 0 {
+            return Err(UniverseError::InvalidArgument(
+                "a universe group needs at least one member".to_string(),
+            ));
+        }
+        let universe = UniverseBuilder::new()
+            .width(width)
+            .height(height)
+            .seed(seed)
+            .build()?;
+        Ok(UniverseGroup {
+            members: vec![universe; count as usize],
+            first_divergence: None,
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.members.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.members.is_empty()
+    }
+
+    /// 所有成员共同的代数
+    pub fn generation(&self) -> u64 {
+        self.members[0].generation()
+    }
+
+    /// 设置第 `index` 个成员的规则（B/S 记法）
+    pub fn set_rule(&mut self, index: usize, rule: &str) -> Result<(), UniverseError> {
+        self.check_index(index)?;
+        self.members[index].set_rule(rule)
+    }
+
+    /// 第 `index` 个成员的 Cell 缓冲区，格式与 `Universe::cells` 相同
+    pub fn cells(&self, index: usize) -> Result<*const Cell, UniverseError> {
+        self.check_index(index)?;
+        Ok(self.members[index].cells())
+    }
+
+    /// 所有成员各 tick 一次；第一次出现任意成员与第 0 个成员不同时记录当时的代数和位置
+    pub fn tick_all(&mut self) {
+        for universe in self.members.iter_mut() {
+            universe.tick();
+        }
+        if self.first_divergence.is_some() {
+            return;
+        }
+        for index in 1..self.members.len() {
+            let diff = self.diff(0, index);
+            if !diff.is_empty() {
+                self.first_divergence = Some((self.generation(), diff));
+                return;
+            }
+        }
+    }
+
+    /// 成员 `a` 和 `b` 之间状态不同的 Cell 数量
+    pub fn divergence(&self, a: usize, b: usize) -> Result<u32, UniverseError> {
+        self.check_index(a)?;
+        self.check_index(b)?;
+        Ok(self.diff(a, b).len() as u32 / 2)
+    }
+
+    /// 第一次出现分歧的代数，还没有分歧时为 None
+    pub fn first_divergence_generation(&self) -> Option<u64> {
+        self.first_divergence
+            .as_ref()
+            .map(|&(generation, _)| generation)
+    }
+
+    /// 第一次出现分歧时与第 0 个成员不同的 Cell，展开为 [row0, col0, row1, col1, ...]
+    pub fn first_divergence_cells(&self) -> Vec<u32> {
+        self.first_divergence
+            .as_ref()
+            .map_or(vec![], |(_, cells)| cells.clone())
+    }
+
+    /// 同时修改所有成员的尺寸，所有 Cell 会被重置为死亡，分歧记录也会被清除
+    pub fn resize(&mut self, width: u32, height: u32) -> Result<(), UniverseError> {
+        Universe::validate_dimensions(width, height)?;
+        for universe in self.members.iter_mut() {
+            universe.resize(width, height)?;
+        }
+        self.first_divergence = None;
+        Ok(())
+    }
+}
+
+impl UniverseGroup {
+    /// 第 `index` 个成员，用于只读访问
+    pub fn member(&self, index: usize) -> Option<&Universe> {
+        self.members.get(index)
+    }
+
+    fn check_index(&self, index: usize) -> Result<(), UniverseError> {
+        if index >= self.members.len() {
+            return Err(UniverseError::InvalidArgument(format!(
+                "member {} does not exist in a group of {}",
+                index,
+                self.members.len()
+            )));
+        }
+        Ok(())
+    }
+
+    /// 复用 `compute_sparse_diff`：成员的尺寸和偏移相同，b 的缓冲区可以直接作为上一份状态
+    fn diff(&self, a: usize, b: usize) -> Vec<u32> {
+        let mut other = vec![0; self.members[b].cells_byte_len()];
+        self.members[b].write_cells_into(&mut other).unwrap();
+        self.members[a].compute_sparse_diff(&other).unwrap()
+    }
+}
@@ -1,17 +1,97 @@
+mod analysis;
+mod builder;
+mod census;
+mod component;
+mod control;
+mod encoding;
+mod engine;
+mod error;
+mod events;
+mod expand;
+mod gesture;
+mod glider;
+mod group;
+mod macrocell;
+mod metrics;
+mod neighbors;
+mod patterns;
+mod record;
+mod render;
+mod rewind;
+mod rle;
+mod rng;
+mod rule;
+mod run;
+#[cfg(not(feature = "web"))]
+mod shared;
+mod soup;
+mod text;
+mod undo;
 mod utils;
+mod viewport;
+mod walls;
+#[cfg(feature = "web")]
+mod wasm;
 
+pub use analysis::{CellExplanation, Direction};
+pub use builder::UniverseBuilder;
+pub use census::Census;
+pub use component::Component;
+pub use control::{RunState, SimulationControl};
+pub use engine::{compare_engines, Engine, EngineResult};
+pub use error::UniverseError;
+use events::TickSummary;
+pub use events::{TickStatus, UniverseEvent};
+pub use gesture::{line_cells, pinch_scale, pointer_distance};
+pub use glider::Glider;
+pub use group::UniverseGroup;
+pub use macrocell::parse_macrocell;
+pub use metrics::Metrics;
+use neighbors::NeighborCounts;
+pub use record::{Recorder, Replay};
+use rewind::RewindBuffer;
+pub use rle::{parse_rle, Pattern};
+pub use rng::RngKind;
+use rng::UniverseRng;
+pub use rule::Rule;
+pub use run::{RunResult, StopCondition};
+#[cfg(not(feature = "web"))]
+pub use shared::SharedUniverse;
+pub use soup::{run_soup_search, SoupReport, SoupResult, SoupSearch};
+use text::Glyphs;
+use undo::UndoStack;
+#[cfg(not(all(feature = "web", target_arch = "wasm32")))]
+pub use utils::take_warnings;
+use viewport::Viewport;
+#[cfg(feature = "web")]
+use wasm::FrameCache;
+#[cfg(feature = "web")]
+pub use wasm::{greet, Timer};
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, VecDeque};
+use std::convert::TryFrom;
 use std::fmt;
+use std::hash::{Hash, Hasher};
+#[cfg(feature = "web")]
 use wasm_bindgen::prelude::*;
 
 use rand::prelude::*;
 
-extern crate web_sys;
-use web_sys::console;
+/// 在浏览器控制台输出日志，其它环境下什么也不做
+#[cfg(all(feature = "web", target_arch = "wasm32"))]
+#[allow(unused_macros)]
+macro_rules! log {
+    ( $( $t:tt )* ) => {
+        web_sys::console::log_1(&format!( $( $t )* ).into());
+    }
+}
 
+#[cfg(not(all(feature = "web", target_arch = "wasm32")))]
 #[allow(unused_macros)]
 macro_rules! log {
     ( $( $t:tt )* ) => {
-        console::log_1(&format!( $( $t )* ).into());
+        let _ = format_args!( $( $t )* );
     }
 }
 
@@ -21,84 +101,206 @@ macro_rules! log {
 #[global_allocator]
 static ALLOC: wee_alloc::WeeAlloc = wee_alloc::WeeAlloc::INIT;
 
-#[wasm_bindgen]
-extern {
-    fn alert(s: &str);
+/// 生命游戏：
+/// 由一个二维网格所表示的无限宇宙，每个网格表示一个生命，生命的状态遵循下面四个规则：
+/// 1. 任何四周邻居存活数少于两个的存活网格将死亡
+/// 2. 任何四周邻居存活数为两个或三个的存活网格将在下一代继续存活
+/// 3. 任何四周邻居存活数多于三个的存活网格将死亡
+/// 4. 任何已经死亡的网格，如果周围邻居存活数为三个，将在下一代复活
+#[cfg_attr(feature = "web", wasm_bindgen)]
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Cell {
+    #[default]
+    Dead = 0,
+    Alive = 1,
 }
 
-#[wasm_bindgen]
-pub fn greet() {
-    alert("Hello, wasm-game-of-life!");
+impl Cell {
+    /// 切换存活状态
+    pub fn toggle(&mut self) {
+        *self = !*self;
+    }
+
+    pub fn is_alive(&self) -> bool {
+        *self == Cell::Alive
+    }
 }
 
-pub struct Timer<'a> {
-    name: &'a str,
+impl std::ops::Not for Cell {
+    type Output = Cell;
+
+    fn not(self) -> Cell {
+        match self {
+            Cell::Dead => Cell::Alive,
+            Cell::Alive => Cell::Dead,
+        }
+    }
 }
 
-impl<'a> Timer<'a> {
-    pub fn new(name: &'a str) -> Timer<'a> {
-        console::time_with_label(name);
-        Timer {
-            name,
+impl From<bool> for Cell {
+    fn from(alive: bool) -> Cell {
+        if alive {
+            Cell::Alive
+        } else {
+            Cell::Dead
         }
     }
 }
 
-impl<'a> Drop for Timer<'a> {
-    fn drop(&mut self) {
-        console::time_end_with_label(self.name);
+impl From<Cell> for bool {
+    fn from(cell: Cell) -> bool {
+        cell.is_alive()
     }
 }
 
-/// 生命游戏：
-/// 由一个二维网格所表示的无限宇宙，每个网格表示一个生命，生命的状态遵循下面四个规则：
-/// 1. 任何四周邻居存活数少于两个的存活网格将死亡
-/// 2. 任何四周邻居存活数为两个或三个的存活网格将在下一代继续存活
-/// 3. 任何四周邻居存活数多于三个的存活网格将死亡
-/// 4. 任何已经死亡的网格，如果周围邻居存活数为三个，将在下一代复活
-#[wasm_bindgen]
+impl TryFrom<u8> for Cell {
+    type Error = UniverseError;
+
+    /// 只接受 0（Dead）和 1（Alive），与 `cells()` 返回的内存布局一致
+    fn try_from(value: u8) -> Result<Cell, UniverseError> {
+        match value {
+            0 => Ok(Cell::Dead),
+            1 => Ok(Cell::Alive),
+            _ => Err(UniverseError::InvalidArgument(format!(
+                "invalid cell state {}: expected 0 or 1",
+                value
+            ))),
+        }
+    }
+}
+
+/// wasm-bindgen 不支持在枚举上导出方法，以下函数供 JS 使用
+#[cfg_attr(feature = "web", wasm_bindgen)]
+pub fn cell_from_bool(alive: bool) -> Cell {
+    Cell::from(alive)
+}
+
+/// 将 `cells()` 中读取到的字节转换为 Cell，不是 0/1 时抛出错误
+#[cfg_attr(feature = "web", wasm_bindgen)]
+pub fn cell_from_u8(value: u8) -> Result<Cell, UniverseError> {
+    Cell::try_from(value)
+}
+
+#[cfg_attr(feature = "web", wasm_bindgen)]
+pub fn cell_is_alive(cell: Cell) -> bool {
+    cell.is_alive()
+}
+
+#[cfg_attr(feature = "web", wasm_bindgen)]
+pub fn cell_toggled(cell: Cell) -> Cell {
+    !cell
+}
+
+/// 边界的处理方式：
+/// - Toroidal: 上下、左右相连的环形宇宙（默认）
+/// - Dead: 有限的网格，网格外的 Cell 视为死亡
+/// - Alive: 有限的网格，网格外的 Cell 视为存活，用于研究边缘效应
+#[cfg_attr(feature = "web", wasm_bindgen)]
 #[repr(u8)]
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
-pub enum Cell {
-    Dead = 0,
-    Alive = 1,
+pub enum Boundary {
+    Toroidal = 0,
+    Dead = 1,
+    Alive = 2,
 }
 
-impl Cell {
-    fn toggle(&mut self) {
-        *self = match *self {
-            Cell::Dead => Cell::Alive,
-            Cell::Alive => Cell::Dead,
-        };
-    }
+/// `merge` 逐个 Cell 组合两个 Universe 的方式，a 为当前 Universe，b 为另一个：
+/// - Or: a 或 b 存活
+/// - And: a 和 b 都存活
+/// - Xor: 恰好一个存活
+/// - AndNot: a 存活而 b 死亡，用于从图案中减去另一个图案
+#[cfg_attr(feature = "web", wasm_bindgen)]
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MergeOp {
+    Or = 0,
+    And = 1,
+    Xor = 2,
+    AndNot = 3,
+}
+
+/// 新建 Universe 时随机数种子的来源：
+/// - Environment: 浏览器中来自 `Math.random()`，原生平台上来自系统时间（默认）
+/// - Deterministic: 不读取任何外部熵，种子由一个固定常数加上计数器得到，
+///   调用 `set_entropy_mode` 之后新建的 Universe 在所有平台上的随机序列都相同
+#[cfg_attr(feature = "web", wasm_bindgen)]
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum EntropyMode {
+    #[default]
+    Environment = 0,
+    Deterministic = 1,
+}
+
+/// 设置当前线程之后新建的 Universe 使用的种子来源，同时把确定性模式的计数器归零；
+/// 已经存在的 Universe 和显式设置的种子（`set_seed`、`UniverseBuilder::seed`）不受影响
+#[cfg_attr(feature = "web", wasm_bindgen)]
+pub fn set_entropy_mode(mode: EntropyMode) {
+    utils::set_entropy_mode(mode);
 }
 
+/// `set_rule_table` 查找表的长度：2 种状态 × 256 种邻居组合
+const RULE_TABLE_LEN: usize = 512;
 
-/// 比如，一个三行三列的 Universe, 
+/// 比如，一个三行三列的 Universe,
 /// [ 0, 1, 2, 3, 4, 5, 6, 7, 8 ]
 /// |  row0  |  row1  |  row2  |
-#[wasm_bindgen]
+#[cfg_attr(feature = "web", wasm_bindgen)]
+#[derive(Clone)]
 pub struct Universe {
     width: u32,
     height: u32,
     cells: Vec<Cell>,
     _cells: Vec<Cell>,
+    generation: u64,
+    #[cfg(feature = "web")]
+    callbacks: Vec<(UniverseEvent, js_sys::Function)>,
+    row_offset: u32,
+    col_offset: u32,
+    verbose: bool,
+    snapshots: VecDeque<Vec<Cell>>,
+    snapshot_capacity: usize,
+    viewport: Viewport,
+    rule: Rule,
+    /// 非空时第 g 代 tick 使用 rule_schedule[g % len]，代替 rule
+    rule_schedule: Vec<Rule>,
+    /// 非空时为 512 项的查找表，优先于 rule 和 rule_schedule，见 `set_rule_table`
+    rule_table: Vec<u8>,
+    boundary: Boundary,
+    #[cfg(feature = "web")]
+    frame: Option<FrameCache>,
+    glyphs: Glyphs,
+    walls: Vec<bool>,
+    walls_count_as_dead: bool,
+    wrap_shift: (i32, i32),
+    rng: UniverseRng,
+    /// 每个 Cell 连续存活的 tick 数，按 self.cells 的下标存放；为空表示还没有 tick 过
+    #[cfg(feature = "cell-ages")]
+    ages: Vec<u32>,
+    /// 年龄的上限，见 `set_age_cap`
+    #[cfg(feature = "cell-ages")]
+    age_cap: u32,
+    /// 存活 Cell 碰到边界时是否扩展网格，见 `set_auto_expand`
+    auto_expand: bool,
+    /// 最近若干代 tick 之前的状态，见 `enable_rewind`
+    rewind: RewindBuffer,
+    /// 编辑的撤销栈，见 `undo`
+    undo: UndoStack,
+    /// 上一次 tick 中状态发生变化的 Cell 数量，见 `metrics`
+    active_cells: u32,
+    /// 存活 Cell 在 self.cells 中的下标，可能包含已经死亡或重复的下标，见 `live_components`
+    live: Vec<u32>,
+    /// 上一次 tick 中没有任何 Cell 发生变化，并且之后没有编辑过，见 `UniverseEvent::stabilized`
+    stable: bool,
+    /// `neighbor_counts_ptr` 的缓存
+    neighbor_counts: Option<NeighborCounts>,
 }
 
-#[wasm_bindgen]
+#[cfg_attr(feature = "web", wasm_bindgen)]
 impl Universe {
     pub fn new() -> Universe {
-        let width = 64;
-        let height = 64;
-
-        let cells = vec![];
-        let _cells = vec![];
-        let mut universe = Universe {
-            width,
-            height,
-            cells,
-            _cells,
-        };
+        let mut universe = Universe::with_size(64, 64);
 
         // 随机生成 Cell 状态
         universe.start();
@@ -106,111 +308,938 @@ impl Universe {
         universe
     }
 
+    /// 创建一个指定大小、所有 Cell 均为死亡状态的 Universe
+    pub fn new_with_size(width: u32, height: u32) -> Result<Universe, UniverseError> {
+        Universe::validate_dimensions(width, height)?;
+        Ok(Universe::with_size(width, height))
+    }
+
+    /// 深拷贝一个 Universe，两者之后互不影响；副本不带 `on_event` 注册的回调
+    #[cfg_attr(not(feature = "web"), allow(unused_mut))]
+    pub fn duplicate(&self) -> Universe {
+        let mut universe = self.clone();
+        #[cfg(feature = "web")]
+        universe.callbacks.clear();
+        universe
+    }
+
     pub fn width(&self) -> u32 {
         self.width
     }
 
-    pub fn set_width(&mut self, width: u32) {
-        self.width = width;
-        self.cells = (0..self.width * self.height).map(|_| Cell::Dead).collect();
+    /// 修改宽度，所有 Cell 会被重置为死亡
+    pub fn set_width(&mut self, width: u32) -> Result<(), UniverseError> {
+        self.resize(width, self.height)
     }
 
     pub fn height(&self) -> u32 {
         self.height
     }
 
-    pub fn set_height(&mut self, height: u32) {
+    /// 修改高度，所有 Cell 会被重置为死亡
+    pub fn set_height(&mut self, height: u32) -> Result<(), UniverseError> {
+        self.resize(self.width, height)
+    }
+
+    /// 同时修改宽度和高度，所有 Cell 会被重置为死亡；
+    /// 尺寸不合法时返回错误，Universe 保持不变
+    pub fn resize(&mut self, width: u32, height: u32) -> Result<(), UniverseError> {
+        Universe::validate_dimensions(width, height)?;
+
+        self.width = width;
         self.height = height;
-        self.cells = (0..self.width * self.height).map(|_| Cell::Dead).collect();
+        self.cells = vec![Cell::Dead; (width * height) as usize];
+        self.sync_cells();
+        self.active_cells = 0;
+        self.stable = false;
+        self.snapshots.clear();
+        #[cfg(feature = "cell-ages")]
+        self.ages.clear();
+        self.walls.clear();
+        self.rewind.clear();
+        self.undo.clear();
+        self.viewport = Viewport::full(self.width, self.height);
+        self.row_offset = 0;
+        self.col_offset = 0;
+        Ok(())
     }
 
+    /// Cell 缓冲区的地址，每个 Cell 占一个字节（0 为死亡，1 为存活），共 `cells_byte_len` 字节。
+    /// tick、start 等操作都原地修改这块内存，地址只会在改变尺寸时变化。
+    /// 但任何可能分配内存的调用（包括 tick）都可能让 Wasm 内存增长，之前在 `memory.buffer`
+    /// 上创建的视图会随之失效，所以每次这样的调用之后都要重新创建视图，不能一直持有同一个；
+    /// 读取时也不能同时 tick，否则会读到一半新一半旧的状态，
+    /// 需要并发时请自行双缓冲（例如先用 `write_cells_into` 复制一份）
     pub fn cells(&self) -> *const Cell {
         self.cells.as_ptr()
     }
 
+    /// `cells()` 指向的缓冲区的字节数，等于 width * height
+    pub fn cells_byte_len(&self) -> usize {
+        self.cells.len() * std::mem::size_of::<Cell>()
+    }
+
+    /// 将所有 Cell 按 cells() 的顺序写入调用方提供的缓冲区（死亡为 0，存活为 1），
+    /// 这样 JS 可以一直复用同一个 Uint8Array
+    pub fn write_cells_into(&self, out: &mut [u8]) -> Result<(), UniverseError> {
+        if out.len() != self.cells.len() {
+            return Err(UniverseError::LengthMismatch {
+                expected: self.cells.len(),
+                actual: out.len(),
+            });
+        }
+
+        for (byte, &cell) in out.iter_mut().zip(self.cells.iter()) {
+            *byte = cell as u8;
+        }
+
+        Ok(())
+    }
+
+    /// 与 JS 保存的上一份 cells 缓冲区（格式同 `write_cells_into`）比较，
+    /// 返回所有发生变化的 Cell 坐标，展开为 [row0, col0, row1, col1, ...]
+    pub fn compute_sparse_diff(&self, prev_cells: &[u8]) -> Result<Vec<u32>, UniverseError> {
+        if prev_cells.len() != self.cells.len() {
+            return Err(UniverseError::LengthMismatch {
+                expected: self.cells.len(),
+                actual: prev_cells.len(),
+            });
+        }
+
+        let mut diff = vec![];
+        for row in 0..self.height {
+            for column in 0..self.width {
+                let index = self.get_index(row, column);
+                if (prev_cells[index] != 0) != (self.cells[index] == Cell::Alive) {
+                    diff.push(row);
+                    diff.push(column);
+                }
+            }
+        }
+        Ok(diff)
+    }
+
     pub fn render(&self) -> String {
         self.to_string()
     }
 
+    /// 与 render 相同，但额外输出一行和一列：重复第 0 行和第 0 列，
+    /// 环形宇宙平铺显示时接缝处看起来是连续的
+    pub fn render_wrapped(&self) -> String {
+        let mut out = String::new();
+        for row in 0..=self.height {
+            for column in 0..=self.width {
+                let cell = self.cells[self.get_index(row % self.height, column % self.width)];
+                out.push(self.glyphs.glyph(cell));
+            }
+            out.push_str(&self.glyphs.row_separator);
+        }
+        out
+    }
+
+    /// 当前存活的 Cell 数量
+    pub fn population(&self) -> u32 {
+        self.cells
+            .iter()
+            .filter(|&&cell| cell == Cell::Alive)
+            .count() as u32
+    }
+
+    /// 存活 Cell 的年龄分布：第 k 项是恰好连续存活了 k 次 tick 的 Cell 数量（新出生的为 0），
+    /// 长度为最大年龄加一，没有存活 Cell 时为空
+    #[cfg(feature = "cell-ages")]
+    pub fn age_distribution(&self) -> Vec<u32> {
+        let mut histogram = vec![];
+        for (index, &cell) in self.cells.iter().enumerate() {
+            if cell != Cell::Alive {
+                continue;
+            }
+            let age = self.ages.get(index).cloned().unwrap_or(0) as usize;
+            if histogram.len() <= age {
+                histogram.resize(age + 1, 0);
+            }
+            histogram[age] += 1;
+        }
+        histogram
+    }
+
+    /// 年龄的上限：连续存活更久的 Cell 年龄停在 `cap`，已经超过的立即被截断。
+    /// 渲染时可以用 `age / cap` 把颜色渐变到一个固定的色调；默认不限制
+    #[cfg(feature = "cell-ages")]
+    pub fn set_age_cap(&mut self, cap: u32) {
+        self.age_cap = cap;
+        for age in self.ages.iter_mut() {
+            *age = (*age).min(cap);
+        }
+    }
+
+    #[cfg(feature = "cell-ages")]
+    pub fn age_cap(&self) -> u32 {
+        self.age_cap
+    }
+
+    /// 每个 Cell 连续存活的 tick 数（不超过 `age_cap`），顺序与 `get_cells` 相同；
+    /// 死亡和新出生的 Cell 为 0
+    #[cfg(feature = "cell-ages")]
+    pub fn ages(&self) -> Vec<u32> {
+        let mut ages = vec![0; self.cells.len()];
+        for (index, age) in ages.iter_mut().enumerate() {
+            if self.cells[index] == Cell::Alive {
+                *age = self.ages.get(index).cloned().unwrap_or(0);
+            }
+        }
+        ages
+    }
+
+    /// 以 (row, column) 为左上角、高 height 宽 width 的区域内存活 Cell 的数量，
+    /// 超出 Universe 的部分会被裁掉
+    pub fn population_in(&self, row: u32, column: u32, height: u32, width: u32) -> u32 {
+        let row1 = row.saturating_add(height).min(self.height);
+        let col1 = column.saturating_add(width).min(self.width);
+        let mut count = 0;
+        for r in row..row1 {
+            for c in column..col1 {
+                count += self.cells[self.get_index(r, c)] as u32;
+            }
+        }
+        count
+    }
+
+    /// 把 Universe 切成 `rows` 行 `cols` 列的区域，按行优先的顺序返回每个区域内存活 Cell 的数量，
+    /// 在 JS 中是 `Uint32Array`，可以画成热力图。尺寸不能整除时，多出来的行和列归入最后一行和最后一列的区域；
+    /// 只遍历一次 Cell。`rows`、`cols` 为 0 或者超过高、宽时返回错误
+    pub fn region_populations(&self, rows: u32, cols: u32) -> Result<Vec<u32>, UniverseError> {
+        if rows == 0 || cols == 0 || rows > self.height || cols > self.width {
+            return Err(UniverseError::InvalidArgument(format!(
+                "cannot split a {}x{} universe into {}x{} regions",
+                self.width, self.height, cols, rows
+            )));
+        }
+        let (region_height, region_width) = (self.height / rows, self.width / cols);
+        let mut counts = vec![0; (rows * cols) as usize];
+        for row in 0..self.height {
+            let region_row = (row / region_height).min(rows - 1);
+            for column in 0..self.width {
+                let region_col = (column / region_width).min(cols - 1);
+                counts[(region_row * cols + region_col) as usize] +=
+                    self.cells[self.get_index(row, column)] as u32;
+            }
+        }
+        Ok(counts)
+    }
+
+    /// 当前代数，每次 tick 加一
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
     /// 调用进行所有生命的状态更新
     pub fn tick(&mut self) {
+        self.step();
+    }
+
+    /// 与 `tick` 相同，同时返回这一代的变化情况，方便 JS 循环在停止变化后自动暂停
+    pub fn tick_status(&mut self) -> TickStatus {
+        let summary = self.step();
+        if summary.population == 0 {
+            TickStatus::Extinct
+        } else if !summary.changed {
+            TickStatus::Stable
+        } else {
+            TickStatus::Changed
+        }
+    }
+
+    /// 连续 tick `steps` 次，返回每一次之后的存活数量，
+    /// 快进时只需要一次 Wasm 调用就能同时得到画图用的数据
+    pub fn tick_many_stats(&mut self, steps: u32) -> Vec<u32> {
+        (0..steps).map(|_| self.step().population).collect()
+    }
+
+    /// 连续 tick `n_ticks` 次，返回每一次之后的密度（存活数量 / Cell 总数），
+    /// 用于观察随机汤的收敛、飞船的恒定密度或者逐渐消亡的图案
+    pub fn compute_alive_density_over_time(&mut self, n_ticks: u32) -> Vec<f64> {
+        (0..n_ticks)
+            .map(|_| self.step().population as f64 / self.cells.len() as f64)
+            .collect()
+    }
+
+    /// 先正常 tick，再把网格与它旋转 90°、180°、270° 的三个副本做 OR，
+    /// 结果总是 4 重旋转对称的；这是有损操作：不对称的部分不会被删除，而是补全为对称，
+    /// 因此存活数量只会增加。只支持正方形的 Universe
+    pub fn step_symmetric(&mut self) -> Result<(), UniverseError> {
+        if self.width != self.height {
+            return Err(UniverseError::InvalidArgument(format!(
+                "step_symmetric requires a square universe, got {}x{}",
+                self.width, self.height
+            )));
+        }
+        self.tick();
+
+        let n = self.width - 1;
+        let mut next = self.cells.clone();
+        for row in 0..=n {
+            for column in 0..=n {
+                let rotations = [
+                    (row, column),
+                    (column, n - row),
+                    (n - row, n - column),
+                    (n - column, row),
+                ];
+                if rotations
+                    .iter()
+                    .any(|&(r, c)| self.cells[self.get_index(r, c)] == Cell::Alive)
+                {
+                    next[self.get_index(row, column)] = Cell::Alive;
+                }
+            }
+        }
+        self.cells.copy_from_slice(&next);
+        self.sync_cells();
+        Ok(())
+    }
+
+    /// 以左上角 (height/2) × (width/2) 的区域为准，水平镜像到右上角、垂直镜像到左下角、
+    /// 同时翻转到右下角，覆盖这三个区域原有的内容，得到左右、上下都对称的网格；
+    /// 宽或高为奇数时，正中间的列或行保持不变
+    pub fn mirror_to_quadrants(&mut self) {
+        let (h, w) = (self.height, self.width);
+        for row in 0..h / 2 {
+            for column in 0..w / 2 {
+                let cell = self.cells[self.get_index(row, column)];
+                for (r, c) in [
+                    (row, w - 1 - column),
+                    (h - 1 - row, column),
+                    (h - 1 - row, w - 1 - column),
+                ] {
+                    let index = self.get_index(r, c);
+                    self.cells[index] = cell;
+                }
+            }
+        }
+        self.commit_edit();
+    }
+
+    pub fn toggle_cell(&mut self, row: u32, column: u32) -> Result<(), UniverseError> {
+        self.check_bounds(row, column)?;
+        let index = self.get_index(row, column);
+        let cell = !self.cells[index];
+        self.commit_cells_edit(&[(index, cell)]);
+        Ok(())
+    }
+
+    /// 设置单个 Cell 的状态，越界时返回错误；这是 Rust 中推荐的单个 Cell 写入方式
+    pub fn set_cell(&mut self, row: u32, column: u32, cell: Cell) -> Result<(), UniverseError> {
+        self.check_bounds(row, column)?;
+        let index = self.get_index(row, column);
+        self.commit_cells_edit(&[(index, cell)]);
+        Ok(())
+    }
+
+    /// 只对 (row, column) 应用一次当前规则并立即更新它，其它 Cell 和代数都不变，
+    /// 用于逐个 Cell 演示规则的教学场景；之后的 Cell 会看到这个 Cell 的新状态
+    pub fn apply_rule_at(&mut self, row: u32, column: u32) -> Result<(), UniverseError> {
+        self.check_bounds(row, column)?;
+        let cell = self.next_cell(self.current_rule(), row, column);
+        self.set_cell(row, column, cell)
+    }
+
+    /// 设置逻辑原点相对于 cells 缓冲区的偏移（环形），用于平移视口
+    /// 偏移作用于所有基于 (row, column) 的访问，cells() 返回的缓冲区本身不受影响
+    pub fn set_toroidal_offset(&mut self, row_offset: i32, col_offset: i32) {
+        self.row_offset = row_offset.rem_euclid(self.height as i32) as u32;
+        self.col_offset = col_offset.rem_euclid(self.width as i32) as u32;
+    }
+
+    /// 环形宇宙的接缝错位：邻居跨过左右边界时行号额外移动 row_shift，
+    /// 跨过上下边界时列号额外移动 col_shift（类似螺旋缠绕的圆柱面），默认都为 0
+    pub fn set_wrap_shift(&mut self, row_shift: i32, col_shift: i32) {
+        self.wrap_shift = (row_shift, col_shift);
+    }
+
+    /// 在当前偏移的基础上继续平移
+    pub fn scroll(&mut self, dr: i32, dc: i32) {
+        let row_offset = (self.row_offset as i64 + dr as i64).rem_euclid(self.height as i64);
+        let col_offset = (self.col_offset as i64 + dc as i64).rem_euclid(self.width as i64);
+        self.row_offset = row_offset as u32;
+        self.col_offset = col_offset as u32;
+    }
+
+    pub fn row_offset(&self) -> u32 {
+        self.row_offset
+    }
+
+    pub fn col_offset(&self) -> u32 {
+        self.col_offset
+    }
+
+    /// 设置 B/S 记法的规则，例如 `B3/S23`；
+    /// 也接受 `rule_string` 输出的 Golly `MAP` 记法，等价于用对应的查找表调用 `set_rule_table`
+    pub fn set_rule(&mut self, rule: &str) -> Result<(), UniverseError> {
+        if rule.trim().starts_with("MAP") {
+            return self.set_rule_table(&rule::map_to_table(rule.trim())?);
+        }
+        self.use_rule(rule.parse()?);
+        Ok(())
+    }
+
+    /// 切换到 HighLife（B36/S23），等价于 `set_rule("B36/S23")`
+    pub fn use_highlife_preset(&mut self) {
+        self.use_rule(Rule::HIGHLIFE);
+    }
+
+    /// 切换到 Day & Night（B3678/S34678）
+    pub fn use_daynight_preset(&mut self) {
+        self.use_rule(Rule::DAY_AND_NIGHT);
+    }
+
+    /// 切换到 Seeds（B2/S）
+    pub fn use_seeds_preset(&mut self) {
+        self.use_rule(Rule::SEEDS);
+    }
+
+    /// 当前规则的 B/S 记法
+    pub fn rule(&self) -> String {
+        self.rule.to_string()
+    }
+
+    /// 实际生效的规则的规范记法，例如 `set_rule("b63/s32")` 之后为 `B36/S23`。
+    /// 设置了 `set_rule_schedule` 时为各个规则以逗号连接；设置了查找表时，
+    /// 只依赖邻居数量的表为等价的 B/S 记法，其它为 Golly 的 `MAP` 记法，可以再传给 `set_rule`
+    pub fn rule_string(&self) -> String {
+        if !self.rule_table.is_empty() {
+            return match rule::totalistic_rule(&self.rule_table) {
+                Some(rule) => rule.to_string(),
+                None => rule::table_to_map(&self.rule_table),
+            };
+        }
+        if !self.rule_schedule.is_empty() {
+            let rules: Vec<String> = self.rule_schedule.iter().map(Rule::to_string).collect();
+            return rules.join(",");
+        }
+        self.rule.to_string()
+    }
+
+    /// `set_rule` 设置的规则出生条件的位掩码，格式见 `Rule::birth_mask`（B3 为 `[0b1000]`）
+    pub fn rule_birth_mask(&self) -> Vec<u8> {
+        self.rule.birth_mask()
+    }
+
+    /// `set_rule` 设置的规则存活条件的位掩码，格式见 `Rule::birth_mask`（S23 为 `[0b1100]`）
+    pub fn rule_survive_mask(&self) -> Vec<u8> {
+        self.rule.survival_mask()
+    }
+
+    /// 用显式的邻居数列表设置规则，`apply_totalistic_rule(&[3], &[2, 3])` 等价于 `set_rule("B3/S23")`
+    pub fn apply_totalistic_rule(
+        &mut self,
+        birth_counts: &[u8],
+        survival_counts: &[u8],
+    ) -> Result<(), UniverseError> {
+        self.rule = Rule::from_counts(birth_counts, survival_counts)?;
+        self.rule_table.clear();
+        Ok(())
+    }
+
+    /// 当前规则下会出生的邻居数，升序，可以直接传回 `apply_totalistic_rule`
+    pub fn birth_counts(&self) -> Vec<u8> {
+        self.rule.birth_counts()
+    }
+
+    /// 当前规则下会存活的邻居数，升序
+    pub fn survival_counts(&self) -> Vec<u8> {
+        self.rule.survival_counts()
+    }
+
+    /// “Life without Death” 模式：Cell 按当前规则的出生条件出生，但一旦存活就永远不会死亡，
+    /// 存活数量只增不减。同时作用于 `set_rule_schedule` 的规则序列；`set_rule` 会重新关闭它，
+    /// 使用 `set_rule_table` 的查找表时不起作用
+    pub fn set_life_without_death(&mut self, enabled: bool) {
+        self.rule = self.rule.with_no_death(enabled);
+        for rule in self.rule_schedule.iter_mut() {
+            *rule = rule.with_no_death(enabled);
+        }
+    }
+
+    pub fn life_without_death(&self) -> bool {
+        self.rule.no_death()
+    }
+
+    /// 用 512 项的查找表设置任意的二状态规则：下标为 `state << 8 | neighbors`，
+    /// state 为当前 Cell 的状态（0 或 1），neighbors 的第 0..8 位依次是
+    /// 西北、北、东北、西、东、西南、南、东南八个邻居是否存活；每一项必须是 0 或 1，
+    /// 表示下一代的状态。B/S 规则是其中只依赖邻居数量的特例。
+    /// 之后再调用 `set_rule`、`apply_totalistic_rule` 或 `set_rule_schedule` 会取消查找表
+    pub fn set_rule_table(&mut self, table: &[u8]) -> Result<(), UniverseError> {
+        if table.len() != RULE_TABLE_LEN {
+            return Err(UniverseError::LengthMismatch {
+                expected: RULE_TABLE_LEN,
+                actual: table.len(),
+            });
+        }
+        if let Some(index) = table.iter().position(|&entry| entry > 1) {
+            return Err(UniverseError::InvalidArgument(format!(
+                "rule table entry {} is {}: expected 0 or 1",
+                index, table[index]
+            )));
+        }
+        self.rule_table = table.to_vec();
+        Ok(())
+    }
+
+    /// 当前使用的查找表，格式与 `set_rule_table` 相同；没有设置查找表时由当前的 B/S 规则生成
+    pub fn get_rule_table(&self) -> Vec<u8> {
+        if !self.rule_table.is_empty() {
+            return self.rule_table.clone();
+        }
+        let rule = self.current_rule();
+        (0..RULE_TABLE_LEN)
+            .map(|index| {
+                let cell = Cell::from(index >> 8 == 1);
+                let neighbors = (index & 0xff).count_ones();
+                rule.next_state(cell, neighbors) as u8
+            })
+            .collect()
+    }
+
+    /// 设置边界的处理方式，默认为 Toroidal
+    pub fn set_boundary(&mut self, boundary: Boundary) {
+        self.boundary = boundary;
+    }
+
+    pub fn boundary(&self) -> Boundary {
+        self.boundary
+    }
+
+    /// 设置 tick 时保留的历史快照数量，0 表示不保留
+    pub fn set_snapshot_capacity(&mut self, capacity: u32) {
+        self.snapshot_capacity = capacity as usize;
+        self.snapshots.truncate(self.snapshot_capacity);
+    }
+
+    /// 开启后，可疑的操作（例如越界的坐标）会通过 `console.warn` 输出警告
+    pub fn set_verbose(&mut self, verbose: bool) {
+        self.verbose = verbose;
+    }
+
+    pub fn reset(&mut self) {
+        for cell in self.cells.iter_mut() {
+            *cell = Cell::Dead;
+        }
+        self.commit_edit();
+        self.generation = 0;
+        self.snapshots.clear();
+        #[cfg(feature = "cell-ages")]
+        self.ages.clear();
+        log!("Reset all Cells to Dead!");
+    }
+
+    /// 重新设置 Universe 自带的随机数生成器，之后的 `start` 等随机操作都可以复现
+    pub fn set_seed(&mut self, seed: u64) {
+        self.rng = UniverseRng::seeded(self.rng.kind(), seed);
+    }
+
+    pub fn start(&mut self) {
+        // 随机生成 Cell 状态
+        let rng = &mut self.rng;
+        let cells: Vec<Cell> = (0..self.width * self.height)
+            .map(|_| if rng.gen() { Cell::Alive } else { Cell::Dead })
+            .collect();
+
+        self.cells.copy_from_slice(&cells);
+        self.commit_edit();
+        self.generation = 0;
+        self.snapshots.clear();
+        #[cfg(feature = "cell-ages")]
+        self.ages.clear();
+    }
+
+    /// 清空所有 Cell，然后均匀随机地选择恰好 `count` 个不同的 Cell 设置为存活
+    /// `count` 超过 Cell 总数时返回错误
+    pub fn set_random_alive_cells(&mut self, count: u32, seed: u64) -> Result<(), UniverseError> {
+        let len = self.cells.len();
+        if count as usize > len {
+            return Err(UniverseError::InvalidArgument(format!(
+                "cannot place {} alive cells in a universe of {} cells",
+                count, len
+            )));
+        }
+
+        let mut rng = UniverseRng::seeded(self.rng.kind(), seed);
+        self.cells.iter_mut().for_each(|cell| *cell = Cell::Dead);
+        for index in rand::seq::index::sample(&mut rng, len, count as usize) {
+            self.cells[index] = Cell::Alive;
+        }
+        self.commit_edit();
+        self.generation = 0;
+
+        Ok(())
+    }
+
+    /// 承载量限制：存活数量超过 `max_alive` 时，使用 Universe 的随机数生成器
+    /// 均匀随机地杀死多出来的 Cell，使存活数量恰好为 `max_alive`
+    pub fn clamp_population(&mut self, max_alive: u32) {
+        let mut alive: Vec<usize> = (0..self.cells.len())
+            .filter(|&index| self.cells[index] == Cell::Alive)
+            .collect();
+        if alive.len() <= max_alive as usize {
+            return;
+        }
+
+        alive.shuffle(&mut self.rng);
+        for &index in alive[max_alive as usize..].iter() {
+            self.cells[index] = Cell::Dead;
+        }
+        self.commit_edit();
+    }
+
+    /// 扰动：以 `seed` 不重复地选出 `n_flips` 个 Cell 并翻转状态，
+    /// 恰好改变 `n_flips` 个 Cell（超过 Cell 总数时翻转全部）；
+    /// 相同的状态和种子总是翻转相同的 Cell，不影响 Universe 自带的随机数生成器
+    pub fn perturb(&mut self, n_flips: u32, seed: u64) {
+        let n_flips = (n_flips as usize).min(self.cells.len());
+        let mut rng = UniverseRng::seeded(self.rng.kind(), seed);
+        for index in rand::seq::index::sample(&mut rng, self.cells.len(), n_flips) {
+            self.cells[index].toggle();
+        }
+        self.commit_edit();
+    }
+
+    /// 撒播：以 `seed` 均匀随机地选出 `count` 个当前死亡的 Cell 设置为存活，已存活的 Cell 不受影响；
+    /// 死亡的 Cell 不足 `count` 个时全部设置为存活
+    pub fn sprinkle(&mut self, count: u32, seed: u64) {
+        let dead: Vec<usize> = (0..self.cells.len())
+            .filter(|&index| self.cells[index] == Cell::Dead)
+            .collect();
+        let count = (count as usize).min(dead.len());
+        let mut rng = UniverseRng::seeded(self.rng.kind(), seed);
+        for i in rand::seq::index::sample(&mut rng, dead.len(), count) {
+            self.cells[dead[i]] = Cell::Alive;
+        }
+        self.commit_edit();
+    }
+
+    /// 用 `op` 逐个 Cell 地把 `other` 合并到当前 Universe，按 (row, column) 对应；
+    /// 两者尺寸不同时返回错误，且不修改任何 Cell
+    pub fn merge(&mut self, other: &Universe, op: MergeOp) -> Result<(), UniverseError> {
+        if (other.width, other.height) != (self.width, self.height) {
+            return Err(UniverseError::InvalidArgument(format!(
+                "cannot merge a {}x{} universe into a {}x{} universe",
+                other.width, other.height, self.width, self.height
+            )));
+        }
+        for row in 0..self.height {
+            for column in 0..self.width {
+                let index = self.get_index(row, column);
+                let a = self.cells[index].is_alive();
+                let b = other.cells[other.get_index(row, column)].is_alive();
+                self.cells[index] = Cell::from(match op {
+                    MergeOp::Or => a || b,
+                    MergeOp::And => a && b,
+                    MergeOp::Xor => a != b,
+                    MergeOp::AndNot => a && !b,
+                });
+            }
+        }
+        self.commit_edit();
+        Ok(())
+    }
+
+    /// 与 `other` 交换状态：Cell（包括显示偏移和年龄）、墙、代数、规则以及历史快照、回退缓冲区和撤销栈，
+    /// 只交换 Vec 而不复制，耗时与尺寸无关。墙与 Cell 一样按缓冲区下标存放，随显示偏移一起交换才能
+    /// 留在原来的位置上。快照、回退缓冲区和撤销栈的容量、年龄上限、边界、视口等设置保持不变，
+    /// 交换来的帧和年龄超出本方设置的部分会被丢弃或截断。
+    /// 之后两者 `cells()` 的地址也互换。两者尺寸不同时返回错误，且不修改任何一方
+    pub fn swap(&mut self, other: &mut Universe) -> Result<(), UniverseError> {
+        if (other.width, other.height) != (self.width, self.height) {
+            return Err(UniverseError::InvalidArgument(format!(
+                "cannot swap a {}x{} universe with a {}x{} universe",
+                self.width, self.height, other.width, other.height
+            )));
+        }
+        std::mem::swap(&mut self.cells, &mut other.cells);
+        std::mem::swap(&mut self._cells, &mut other._cells);
+        std::mem::swap(&mut self.row_offset, &mut other.row_offset);
+        std::mem::swap(&mut self.col_offset, &mut other.col_offset);
+        std::mem::swap(&mut self.walls, &mut other.walls);
+        #[cfg(feature = "cell-ages")]
+        {
+            std::mem::swap(&mut self.ages, &mut other.ages);
+            for universe in [&mut *self, &mut *other] {
+                let cap = universe.age_cap;
+                universe
+                    .ages
+                    .iter_mut()
+                    .for_each(|age| *age = (*age).min(cap));
+            }
+        }
+        std::mem::swap(&mut self.generation, &mut other.generation);
+        std::mem::swap(&mut self.rule, &mut other.rule);
+        std::mem::swap(&mut self.rule_schedule, &mut other.rule_schedule);
+        std::mem::swap(&mut self.rule_table, &mut other.rule_table);
+        std::mem::swap(&mut self.snapshots, &mut other.snapshots);
+        self.snapshots.truncate(self.snapshot_capacity);
+        other.snapshots.truncate(other.snapshot_capacity);
+        self.rewind.swap_frames(&mut other.rewind);
+        self.undo.swap_edits(&mut other.undo);
+        std::mem::swap(&mut self.active_cells, &mut other.active_cells);
+        std::mem::swap(&mut self.stable, &mut other.stable);
+        std::mem::swap(&mut self.live, &mut other.live);
+        Ok(())
+    }
+
+    /// 按照每个 Cell 的存活概率 `probs` 重新填充 Universe，`seed` 决定随机序列
+    pub fn fill_from_mask(&mut self, probs: &[f32], seed: u64) -> Result<(), UniverseError> {
+        let len = (self.width * self.height) as usize;
+        if probs.len() != len {
+            return Err(UniverseError::LengthMismatch {
+                expected: len,
+                actual: probs.len(),
+            });
+        }
+
+        let mut rng = UniverseRng::seeded(self.rng.kind(), seed);
+        for (cell, &p) in self.cells.iter_mut().zip(probs) {
+            *cell = if rng.gen::<f32>() < p {
+                Cell::Alive
+            } else {
+                Cell::Dead
+            };
+        }
+        self.commit_edit();
+        self.generation = 0;
+
+        Ok(())
+    }
+}
+
+impl Universe {
+    /// 计算下一代并返回这一代的摘要，`tick` 与 `tick_status` 共用
+    fn step(&mut self) -> TickSummary {
         //let _time = Timer::new("Universe::tick");
         //let mut next = self.cells.clone();
+        let mut prev_population = 0;
+        let mut population = 0;
+        let mut active = 0;
+        let rule = self.current_rule();
+        #[cfg(feature = "cell-ages")]
+        self.ages.resize(self.cells.len(), 0);
+        self.live.clear();
         for row in 0..self.height {
             for column in 0..self.width {
                 let index = self.get_index(row, column);
                 let cell = self.cells[index];
-                let live_neighbors = self.live_neighbor_count(row, column);
 
                 //let state = cell;
 
-                let next_cell = match(cell, live_neighbors) {
-                    (Cell::Alive, x) if x < 2 => Cell::Dead,
-                    (Cell::Alive, 2) | (Cell::Alive, 3) => Cell::Alive,
-                    (Cell::Alive, x) if x > 3 => Cell::Dead,
-                    (Cell::Dead, 3) => Cell::Alive,
-                    (otherwise, _) => otherwise,
-                };
+                let next_cell = self.next_cell(rule, row, column);
 
                 // console.log
                 //if next_cell != state {
                 //    log!("the {} {} cell have transited from {:?} to {:?}", row, column, state, next_cell);
                 //}
 
+                prev_population += cell as u32;
+                population += next_cell as u32;
+                active += (next_cell != cell) as u32;
+                #[cfg(feature = "cell-ages")]
+                {
+                    self.ages[index] = if cell == Cell::Alive && next_cell == Cell::Alive {
+                        self.ages[index].saturating_add(1).min(self.age_cap)
+                    } else {
+                        0
+                    };
+                }
+
                 self._cells[index] = next_cell;
+                if next_cell == Cell::Alive {
+                    self.live.push(index as u32);
+                }
             }
         }
 
-        self.cells = self._cells.clone();
+        if self.snapshot_capacity > 0 {
+            self.snapshots.push_front(self.cells.clone());
+            self.snapshots.truncate(self.snapshot_capacity);
+        }
+        if self.rewind.is_enabled() {
+            let metrics = self.metrics();
+            self.rewind.push(
+                self.generation,
+                (self.width, self.height),
+                &self.cells,
+                metrics,
+            );
+        }
+        // 原地复制而不是替换 Vec，`cells()` 返回的地址在 tick 之间保持不变
+        self.cells.copy_from_slice(&self._cells);
+        self.generation += 1;
+        self.active_cells = active;
+        self.expand_if_touching_edge();
+
+        let summary = TickSummary {
+            generation: self.generation,
+            prev_population,
+            population,
+            changed: active > 0,
+            was_stable: self.stable,
+        };
+        self.stable = active == 0;
+        #[cfg(feature = "web")]
+        self.dispatch_events(&summary);
+        summary
     }
 
-    pub fn toggle_cell(&mut self, row: u32, column: u32) {
-        let index = self.get_index(row, column);
-        self.cells[index].toggle();
-        self._cells[index].toggle();
+    /// 支持的最大 Cell 数量：2^26
+    pub const MAX_CELLS: u32 = 1 << 26;
+
+    /// 宽和高都必须大于 0，且 Cell 总数不能超过 MAX_CELLS
+    pub fn validate_dimensions(width: u32, height: u32) -> Result<(), UniverseError> {
+        match width.checked_mul(height) {
+            Some(cells) if cells > 0 && cells <= Universe::MAX_CELLS => Ok(()),
+            _ => Err(UniverseError::InvalidDimensions { width, height }),
+        }
     }
 
-    pub fn reset(&mut self) {
-        for cell in self.cells.iter_mut() {
-            *cell = Cell::Dead;
+    /// 按顺序轮流使用多个规则：第 g 代的 tick 使用 `rules[g % rules.len()]`；
+    /// 传入空列表时恢复为 `set_rule` 设置的单一规则
+    pub fn set_rule_schedule(&mut self, rules: Vec<Rule>) {
+        self.rule_schedule = rules;
+        self.rule_table.clear();
+    }
+
+    /// 下一次 tick 将使用的规则
+    pub fn current_rule(&self) -> Rule {
+        if self.rule_schedule.is_empty() {
+            return self.rule;
         }
-        self._cells = self.cells.clone();
-        log!("Reset all Cells to Dead!");
+        let len = self.rule_schedule.len() as u64;
+        self.rule_schedule[(self.generation % len) as usize]
     }
 
-    pub fn start(&mut self) {
-        // 随机生成 Cell 状态
-        let cells: Vec<Cell> = (0..self.width * self.height)
-            .map(|_| {
-                if random() {
-                    Cell::Alive
-                } else {
-                    Cell::Dead
-                }
-            })
-            .collect();
+    /// 创建一个指定大小的 Universe，按行优先的顺序对每个 (row, column) 调用 `f` 得到它的状态
+    pub fn from_fn(
+        width: u32,
+        height: u32,
+        mut f: impl FnMut(u32, u32) -> Cell,
+    ) -> Result<Universe, UniverseError> {
+        Universe::try_from_fn(width, height, |row, column| Ok(f(row, column)))
+    }
 
-        self._cells = cells.clone();
-        self.cells = cells;
+    /// 与 `from_fn` 相同，但 `f` 可以失败，第一个错误会直接返回
+    pub(crate) fn try_from_fn<E: From<UniverseError>>(
+        width: u32,
+        height: u32,
+        mut f: impl FnMut(u32, u32) -> Result<Cell, E>,
+    ) -> Result<Universe, E> {
+        Universe::validate_dimensions(width, height)?;
+        let mut universe = Universe::with_size(width, height);
+        for row in 0..height {
+            for column in 0..width {
+                let index = universe.get_index(row, column);
+                universe.cells[index] = f(row, column)?;
+            }
+        }
+        universe.sync_cells();
+        Ok(universe)
     }
-}
 
-impl Universe {
-    fn live_neighbor_count(&self, row: u32, column: u32) -> u8 {
+    /// 创建一个指定大小、所有 Cell 均为死亡状态的 Universe，调用方负责保证尺寸合法
+    fn with_size(width: u32, height: u32) -> Universe {
+        let cells = vec![Cell::Dead; (width * height) as usize];
+        let _cells = cells.clone();
+
+        Universe {
+            width,
+            height,
+            cells,
+            _cells,
+            generation: 0,
+            #[cfg(feature = "web")]
+            callbacks: vec![],
+            row_offset: 0,
+            col_offset: 0,
+            verbose: false,
+            snapshots: VecDeque::new(),
+            snapshot_capacity: 0,
+            viewport: Viewport::full(width, height),
+            rule: Rule::CONWAY,
+            rule_schedule: vec![],
+            rule_table: vec![],
+            boundary: Boundary::Toroidal,
+            #[cfg(feature = "web")]
+            frame: None,
+            glyphs: Glyphs::default(),
+            walls: vec![],
+            walls_count_as_dead: true,
+            wrap_shift: (0, 0),
+            rng: UniverseRng::seeded(RngKind::Std, utils::initial_seed()),
+            #[cfg(feature = "cell-ages")]
+            ages: vec![],
+            #[cfg(feature = "cell-ages")]
+            age_cap: u32::MAX,
+            auto_expand: false,
+            rewind: RewindBuffer::default(),
+            undo: UndoStack::default(),
+            active_cells: 0,
+            live: vec![],
+            stable: false,
+            neighbor_counts: None,
+        }
+    }
+
+    /// 替换当前规则，同时清除 `set_rule_table` 设置的查找表
+    fn use_rule(&mut self, rule: Rule) {
+        self.rule = rule;
+        self.rule_table.clear();
+    }
+
+    /// 供内部模拟使用的副本：不带 JS 回调，不会触发事件；也不自动扩展、不记录回退帧和编辑，
+    /// 只读的分析不会把临时网格扩展得很大，也不会保存没人读取的帧和编辑
+    pub(crate) fn detached_clone(&self) -> Universe {
+        let mut universe = self.clone();
+        #[cfg(feature = "web")]
+        universe.callbacks.clear();
+        universe.auto_expand = false;
+        universe.rewind.set_capacity(0);
+        universe.undo.set_capacity(0);
+        universe
+    }
+
+    /// 坐标超出范围时返回 OutOfBounds
+    fn check_bounds(&self, row: u32, column: u32) -> Result<(), UniverseError> {
+        if row >= self.height || column >= self.width {
+            return Err(UniverseError::OutOfBounds {
+                row,
+                col: column,
+                width: self.width,
+                height: self.height,
+            });
+        }
+        Ok(())
+    }
+
+    /// verbose 模式下输出警告
+    fn warn(&self, message: &str) {
+        if self.verbose {
+            utils::warn(message);
+        }
+    }
+
+    fn live_neighbor_count(&self, row: u32, column: u32) -> u32 {
+        if self.boundary != Boundary::Toroidal || self.wrap_shift != (0, 0) {
+            return self.count_in_radius(row, column, 1);
+        }
+        if self.width < 3 || self.height < 3 {
+            return self.small_torus_live_neighbor_count(row, column);
+        }
+
         let mut count = 0;
 
         //  上下左右四个方位
-        let north = if row == 0 {
-            self.height - 1
-        } else {
-            row - 1
-        };
+        let north = if row == 0 { self.height - 1 } else { row - 1 };
 
-        let south = if row == self.height - 1 {
-            0
-        } else {
-            row + 1
-        };
+        let south = if row == self.height - 1 { 0 } else { row + 1 };
 
         let west = if column == 0 {
             self.width - 1
@@ -225,61 +1254,368 @@ impl Universe {
         };
 
         let n = self.get_index(north, column);
-        count += self.cells[n] as u8;
+        count += self.neighbor_value(n);
 
         let ne = self.get_index(north, east);
-        count += self.cells[ne] as u8;
+        count += self.neighbor_value(ne);
 
         let e = self.get_index(row, east);
-        count += self.cells[e] as u8;
+        count += self.neighbor_value(e);
 
         let se = self.get_index(south, east);
-        count += self.cells[se] as u8;
+        count += self.neighbor_value(se);
 
         let s = self.get_index(south, column);
-        count += self.cells[s] as u8;
+        count += self.neighbor_value(s);
 
         let ws = self.get_index(south, west);
-        count += self.cells[ws] as u8;
+        count += self.neighbor_value(ws);
 
         let w = self.get_index(row, west);
-        count += self.cells[w] as u8;
+        count += self.neighbor_value(w);
 
         let nw = self.get_index(north, west);
-        count += self.cells[nw] as u8;
+        count += self.neighbor_value(nw);
+
+        count
+    }
+
+    /// 宽或高小于 3 的环形宇宙中，八个方向的邻居会有重复，甚至包括自身；
+    /// 这里按照真正的环面处理：同一个 Cell 只计算一次，且不计算自身
+    fn small_torus_live_neighbor_count(&self, row: u32, column: u32) -> u32 {
+        let own = self.get_index(row, column);
+        let mut neighbors = Vec::with_capacity(8);
+        for dr in [self.height - 1, 0, 1] {
+            for dc in [self.width - 1, 0, 1] {
+                let index = self.get_index((row + dr) % self.height, (column + dc) % self.width);
+                if index != own && !neighbors.contains(&index) {
+                    neighbors.push(index);
+                }
+            }
+        }
+        neighbors
+            .iter()
+            .map(|&index| self.neighbor_value(index))
+            .sum()
+    }
 
+    /// 以 (row, column) 为中心、边长 2 * radius + 1 的正方形内（不含自身）存活的 Cell 数
+    /// 有限网格中，网格外的邻居按照 Boundary 视为死亡或存活；环形网格比正方形小时，
+    /// 绕回之后重复的 Cell 只计算一次，与 `small_torus_live_neighbor_count` 相同。
+    /// radius 较大时计数可能超过 255，因此使用 u32；
+    /// 邻域超过 `Rule::MAX_NEIGHBORS` 个 Cell 时规则无法表示，返回错误
+    pub fn live_neighbor_count_in_radius(
+        &self,
+        row: u32,
+        column: u32,
+        radius: u32,
+    ) -> Result<u32, UniverseError> {
+        self.check_bounds(row, column)?;
+        let side = 2 * radius as u64 + 1;
+        let (width, height) = (self.width as u64, self.height as u64);
+        let neighborhood = match self.boundary {
+            Boundary::Toroidal if self.wrap_shift == (0, 0) => side.min(width) * side.min(height),
+            Boundary::Toroidal => side.saturating_mul(side).min(width * height),
+            _ => side.saturating_mul(side),
+        } - 1;
+        if neighborhood > Rule::MAX_NEIGHBORS as u64 {
+            return Err(UniverseError::InvalidArgument(format!(
+                "a radius {} neighbourhood has {} cells, rules can count at most {}",
+                radius,
+                neighborhood,
+                Rule::MAX_NEIGHBORS
+            )));
+        }
+        Ok(self.count_in_radius(row, column, radius))
+    }
+
+    fn count_in_radius(&self, row: u32, column: u32, radius: u32) -> u32 {
+        let wraps_onto_itself = self.boundary == Boundary::Toroidal
+            && (2 * radius as u64 + 1 > self.width as u64
+                || 2 * radius as u64 + 1 > self.height as u64);
+        // 没有接缝错位时，超过一圈的偏移只会重复已经数过的行和列
+        let (row_radius, col_radius) = if wraps_onto_itself && self.wrap_shift == (0, 0) {
+            (
+                radius.min(self.height) as i64,
+                radius.min(self.width) as i64,
+            )
+        } else {
+            (radius as i64, radius as i64)
+        };
+        let own = self.get_index(row, column);
+        let mut seen = vec![];
+        let mut count = 0;
+        for dr in -row_radius..=row_radius {
+            for dc in -col_radius..=col_radius {
+                if dr == 0 && dc == 0 {
+                    continue;
+                }
+                count += match self.offset_index(row, column, dr, dc) {
+                    Some(index) if wraps_onto_itself => {
+                        if index == own || seen.contains(&index) {
+                            continue;
+                        }
+                        seen.push(index);
+                        self.neighbor_value(index)
+                    }
+                    Some(index) => self.neighbor_value(index),
+                    None => (self.boundary == Boundary::Alive) as u32,
+                };
+            }
+        }
         count
     }
 
-    /// 通过 row, column 获得在 self.cells 中的位置 id
+    /// (row, column) 在 `rule` 下一代的状态：墙保持不变，设置了查找表时使用查找表
+    pub(crate) fn next_cell(&self, rule: Rule, row: u32, column: u32) -> Cell {
+        let index = self.get_index(row, column);
+        let cell = self.cells[index];
+        if self.is_wall_index(index) {
+            cell
+        } else if !self.rule_table.is_empty() {
+            let neighbors = self.neighbor_mask(row, column) as usize;
+            Cell::from(self.rule_table[(cell as usize) << 8 | neighbors] == 1)
+        } else {
+            rule.next_state(cell, self.live_neighbor_count(row, column))
+        }
+    }
+
+    /// 八个邻居是否存活的位掩码，位的顺序见 `set_rule_table`
+    fn neighbor_mask(&self, row: u32, column: u32) -> u32 {
+        // 与 `small_torus_live_neighbor_count` 一致：宽或高小于 3 的环形宇宙中，
+        // 同一个 Cell 只占第一个指向它的位，指向自身的位为 0
+        let small_torus = self.boundary == Boundary::Toroidal
+            && self.wrap_shift == (0, 0)
+            && (self.width < 3 || self.height < 3);
+        let mut seen = [self.get_index(row, column); 9];
+        let mut seen_len = 1;
+        let mut mask = 0;
+        let mut bit = 0;
+        for dr in -1..=1 {
+            for dc in -1..=1 {
+                if dr == 0 && dc == 0 {
+                    continue;
+                }
+                let alive = match self.offset_index(row, column, dr, dc) {
+                    Some(index) if small_torus && seen[..seen_len].contains(&index) => 0,
+                    Some(index) => {
+                        seen[seen_len] = index;
+                        seen_len += 1;
+                        self.neighbor_value(index)
+                    }
+                    None => (self.boundary == Boundary::Alive) as u32,
+                };
+                mask |= alive << bit;
+                bit += 1;
+            }
+        }
+        mask
+    }
+
+    /// (row + dr, column + dc) 在 self.cells 中的位置，环形宇宙中会绕回（包括接缝错位），
+    /// 有限网格中超出范围时返回 None
+    pub(crate) fn offset_index(&self, row: u32, column: u32, dr: i64, dc: i64) -> Option<usize> {
+        let (height, width) = (self.height as i64, self.width as i64);
+        let (mut r, mut c) = (row as i64 + dr, column as i64 + dc);
+        if self.boundary == Boundary::Toroidal {
+            // 先处理左右接缝，再处理上下接缝，每跨过一次都叠加一次错位
+            let (row_shift, col_shift) = self.wrap_shift;
+            r += c.div_euclid(width) * row_shift as i64;
+            c = c.rem_euclid(width);
+            c += r.div_euclid(height) * col_shift as i64;
+            r = r.rem_euclid(height);
+            c = c.rem_euclid(width);
+        } else if r < 0 || c < 0 || r >= height || c >= width {
+            return None;
+        }
+        Some(self.get_index(r as u32, c as u32))
+    }
+
+    /// 通过 row, column 获得在 self.cells 中的位置 id（已应用环形偏移）
     fn get_index(&self, row: u32, column: u32) -> usize {
+        let row = (row + self.row_offset) % self.height;
+        let column = (column + self.col_offset) % self.width;
         (row * self.width + column) as usize
     }
 
-    /// 获取 self.cells 
+    /// 获取 self.cells
     pub fn get_cells(&self) -> &[Cell] {
         &self.cells
     }
 
-    /// 将数组中的 Cell 设置为存活状态
+    /// 从上到下逐行遍历，每一项是一行的 Cell，与 `get`、`Display` 一样应用了环形偏移；
+    /// 有偏移时一行在 `get_cells` 中不一定是连续的一段，因此每行复制为一个 Vec
+    pub fn rows(&self) -> impl Iterator<Item = Vec<Cell>> + '_ {
+        (0..self.height).map(move |row| {
+            (0..self.width)
+                .map(|column| self.cells[self.get_index(row, column)])
+                .collect()
+        })
+    }
+
+    /// 读取 (row, column) 处的 Cell，越界时返回 None
+    pub fn get(&self, row: u32, column: u32) -> Option<Cell> {
+        if row >= self.height || column >= self.width {
+            return None;
+        }
+        Some(self.cells[self.get_index(row, column)])
+    }
+
+    /// 当前所有 Cell 状态的哈希值，相同的状态一定得到相同的哈希值
+    pub fn state_hash(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        self.cells.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// 连续 tick `steps` 次，每 `every` 次之后调用一次 `cb(generation)`（`every` 为 0 时不调用），
+    /// 原生平台上模拟大网格时可以借此报告进度；Wasm 中请分批调用 `tick_many_stats`
+    pub fn tick_many_with_callback<F: FnMut(u64)>(&mut self, steps: u32, every: u32, mut cb: F) {
+        for step in 1..=steps {
+            self.step();
+            if every > 0 && step % every == 0 {
+                cb(self.generation);
+            }
+        }
+    }
+
+    /// 持续 tick 直到 Universe 进入某个循环，最多 `max_steps` 次
+    /// 返回 (循环开始的代数, 周期)；哈希相同时会再比较完整状态以排除碰撞。
+    /// 经过的每一代按位打包保存，每个 Cell 只占 1 比特，`run_soup_search` 对每锅汤都会调用
+    pub fn run_until_cycle(&mut self, max_steps: u32) -> Option<(u32, u32)> {
+        let mut seen: HashMap<u64, Vec<(u64, Vec<u8>)>> = HashMap::new();
+        seen.entry(self.state_hash())
+            .or_default()
+            .push((self.generation, rewind::pack(&self.cells)));
+
+        for _ in 0..max_steps {
+            self.tick();
+
+            let packed = rewind::pack(&self.cells);
+            let states = seen.entry(self.state_hash()).or_default();
+            if let Some((start, _)) = states.iter().find(|(_, state)| *state == packed) {
+                return Some((*start as u32, (self.generation - start) as u32));
+            }
+            states.push((self.generation, packed));
+        }
+
+        None
+    }
+
+    /// 将数组中的 Cell 设置为存活状态：只会让列出的 Cell 存活，不会清除任何已有的 Cell，
+    /// 超出范围的坐标会被忽略。需要写入精确状态时使用 `set_cells_state`
     pub fn set_cells(&mut self, cells: &[(u32, u32)]) {
+        self.set_cells_alive(cells);
+    }
+
+    /// 将每个 (row, column) 设置为给定的状态，未列出的 Cell 保持不变；
+    /// 任何一项越界时返回包含该项下标的错误，且不修改任何 Cell
+    pub fn set_cells_state(&mut self, cells: &[(u32, u32, Cell)]) -> Result<(), UniverseError> {
+        let outside = cells
+            .iter()
+            .position(|&(row, column, _)| row >= self.height || column >= self.width);
+        if let Some(i) = outside {
+            let (row, column, _) = cells[i];
+            return Err(UniverseError::InvalidArgument(format!(
+                "entry {}: cell ({}, {}) is out of bounds for a {}x{} universe",
+                i, row, column, self.width, self.height
+            )));
+        }
+
+        let edits: Vec<(usize, Cell)> = cells
+            .iter()
+            .map(|&(row, column, state)| (self.get_index(row, column), state))
+            .collect();
+        self.commit_cells_edit(&edits);
+        Ok(())
+    }
+
+    /// 将数组中的 Cell 设置为存活状态，超出范围的坐标会被忽略
+    pub fn set_cells_alive(&mut self, cells: &[(u32, u32)]) {
+        self.set_cells_to(cells, Cell::Alive);
+    }
+
+    /// 将数组中的 Cell 设置为死亡状态，超出范围的坐标会被忽略
+    pub fn set_cells_dead(&mut self, cells: &[(u32, u32)]) {
+        self.set_cells_to(cells, Cell::Dead);
+    }
+
+    /// 以 (row, column) 为左上角放置图案，图案必须完整地位于 Universe 内
+    /// 图案超出范围时返回图案右下角的坐标
+    pub fn stamp_pattern(
+        &mut self,
+        pattern: &Pattern,
+        row: u32,
+        column: u32,
+    ) -> Result<(), UniverseError> {
+        if row as u64 + pattern.height as u64 > self.height as u64
+            || column as u64 + pattern.width as u64 > self.width as u64
+        {
+            return Err(UniverseError::OutOfBounds {
+                row: row.saturating_add(pattern.height.saturating_sub(1)),
+                col: column.saturating_add(pattern.width.saturating_sub(1)),
+                width: self.width,
+                height: self.height,
+            });
+        }
+
+        let cells: Vec<(u32, u32, Cell)> = pattern
+            .cells
+            .iter()
+            .map(|&(r, c)| (row + r, column + c, Cell::Alive))
+            .collect();
+        self.set_cells_state(&cells)
+    }
+
+    fn set_cells_to(&mut self, cells: &[(u32, u32)], state: Cell) {
+        let mut edits = Vec::with_capacity(cells.len());
         for (row, column) in cells.iter().cloned() {
-            let index = self.get_index(row, column);
-            self.cells[index] = Cell::Alive;
+            if row >= self.height || column >= self.width {
+                self.warn(&format!(
+                    "ignoring out-of-bounds cell ({}, {}) in a {}x{} universe",
+                    row, column, self.height, self.width
+                ));
+                continue;
+            }
+            edits.push((self.get_index(row, column), state));
         }
+        self.commit_cells_edit(&edits);
     }
 }
 
-impl fmt::Display for Universe {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        for line in self.cells.as_slice().chunks(self.width as usize) {
-            for &cell in line {
-                let symbol = if cell == Cell::Dead { '◻' } else { '◼' };
-                write!(f, "{}", symbol)?;
-            }
-            write!(f, "\n")?;
+impl Default for Universe {
+    fn default() -> Universe {
+        Universe::new()
+    }
+}
+
+/// `universe[(row, column)]` 读取一个 Cell，越界时与标准库集合一样 panic，
+/// 不 panic 的版本是 `get`。
+/// 没有实现 IndexMut：写入 Cell 时需要同步 `_cells` 等内部状态，
+/// 无法通过交出 `&mut Cell` 做到，写入请使用 `set_cell`
+impl std::ops::Index<(u32, u32)> for Universe {
+    type Output = Cell;
+
+    fn index(&self, (row, column): (u32, u32)) -> &Cell {
+        if row >= self.height || column >= self.width {
+            panic!(
+                "index ({}, {}) out of bounds for a {}x{} universe",
+                row, column, self.width, self.height
+            );
         }
+        &self.cells[self.get_index(row, column)]
+    }
+}
 
-        Ok(())
+/// 只输出摘要信息，而不是所有 Cell
+impl fmt::Debug for Universe {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Universe")
+            .field("width", &self.width)
+            .field("height", &self.height)
+            .field("generation", &self.generation)
+            .field("population", &self.population())
+            .field("rule", &self.rule.to_string())
+            .finish()
     }
 }
@@ -0,0 +1,277 @@
+#[cfg(feature = "web")]
+use wasm_bindgen::prelude::*;
+
+use crate::rle::check_size;
+use crate::{Pattern, Universe, UniverseError};
+
+/// 四叉树节点：level 3 的叶子是 8x8 的 Cell，每行一个字节；
+/// 更高的 level k 节点边长 2^k，按 nw、ne、sw、se 引用之前的节点，0 表示全空
+enum Node {
+    Leaf([u8; 8]),
+    Inner { level: u32, children: [usize; 4] },
+}
+
+impl Node {
+    fn level(&self) -> u32 {
+        match self {
+            Node::Leaf(_) => LEAF_LEVEL,
+            Node::Inner { level, .. } => *level,
+        }
+    }
+}
+
+/// 叶子节点的 level（8x8）
+const LEAF_LEVEL: u32 = 3;
+/// 坐标使用 u64，根节点的边长不能超过 2^63
+const MAX_LEVEL: u32 = 63;
+
+/// 存活 Cell 的外接矩形 (top, left, bottom, right)，包含两端
+type Bounds = (u64, u64, u64, u64);
+
+/// 解析 Golly 的 Macrocell（`[M2]`）格式，例如两个滑翔机：
+/// ```text
+/// [M2] (golly 4.2)
+/// #R B3/S23
+/// .*$..*$***$
+/// 4 1 0 0 1
+/// ```
+/// 节点从 1 开始按出现顺序编号，最后一个节点是根。四叉树可以描述数十亿个 Cell，
+/// 这里只展开存活 Cell 的外接矩形：结果裁剪到这个矩形，矩形超过 `Universe::MAX_CELLS` 时报错。
+/// 只支持两种状态的规则，`#` 开头的行（包括 #R、#G）被忽略
+pub fn parse_macrocell(s: &str) -> Result<Pattern, UniverseError> {
+    let mut lines = s
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty());
+    match lines.next() {
+        Some((_, line)) if line.trim_start().starts_with("[M2]") => {}
+        Some((line_no, _)) => {
+            return Err(UniverseError::ParseError {
+                line: line_no + 1,
+                message: "missing [M2] header".to_string(),
+            })
+        }
+        None => return Ok(Pattern::default()),
+    }
+
+    let mut nodes: Vec<Node> = vec![];
+    let mut bounds: Vec<Option<Bounds>> = vec![];
+    let mut root_line = 0;
+    for (line_no, line) in lines {
+        let line = line.trim();
+        if line.starts_with('#') {
+            continue;
+        }
+        let error = |message: String| UniverseError::ParseError {
+            line: line_no + 1,
+            message,
+        };
+        let node = if line.starts_with(['.', '*', '$']) {
+            parse_leaf(line).map_err(error)?
+        } else {
+            parse_inner(line, &nodes).map_err(error)?
+        };
+        bounds.push(node_bounds(&node, &bounds));
+        nodes.push(node);
+        root_line = line_no + 1;
+    }
+
+    let (top, left, bottom, right) = match bounds.last() {
+        Some(Some(root)) => *root,
+        _ => return Ok(Pattern::default()),
+    };
+    let (width, height) = (right - left + 1, bottom - top + 1);
+    check_size(width, height).map_err(|message| UniverseError::ParseError {
+        line: root_line,
+        message,
+    })?;
+
+    let mut pattern = Pattern {
+        width: width as u32,
+        height: height as u32,
+        cells: vec![],
+    };
+    expand(&nodes, &bounds, nodes.len(), (0, 0), &mut |row, column| {
+        pattern
+            .cells
+            .push(((row - top) as u32, (column - left) as u32))
+    });
+    pattern.cells.sort_unstable();
+    Ok(pattern)
+}
+
+/// 叶子节点：`.` 为死亡，`*` 为存活，`$` 结束一行；行尾的死亡 Cell 和末尾的空行可以省略
+fn parse_leaf(line: &str) -> Result<Node, String> {
+    let mut rows = [0u8; 8];
+    let (mut row, mut column) = (0, 0);
+    for c in line.chars() {
+        if row >= 8 {
+            return Err("leaf node has more than 8 rows".to_string());
+        }
+        match c {
+            '$' => {
+                row += 1;
+                column = 0;
+                continue;
+            }
+            '*' | '.' if column >= 8 => return Err("leaf row is longer than 8 cells".to_string()),
+            '*' => rows[row] |= 1 << column,
+            '.' => {}
+            c => return Err(format!("unexpected character '{}'", c)),
+        }
+        column += 1;
+    }
+    Ok(Node::Leaf(rows))
+}
+
+/// 内部节点：`level nw ne sw se`
+fn parse_inner(line: &str, nodes: &[Node]) -> Result<Node, String> {
+    let fields: Vec<&str> = line.split_whitespace().collect();
+    if fields.len() != 5 {
+        return Err(format!("expected 'level nw ne sw se', found '{}'", line));
+    }
+    let level: u32 = fields[0]
+        .parse()
+        .map_err(|_| format!("invalid level '{}'", fields[0]))?;
+    if level == 1 {
+        return Err("multi-state patterns are not supported".to_string());
+    }
+    if level <= LEAF_LEVEL || level > MAX_LEVEL {
+        return Err(format!(
+            "level {} is not between {} and {}",
+            level,
+            LEAF_LEVEL + 1,
+            MAX_LEVEL
+        ));
+    }
+
+    let mut children = [0usize; 4];
+    for (child, field) in children.iter_mut().zip(&fields[1..]) {
+        *child = field
+            .parse()
+            .map_err(|_| format!("invalid node index '{}'", field))?;
+        match nodes.get(child.wrapping_sub(1)) {
+            _ if *child == 0 => {}
+            Some(node) if node.level() == level - 1 => {}
+            Some(node) => {
+                return Err(format!(
+                    "node {} has level {}, expected {}",
+                    child,
+                    node.level(),
+                    level - 1
+                ))
+            }
+            None => return Err(format!("node {} is not defined yet", child)),
+        }
+    }
+    Ok(Node::Inner { level, children })
+}
+
+/// 节点内存活 Cell 的外接矩形（相对节点左上角），子节点的结果已经在 `bounds` 中
+fn node_bounds(node: &Node, bounds: &[Option<Bounds>]) -> Option<Bounds> {
+    match node {
+        Node::Leaf(rows) => {
+            let mut live_rows = (0..8u64).filter(|&r| rows[r as usize] != 0);
+            let top = live_rows.next()?;
+            let columns = rows.iter().fold(0u8, |acc, row| acc | row);
+            Some((
+                top,
+                columns.trailing_zeros() as u64,
+                live_rows.next_back().unwrap_or(top),
+                7 - columns.leading_zeros() as u64,
+            ))
+        }
+        Node::Inner { level, children } => {
+            let half = 1u64 << (level - 1);
+            children
+                .iter()
+                .zip(quadrant_offsets(half))
+                .filter(|&(&child, _)| child != 0)
+                .filter_map(|(&child, (dr, dc))| {
+                    bounds[child - 1].map(|(t, l, b, r)| (t + dr, l + dc, b + dr, r + dc))
+                })
+                .reduce(|(t0, l0, b0, r0), (t1, l1, b1, r1)| {
+                    (t0.min(t1), l0.min(l1), b0.max(b1), r0.max(r1))
+                })
+        }
+    }
+}
+
+fn quadrant_offsets(half: u64) -> [(u64, u64); 4] {
+    [(0, 0), (0, half), (half, 0), (half, half)]
+}
+
+/// 以 `origin` 为左上角展开第 `index` 个节点（从 1 开始），跳过没有存活 Cell 的子树
+fn expand(
+    nodes: &[Node],
+    bounds: &[Option<Bounds>],
+    index: usize,
+    origin: (u64, u64),
+    visit: &mut impl FnMut(u64, u64),
+) {
+    if index == 0 || bounds[index - 1].is_none() {
+        return;
+    }
+    match &nodes[index - 1] {
+        Node::Leaf(rows) => {
+            for (r, &bits) in rows.iter().enumerate() {
+                for c in (0..8).filter(|c| bits & (1 << c) != 0) {
+                    visit(origin.0 + r as u64, origin.1 + c as u64);
+                }
+            }
+        }
+        Node::Inner { level, children } => {
+            let half = 1u64 << (level - 1);
+            for (&child, (dr, dc)) in children.iter().zip(quadrant_offsets(half)) {
+                expand(nodes, bounds, child, (origin.0 + dr, origin.1 + dc), visit);
+            }
+        }
+    }
+}
+
+#[cfg_attr(feature = "web", wasm_bindgen)]
+impl Universe {
+    /// 解析 Macrocell 并以 (row, column) 为左上角粘贴到当前网格上，规则与 `stamp_rle` 相同
+    pub fn stamp_macrocell(
+        &mut self,
+        macrocell: &str,
+        row: u32,
+        column: u32,
+    ) -> Result<(), UniverseError> {
+        self.check_bounds(row, column)?;
+        let pattern = parse_macrocell(macrocell)?;
+        self.stamp_wrapped(&pattern, row, column)
+    }
+
+    /// 由 Macrocell 文件创建一个新的 Universe：尺寸为存活 Cell 的外接矩形（见 `parse_macrocell`），
+    /// 有 `#R` 行时使用其中的规则（B/S 或 MAP 记法，同 `set_rule`），否则为康威规则；
+    /// 有 `#G` 行时从其中的代数开始；其它设置均为默认值。没有存活 Cell 时无法确定尺寸，返回错误
+    pub fn import_macrocell(macrocell: &str) -> Result<Universe, UniverseError> {
+        let pattern = parse_macrocell(macrocell)?;
+        if pattern.cells.is_empty() {
+            return Err(UniverseError::InvalidArgument(
+                "macrocell pattern has no live cells".to_string(),
+            ));
+        }
+        let mut universe = Universe::with_size(pattern.width, pattern.height);
+        if let Some(rule) = macrocell
+            .lines()
+            .find_map(|line| line.trim().strip_prefix("#R"))
+        {
+            universe.set_rule(rule.trim())?;
+        }
+        if let Some(generation) = macrocell
+            .lines()
+            .find_map(|line| line.trim().strip_prefix("#G"))
+        {
+            universe.generation = generation.trim().parse().map_err(|_| {
+                UniverseError::InvalidArgument(format!(
+                    "invalid generation {:?}",
+                    generation.trim()
+                ))
+            })?;
+        }
+        universe.stamp_wrapped(&pattern, 0, 0)?;
+        Ok(universe)
+    }
+}
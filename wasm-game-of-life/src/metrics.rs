@@ -0,0 +1,59 @@
+use std::collections::HashMap;
+
+use crate::{Cell, Universe};
+
+/// `block_entropy` 使用的方块边长
+const BLOCK: u32 = 4;
+
+/// 统计面板使用的一组指标，`Copy` 且很小；开启 `enable_rewind` 后每一代的指标
+/// 随回退缓冲区一起记录，见 `get_metrics_timeline`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Metrics {
+    /// 存活数量 / Cell 总数
+    pub density: f64,
+    /// 4×4 方块图案的香农熵，单位为比特，见 `Universe::block_entropy`
+    pub entropy: f64,
+    /// 上一次 tick 中状态发生变化的 Cell 数量，还没有 tick 过或者之后编辑过 Cell 时为 0
+    pub active_cells: u32,
+}
+
+impl Universe {
+    /// 当前状态的密度、方块熵以及上一次 tick 的活跃 Cell 数量；
+    /// 活跃数量在 tick 时顺便统计，另外两项在调用时计算，耗时与 Cell 数量成正比
+    pub fn metrics(&self) -> Metrics {
+        Metrics {
+            density: self.population() as f64 / self.cells.len() as f64,
+            entropy: self.block_entropy(),
+            active_cells: self.active_cells,
+        }
+    }
+
+    /// 把网格切成 4×4 的方块，每个方块的 16 个 Cell 组成一个图案，返回图案分布的香农熵（比特）。
+    /// 空的或者均匀的网格为 0；图案越杂乱越大，上限为 16 和 log2(方块数) 中较小的一个。
+    /// 宽或高不是 4 的倍数时，最右边和最下边不足一个方块的部分被忽略；不足一个方块时为 0
+    pub fn block_entropy(&self) -> f64 {
+        let (rows, cols) = (self.height / BLOCK, self.width / BLOCK);
+        let blocks = (rows * cols) as f64;
+        let mut counts: HashMap<u16, u32> = HashMap::new();
+        for block_row in 0..rows {
+            for block_col in 0..cols {
+                let mut pattern = 0u16;
+                for r in 0..BLOCK {
+                    for c in 0..BLOCK {
+                        let index = self.get_index(block_row * BLOCK + r, block_col * BLOCK + c);
+                        pattern = pattern << 1 | (self.cells[index] == Cell::Alive) as u16;
+                    }
+                }
+                *counts.entry(pattern).or_insert(0) += 1;
+            }
+        }
+        counts
+            .values()
+            .map(|&count| {
+                let p = count as f64 / blocks;
+                -p * p.log2()
+            })
+            .sum::<f64>()
+            .max(0.0)
+    }
+}
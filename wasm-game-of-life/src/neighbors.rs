@@ -0,0 +1,79 @@
+#[cfg(feature = "web")]
+use wasm_bindgen::prelude::*;
+
+use crate::{Boundary, Cell, Universe};
+
+/// 影响邻居数的设置：尺寸、偏移、边界、接缝错位以及墙是否视为死亡
+type Settings = (u32, u32, u32, u32, Boundary, (i32, i32), bool);
+
+/// `neighbor_counts_ptr` 缓存的邻居数，连同计算时的状态一起保存，
+/// 这样不需要在每个修改 Cell 的方法里使缓存失效，取用时比较一下即可
+#[derive(Clone, Debug)]
+pub(crate) struct NeighborCounts {
+    settings: Settings,
+    cells: Vec<Cell>,
+    walls: Vec<bool>,
+    counts: Vec<u8>,
+}
+
+#[cfg_attr(feature = "web", wasm_bindgen)]
+impl Universe {
+    /// 每个 Cell 的存活邻居数（0 到 8），与 `cells()` 的布局相同，每个 Cell 一个字节，
+    /// 共 `cells_byte_len` 字节，可以直接作为纹理上传给着色器。结果会被缓存，
+    /// 任何修改（tick、编辑、改变边界等）之后的下一次调用会重新计算；
+    /// 尺寸不变时重新计算会复用同一块内存，但持有的视图只在下一次修改之前有效
+    pub fn neighbor_counts_ptr(&mut self) -> *const u8 {
+        let settings = self.neighbor_settings();
+        let fresh = match &self.neighbor_counts {
+            Some(cache) => {
+                cache.settings == settings && cache.cells == self.cells && cache.walls == self.walls
+            }
+            None => false,
+        };
+        if !fresh {
+            let mut cache = self.neighbor_counts.take().unwrap_or(NeighborCounts {
+                settings,
+                cells: vec![],
+                walls: vec![],
+                counts: vec![],
+            });
+            cache.counts.resize(self.cells.len(), 0);
+            for row in 0..self.height {
+                for column in 0..self.width {
+                    let index = self.get_index(row, column);
+                    cache.counts[index] = self.live_neighbor_count(row, column) as u8;
+                }
+            }
+            cache.settings = settings;
+            cache.cells.clone_from(&self.cells);
+            cache.walls.clone_from(&self.walls);
+            self.neighbor_counts = Some(cache);
+        }
+        self.neighbor_counts.as_ref().unwrap().counts.as_ptr()
+    }
+
+    /// 第 k 项为恰好有 k 个存活邻居的 Cell 数量，共 9 项
+    pub fn neighbor_histogram(&self) -> Vec<u32> {
+        let mut histogram = vec![0; 9];
+        for row in 0..self.height {
+            for column in 0..self.width {
+                histogram[self.live_neighbor_count(row, column) as usize] += 1;
+            }
+        }
+        histogram
+    }
+}
+
+impl Universe {
+    fn neighbor_settings(&self) -> Settings {
+        (
+            self.width,
+            self.height,
+            self.row_offset,
+            self.col_offset,
+            self.boundary,
+            self.wrap_shift,
+            self.walls_count_as_dead,
+        )
+    }
+}
@@ -0,0 +1,44 @@
+#[cfg(feature = "web")]
+use wasm_bindgen::prelude::*;
+
+use crate::{rle, Universe, UniverseError};
+
+/// 内置的图案，以 RLE 格式嵌入到二进制中
+const PATTERNS: &[(&str, &str)] = &[
+    ("blinker", include_str!("patterns/blinker.rle")),
+    ("glider", include_str!("patterns/glider.rle")),
+    ("lwss", include_str!("patterns/lwss.rle")),
+    ("pulsar", include_str!("patterns/pulsar.rle")),
+    (
+        "pentadecathlon",
+        include_str!("patterns/pentadecathlon.rle"),
+    ),
+    (
+        "gosper_glider_gun",
+        include_str!("patterns/gosper_glider_gun.rle"),
+    ),
+    ("r_pentomino", include_str!("patterns/r_pentomino.rle")),
+];
+
+/// 按名称查找内置图案的 RLE
+pub(crate) fn find(name: &str) -> Option<&'static str> {
+    PATTERNS
+        .iter()
+        .find(|(pattern, _)| *pattern == name)
+        .map(|(_, rle)| *rle)
+}
+
+#[cfg_attr(feature = "web", wasm_bindgen)]
+impl Universe {
+    /// 所有内置图案的名称，可以传给 `load_pattern`
+    pub fn available_patterns() -> Vec<String> {
+        PATTERNS.iter().map(|(name, _)| name.to_string()).collect()
+    }
+
+    /// 以 (row, column) 为左上角放置一个内置图案，其余 Cell 保持不变
+    pub fn load_pattern(&mut self, name: &str, row: u32, column: u32) -> Result<(), UniverseError> {
+        let rle = find(name).ok_or_else(|| UniverseError::UnknownPattern(name.to_string()))?;
+        let pattern = rle::parse_rle(rle)?;
+        self.stamp_pattern(&pattern, row, column)
+    }
+}
@@ -0,0 +1,463 @@
+#[cfg(feature = "web")]
+use wasm_bindgen::prelude::*;
+
+use std::convert::TryFrom;
+use std::str::SplitWhitespace;
+
+use crate::{utils, Boundary, Cell, Universe, UniverseError};
+
+/// 操作日志的第一行，后面跟着宽、高和随机数种子
+const LOG_HEADER: &str = "gol-log 1";
+
+/// 一次会改变 Universe 的操作；依赖随机数的操作（start、clamp）
+/// 按原来的顺序重放，加上日志头部记录的种子就能得到完全相同的结果
+#[derive(Clone, Debug, PartialEq)]
+enum Op {
+    Seed(u64),
+    Start,
+    Reset,
+    Resize(u32, u32),
+    Toggle(u32, u32),
+    Set(u32, u32, Cell),
+    Cells(Vec<(u32, u32, Cell)>),
+    Rule(String),
+    Boundary(Boundary),
+    Wall(u32, u32, bool),
+    Stamp(u32, u32, String),
+    Mirror,
+    Tick(u32),
+    Perturb(u32, u64),
+    Sprinkle(u32, u64),
+    Random(u32, u64),
+    Clamp(u32),
+}
+
+impl Op {
+    fn apply(&self, universe: &mut Universe) -> Result<(), UniverseError> {
+        match self {
+            Op::Seed(seed) => universe.set_seed(*seed),
+            Op::Start => universe.start(),
+            Op::Reset => universe.reset(),
+            Op::Resize(width, height) => universe.resize(*width, *height)?,
+            Op::Toggle(row, column) => universe.toggle_cell(*row, *column)?,
+            Op::Set(row, column, cell) => universe.set_cell(*row, *column, *cell)?,
+            Op::Cells(cells) => universe.set_cells_state(cells)?,
+            Op::Rule(rule) => universe.set_rule(rule)?,
+            Op::Boundary(boundary) => universe.set_boundary(*boundary),
+            Op::Wall(row, column, wall) => universe.set_wall(*row, *column, *wall)?,
+            Op::Stamp(row, column, rle) => universe.stamp_rle(rle, *row, *column)?,
+            Op::Mirror => universe.mirror_to_quadrants(),
+            Op::Tick(n) => {
+                for _ in 0..*n {
+                    universe.tick();
+                }
+            }
+            Op::Perturb(n, seed) => universe.perturb(*n, *seed),
+            Op::Sprinkle(n, seed) => universe.sprinkle(*n, *seed),
+            Op::Random(n, seed) => universe.set_random_alive_cells(*n, *seed)?,
+            Op::Clamp(n) => universe.clamp_population(*n),
+        }
+        Ok(())
+    }
+
+    /// 日志中的一行（不含时间戳），RLE 中的换行和反斜杠会被转义
+    fn to_line(&self) -> String {
+        match self {
+            Op::Seed(seed) => format!("seed {}", seed),
+            Op::Start => "start".to_string(),
+            Op::Reset => "reset".to_string(),
+            Op::Resize(width, height) => format!("resize {} {}", width, height),
+            Op::Toggle(row, column) => format!("toggle {} {}", row, column),
+            Op::Set(row, column, cell) => format!("set {} {} {}", row, column, *cell as u8),
+            Op::Cells(cells) => {
+                let mut line = "cells".to_string();
+                for &(row, column, state) in cells {
+                    line.push_str(&format!(" {} {} {}", row, column, state as u8));
+                }
+                line
+            }
+            Op::Rule(rule) => format!("rule {}", rule),
+            Op::Boundary(boundary) => format!("boundary {}", *boundary as u8),
+            Op::Wall(row, column, wall) => format!("wall {} {} {}", row, column, *wall as u8),
+            Op::Stamp(row, column, rle) => format!(
+                "stamp {} {} {}",
+                row,
+                column,
+                rle.replace('\\', "\\\\").replace('\n', "\\n")
+            ),
+            Op::Mirror => "mirror".to_string(),
+            Op::Tick(n) => format!("tick {}", n),
+            Op::Perturb(n, seed) => format!("perturb {} {}", n, seed),
+            Op::Sprinkle(n, seed) => format!("sprinkle {} {}", n, seed),
+            Op::Random(n, seed) => format!("random {} {}", n, seed),
+            Op::Clamp(n) => format!("clamp {}", n),
+        }
+    }
+
+    fn parse(line: &str) -> Result<Op, String> {
+        let (name, rest) = line.split_once(' ').unwrap_or((line, ""));
+        let mut args = Args(rest.split_whitespace());
+        let op = match name {
+            "seed" => Op::Seed(args.u64("seed")?),
+            "start" => Op::Start,
+            "reset" => Op::Reset,
+            "resize" => Op::Resize(args.u32("width")?, args.u32("height")?),
+            "toggle" => Op::Toggle(args.u32("row")?, args.u32("column")?),
+            "set" => Op::Set(
+                args.u32("row")?,
+                args.u32("column")?,
+                Cell::from(args.flag("cell state")?),
+            ),
+            "boundary" => Op::Boundary(match args.u32("boundary")? {
+                0 => Boundary::Toroidal,
+                1 => Boundary::Dead,
+                2 => Boundary::Alive,
+                other => return Err(format!("invalid boundary {}", other)),
+            }),
+            "wall" => Op::Wall(args.u32("row")?, args.u32("column")?, args.flag("wall")?),
+            "mirror" => Op::Mirror,
+            "tick" => Op::Tick(args.u32("count")?),
+            "perturb" => Op::Perturb(args.u32("count")?, args.u64("seed")?),
+            "sprinkle" => Op::Sprinkle(args.u32("count")?, args.u64("seed")?),
+            "random" => Op::Random(args.u32("count")?, args.u64("seed")?),
+            "clamp" => Op::Clamp(args.u32("count")?),
+            "rule" => Op::Rule(rest.to_string()),
+            "cells" => {
+                let values = rest
+                    .split_whitespace()
+                    .map(|arg| {
+                        arg.parse::<u32>()
+                            .map_err(|_| format!("invalid value '{}'", arg))
+                    })
+                    .collect::<Result<Vec<u32>, String>>()?;
+                if values.len() % 3 != 0 {
+                    return Err("expected row, column, state triples".to_string());
+                }
+                let cells = values
+                    .chunks(3)
+                    .map(|triple| match triple[2] {
+                        0 => Ok((triple[0], triple[1], Cell::Dead)),
+                        1 => Ok((triple[0], triple[1], Cell::Alive)),
+                        state => Err(format!("invalid cell state {}", state)),
+                    })
+                    .collect::<Result<Vec<_>, String>>()?;
+                Op::Cells(cells)
+            }
+            "stamp" => {
+                let mut parts = rest.splitn(3, ' ');
+                let mut num = |what: &str| -> Result<u32, String> {
+                    let arg = parts.next().unwrap_or("");
+                    arg.parse()
+                        .map_err(|_| format!("invalid {} '{}'", what, arg))
+                };
+                let (row, column) = (num("row")?, num("column")?);
+                Op::Stamp(row, column, unescape(parts.next().unwrap_or(""))?)
+            }
+            _ => return Err(format!("unknown operation '{}'", name)),
+        };
+        Ok(op)
+    }
+}
+
+/// 操作的参数，依次取出
+struct Args<'a>(SplitWhitespace<'a>);
+
+impl Args<'_> {
+    fn u64(&mut self, what: &str) -> Result<u64, String> {
+        let arg = self.0.next().ok_or_else(|| format!("missing {}", what))?;
+        arg.parse()
+            .map_err(|_| format!("invalid {} '{}'", what, arg))
+    }
+
+    fn u32(&mut self, what: &str) -> Result<u32, String> {
+        let value = self.u64(what)?;
+        u32::try_from(value).map_err(|_| format!("{} {} is out of range", what, value))
+    }
+
+    /// 0 或 1
+    fn flag(&mut self, what: &str) -> Result<bool, String> {
+        match self.u64(what)? {
+            0 => Ok(false),
+            1 => Ok(true),
+            other => Err(format!("invalid {} {}", what, other)),
+        }
+    }
+}
+
+fn unescape(s: &str) -> Result<String, String> {
+    let mut out = String::with_capacity(s.len());
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => out.push('\n'),
+            Some('\\') => out.push('\\'),
+            other => return Err(format!("invalid escape '\\{}'", other.unwrap_or(' '))),
+        }
+    }
+    Ok(out)
+}
+
+/// 记录用户的每一次修改，导出为可以完整重放的文本日志：
+/// ```
+/// # use wasm_game_of_life::{Recorder, Replay};
+/// let mut recorder = Recorder::new(16, 16, 42).unwrap();
+/// recorder.start();
+/// recorder.toggle_cell(3, 4).unwrap();
+/// recorder.tick(10);
+///
+/// let mut replay = Replay::from_log(&recorder.export()).unwrap();
+/// replay.finish().unwrap();
+/// assert_eq!(replay.universe().get_cells(), recorder.universe().get_cells());
+/// ```
+/// 日志第一行为 `gol-log 1 <width> <height> <seed>`，之后每行是 `<毫秒> <操作> <参数...>`，
+/// 毫秒数是相对于开始记录的时间，只用于参考，重放时不使用。
+///
+/// 只能通过 Recorder 自己的方法修改 Universe（`universe()` 只给出只读引用），因此日志总是完整的。
+/// 支持的操作：`set_seed`、`start`、`reset`、`resize`、`toggle_cell`、`set_cell`、`set_cells_state`、
+/// `set_rule`、`set_boundary`、`set_wall`、`stamp_rle`、`mirror_to_quadrants`、`tick`、`perturb`、
+/// `sprinkle`、`set_random_alive_cells` 和 `clamp_population`；
+/// 其它修改（例如需要另一个 Universe 的 `merge`）无法写成一行日志，不能在记录中使用
+#[cfg_attr(feature = "web", wasm_bindgen)]
+pub struct Recorder {
+    universe: Universe,
+    header: String,
+    ops: Vec<(u64, Op)>,
+    started_ms: u64,
+}
+
+#[cfg_attr(feature = "web", wasm_bindgen)]
+impl Recorder {
+    /// 从一个指定大小、全部死亡、使用 `seed` 作为随机数种子的 Universe 开始记录
+    #[cfg_attr(feature = "web", wasm_bindgen(constructor))]
+    pub fn new(width: u32, height: u32, seed: u64) -> Result<Recorder, UniverseError> {
+        let mut universe = Universe::new_with_size(width, height)?;
+        universe.set_seed(seed);
+        Ok(Recorder {
+            universe,
+            header: format!("{} {} {} {}", LOG_HEADER, width, height, seed),
+            ops: vec![],
+            started_ms: utils::now_ms(),
+        })
+    }
+
+    pub fn set_seed(&mut self, seed: u64) {
+        self.record(Op::Seed(seed)).unwrap();
+    }
+
+    pub fn start(&mut self) {
+        self.record(Op::Start).unwrap();
+    }
+
+    pub fn reset(&mut self) {
+        self.record(Op::Reset).unwrap();
+    }
+
+    pub fn resize(&mut self, width: u32, height: u32) -> Result<(), UniverseError> {
+        self.record(Op::Resize(width, height))
+    }
+
+    pub fn toggle_cell(&mut self, row: u32, column: u32) -> Result<(), UniverseError> {
+        self.record(Op::Toggle(row, column))
+    }
+
+    pub fn set_cell(&mut self, row: u32, column: u32, cell: Cell) -> Result<(), UniverseError> {
+        self.record(Op::Set(row, column, cell))
+    }
+
+    pub fn set_rule(&mut self, rule: &str) -> Result<(), UniverseError> {
+        self.record(Op::Rule(rule.trim().to_string()))
+    }
+
+    pub fn set_boundary(&mut self, boundary: Boundary) {
+        self.record(Op::Boundary(boundary)).unwrap();
+    }
+
+    pub fn set_wall(&mut self, row: u32, column: u32, wall: bool) -> Result<(), UniverseError> {
+        self.record(Op::Wall(row, column, wall))
+    }
+
+    pub fn stamp_rle(&mut self, rle: &str, row: u32, column: u32) -> Result<(), UniverseError> {
+        self.record(Op::Stamp(row, column, rle.to_string()))
+    }
+
+    pub fn mirror_to_quadrants(&mut self) {
+        self.record(Op::Mirror).unwrap();
+    }
+
+    /// tick `n` 次，日志中只占一行
+    pub fn tick(&mut self, n: u32) {
+        self.record(Op::Tick(n)).unwrap();
+    }
+
+    pub fn perturb(&mut self, n_flips: u32, seed: u64) {
+        self.record(Op::Perturb(n_flips, seed)).unwrap();
+    }
+
+    pub fn sprinkle(&mut self, count: u32, seed: u64) {
+        self.record(Op::Sprinkle(count, seed)).unwrap();
+    }
+
+    pub fn set_random_alive_cells(&mut self, count: u32, seed: u64) -> Result<(), UniverseError> {
+        self.record(Op::Random(count, seed))
+    }
+
+    pub fn clamp_population(&mut self, max_alive: u32) {
+        self.record(Op::Clamp(max_alive)).unwrap();
+    }
+
+    /// 已经记录的操作数量
+    pub fn op_count(&self) -> u32 {
+        self.ops.len() as u32
+    }
+
+    /// 导出完整的日志，可以交给 `Replay::from_log` 重放
+    pub fn export(&self) -> String {
+        let mut log = self.header.clone();
+        log.push('\n');
+        for (ms, op) in self.ops.iter() {
+            log.push_str(&format!("{} {}\n", ms, op.to_line()));
+        }
+        log
+    }
+}
+
+impl Recorder {
+    pub fn set_cells_state(&mut self, cells: &[(u32, u32, Cell)]) -> Result<(), UniverseError> {
+        self.record(Op::Cells(cells.to_vec()))
+    }
+
+    /// 记录下来的 Universe 的当前状态
+    pub fn universe(&self) -> &Universe {
+        &self.universe
+    }
+
+    /// 先执行操作，成功后才写入日志，失败的操作不会被记录
+    fn record(&mut self, op: Op) -> Result<(), UniverseError> {
+        op.apply(&mut self.universe)?;
+        let ms = utils::now_ms().saturating_sub(self.started_ms);
+        self.ops.push((ms, op));
+        Ok(())
+    }
+}
+
+/// 按顺序重放 `Recorder` 导出的日志，可以停在任意一个操作或者任意一代
+#[cfg_attr(feature = "web", wasm_bindgen)]
+pub struct Replay {
+    universe: Universe,
+    ops: Vec<Op>,
+    /// 下一个要执行的操作
+    position: usize,
+    /// 当前 Tick 操作中已经执行的次数
+    ticks_done: u32,
+}
+
+#[cfg_attr(feature = "web", wasm_bindgen)]
+impl Replay {
+    /// 解析日志并创建初始的 Universe，此时还没有执行任何操作
+    pub fn from_log(log: &str) -> Result<Replay, UniverseError> {
+        let error = |line: usize, message: String| UniverseError::ParseError { line, message };
+        let mut lines = log.lines();
+        let header = lines.next().unwrap_or("");
+        let fields = header
+            .strip_prefix(LOG_HEADER)
+            .ok_or_else(|| error(1, format!("expected '{}' header", LOG_HEADER)))?;
+        let fields: Vec<&str> = fields.split_whitespace().collect();
+        let (width, height, seed) = match fields[..] {
+            [width, height, seed] => (
+                width
+                    .parse()
+                    .map_err(|_| error(1, "invalid width".to_string()))?,
+                height
+                    .parse()
+                    .map_err(|_| error(1, "invalid height".to_string()))?,
+                seed.parse()
+                    .map_err(|_| error(1, "invalid seed".to_string()))?,
+            ),
+            _ => return Err(error(1, "expected width, height and seed".to_string())),
+        };
+
+        let mut ops = vec![];
+        for (i, line) in lines.enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            // 时间戳只用于参考
+            let (_, op) = line
+                .split_once(' ')
+                .ok_or_else(|| error(i + 2, "expected timestamp and operation".to_string()))?;
+            ops.push(Op::parse(op).map_err(|message| error(i + 2, message))?);
+        }
+
+        let mut universe = Universe::new_with_size(width, height)?;
+        universe.set_seed(seed);
+        Ok(Replay {
+            universe,
+            ops,
+            position: 0,
+            ticks_done: 0,
+        })
+    }
+
+    /// 日志中操作的数量
+    pub fn op_count(&self) -> u32 {
+        self.ops.len() as u32
+    }
+
+    /// 已经完整执行的操作数量
+    pub fn position(&self) -> u32 {
+        self.position as u32
+    }
+
+    /// 执行操作直到已经完整执行了 `op_index` 个操作；已经超过时什么也不做
+    pub fn advance_to_op(&mut self, op_index: u32) -> Result<(), UniverseError> {
+        let target = (op_index as usize).min(self.ops.len());
+        while self.position < target {
+            match self.ops[self.position] {
+                Op::Tick(n) => {
+                    for _ in self.ticks_done..n {
+                        self.universe.tick();
+                    }
+                }
+                ref op => op.apply(&mut self.universe)?,
+            }
+            self.position += 1;
+            self.ticks_done = 0;
+        }
+        Ok(())
+    }
+
+    /// 执行操作直到 Universe 到达第 `generation` 代，可以停在一个 Tick 操作的中间；
+    /// 日志结束前没有到达时停在日志末尾
+    pub fn advance_to_generation(&mut self, generation: u64) -> Result<(), UniverseError> {
+        while self.universe.generation() < generation && self.position < self.ops.len() {
+            match self.ops[self.position] {
+                Op::Tick(n) if self.ticks_done < n => {
+                    self.universe.tick();
+                    self.ticks_done += 1;
+                }
+                Op::Tick(_) => {
+                    self.position += 1;
+                    self.ticks_done = 0;
+                }
+                _ => self.advance_to_op(self.position as u32 + 1)?,
+            }
+        }
+        Ok(())
+    }
+
+    /// 执行剩下的所有操作
+    pub fn finish(&mut self) -> Result<(), UniverseError> {
+        self.advance_to_op(self.ops.len() as u32)
+    }
+}
+
+impl Replay {
+    /// 重放到当前位置的 Universe
+    pub fn universe(&self) -> &Universe {
+        &self.universe
+    }
+}
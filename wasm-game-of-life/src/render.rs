@@ -0,0 +1,141 @@
+#[cfg(feature = "web")]
+use wasm_bindgen::prelude::*;
+
+use crate::{Cell, Universe, UniverseError};
+
+pub(crate) const ALIVE_RGBA: [u8; 4] = [0x00, 0x00, 0x00, 0xff];
+pub(crate) const DEAD_RGBA: [u8; 4] = [0xff, 0xff, 0xff, 0xff];
+
+impl Universe {
+    /// 将所有 Cell 以每个 cell_px × cell_px 像素写入 RGBA 缓冲区（无网格线），
+    /// 缓冲区大小必须为 (width * cell_px) * (height * cell_px) * 4
+    pub(crate) fn write_pixels(&self, buf: &mut [u8], cell_px: u32, alive: [u8; 4], dead: [u8; 4]) {
+        let cell_px = cell_px as usize;
+        let row_bytes = self.width as usize * cell_px * 4;
+        for row in 0..self.height {
+            let line = &mut buf[row as usize * cell_px * row_bytes..][..row_bytes];
+            for column in 0..self.width {
+                let color = if self.cells[self.get_index(row, column)] == Cell::Alive {
+                    alive
+                } else {
+                    dead
+                };
+                for pixel in
+                    line[column as usize * cell_px * 4..][..cell_px * 4].chunks_exact_mut(4)
+                {
+                    pixel.copy_from_slice(&color);
+                }
+            }
+
+            // 同一行 Cell 的其余像素行与第一行相同
+            let (first, rest) = buf[row as usize * cell_px * row_bytes..][..cell_px * row_bytes]
+                .split_at_mut(row_bytes);
+            for line in rest.chunks_exact_mut(row_bytes) {
+                line.copy_from_slice(first);
+            }
+        }
+    }
+
+    /// 整个 Universe 渲染后的像素尺寸；`cell_px` 为 0，或者像素数量太大、
+    /// 无法分配 RGBA 缓冲区时返回错误，调用方应在做任何其它事之前检查
+    pub(crate) fn pixel_size(&self, cell_px: u32) -> Result<(u32, u32), UniverseError> {
+        if cell_px == 0 {
+            return Err(UniverseError::InvalidArgument(
+                "cell_px must be at least 1".to_string(),
+            ));
+        }
+        match (
+            self.width.checked_mul(cell_px),
+            self.height.checked_mul(cell_px),
+        ) {
+            (Some(width), Some(height))
+                if (width as usize)
+                    .checked_mul(height as usize)
+                    .and_then(|pixels| pixels.checked_mul(4))
+                    .is_some() =>
+            {
+                Ok((width, height))
+            }
+            _ => Err(UniverseError::InvalidArgument(format!(
+                "a {}x{} universe at {} px per cell is too large to render",
+                self.width, self.height, cell_px
+            ))),
+        }
+    }
+}
+
+#[cfg_attr(feature = "web", wasm_bindgen)]
+impl Universe {
+    /// 将 RGBA 像素直接写入 JS 提供的缓冲区（例如 SharedArrayBuffer 的视图），
+    /// 不在 Wasm 堆上分配图像；颜色为 0xRRGGBBAA 格式
+    pub fn write_state_to_image_data(
+        &self,
+        buf: &mut [u8],
+        cell_px: u32,
+        alive_color: u32,
+        dead_color: u32,
+    ) -> Result<(), UniverseError> {
+        let (width, height) = self.pixel_size(cell_px)?;
+        let expected = width as usize * height as usize * 4;
+        if buf.len() != expected {
+            return Err(UniverseError::LengthMismatch {
+                expected,
+                actual: buf.len(),
+            });
+        }
+
+        self.write_pixels(
+            buf,
+            cell_px,
+            alive_color.to_be_bytes(),
+            dead_color.to_be_bytes(),
+        );
+        Ok(())
+    }
+
+    /// 依次 tick `n` 次，每次 tick 之后把整帧 RGBA 像素按顺序写入 `buf`，
+    /// 适合一次性导出 GIF/视频而不为每一帧分配内存；`buf` 的大小必须恰好是 `n` 帧，
+    /// `cell_px` 不能为 0，否则返回错误且 Universe 不会前进。
+    /// 所有帧的尺寸必须相同：某次 tick 引起自动扩展时返回错误，
+    /// 此时 Universe 停在扩展之后的那一代，`buf` 中只有之前的帧是完整的
+    pub fn run_and_write_frames_to_buffer(
+        &mut self,
+        n: u32,
+        cell_px: u32,
+        buf: &mut [u8],
+        alive_color: u32,
+        dead_color: u32,
+    ) -> Result<(), UniverseError> {
+        let (width, height) = self.pixel_size(cell_px)?;
+        let frame = width as usize * height as usize * 4;
+        let expected = frame.checked_mul(n as usize);
+        if expected != Some(buf.len()) {
+            let expected = expected.unwrap_or(usize::MAX);
+            return Err(UniverseError::LengthMismatch {
+                expected,
+                actual: buf.len(),
+            });
+        }
+
+        for i in 0..n as usize {
+            self.tick();
+            if self.pixel_size(cell_px)? != (width, height) {
+                return Err(UniverseError::InvalidArgument(format!(
+                    "the universe grew to {}x{} during frame {}, the buffer holds {}x{} frames",
+                    self.width,
+                    self.height,
+                    i,
+                    width / cell_px,
+                    height / cell_px
+                )));
+            }
+            self.write_pixels(
+                &mut buf[i * frame..][..frame],
+                cell_px,
+                alive_color.to_be_bytes(),
+                dead_color.to_be_bytes(),
+            );
+        }
+        Ok(())
+    }
+}
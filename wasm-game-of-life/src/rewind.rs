@@ -0,0 +1,266 @@
+use std::collections::VecDeque;
+use std::mem::size_of;
+
+#[cfg(feature = "web")]
+use wasm_bindgen::prelude::*;
+
+use crate::{Cell, Metrics, Universe, UniverseError};
+
+/// 一帧与较新一帧之间的差异：变化的 Cell 较少时记录它们的下标，
+/// 较多时记录按位打包的异或结果，取两者中较小的一种
+#[derive(Clone, Debug)]
+enum Delta {
+    Sparse(Vec<u32>),
+    Packed(Vec<u8>),
+}
+
+impl Delta {
+    /// `older` 与 `newer` 都是按位打包的状态
+    fn between(older: &[u8], newer: &[u8]) -> Delta {
+        let xor: Vec<u8> = older.iter().zip(newer).map(|(a, b)| a ^ b).collect();
+        let changed: usize = xor.iter().map(|byte| byte.count_ones() as usize).sum();
+        if changed * size_of::<u32>() >= xor.len() {
+            return Delta::Packed(xor);
+        }
+        let mut indices = Vec::with_capacity(changed);
+        for (i, &byte) in xor.iter().enumerate() {
+            for bit in 0..8 {
+                if byte >> bit & 1 == 1 {
+                    indices.push((i * 8 + bit) as u32);
+                }
+            }
+        }
+        Delta::Sparse(indices)
+    }
+
+    /// 把较新一帧的状态原地变回较旧一帧
+    fn apply(&self, packed: &mut [u8]) {
+        match self {
+            Delta::Sparse(indices) => {
+                for &i in indices {
+                    packed[i as usize / 8] ^= 1 << (i % 8);
+                }
+            }
+            Delta::Packed(xor) => packed.iter_mut().zip(xor).for_each(|(a, b)| *a ^= b),
+        }
+    }
+
+    fn byte_len(&self) -> usize {
+        match self {
+            Delta::Sparse(indices) => indices.len() * size_of::<u32>(),
+            Delta::Packed(xor) => xor.len(),
+        }
+    }
+}
+
+/// 最近若干代 tick 之前的状态，见 `enable_rewind`。只有最新的一帧完整保存（按位打包），
+/// 更早的每一帧都保存为与后一帧的差异，丢弃最旧的帧不会影响其它帧
+#[derive(Clone, Debug, Default)]
+pub(crate) struct RewindBuffer {
+    capacity: usize,
+    /// 记录时的 (width, height)，尺寸变化之后旧的帧全部作废
+    size: (u32, u32),
+    /// 最新一帧：(代数, 存活数量, 指标, 按位打包的 Cell)
+    head: Option<(u64, u32, Metrics, Vec<u8>)>,
+    /// 更早的帧，从新到旧：(代数, 存活数量, 指标, 与较新一帧的差异)
+    deltas: VecDeque<(u64, u32, Metrics, Delta)>,
+}
+
+impl RewindBuffer {
+    /// 是否在记录；不记录时 tick 不必为 `push` 计算指标
+    pub(crate) fn is_enabled(&self) -> bool {
+        self.capacity > 0
+    }
+
+    fn len(&self) -> usize {
+        self.head.iter().count() + self.deltas.len()
+    }
+
+    pub(crate) fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity;
+        self.trim(capacity);
+    }
+
+    /// 只保留最新的 `frames` 帧，容量不变
+    fn trim(&mut self, frames: usize) {
+        if frames == 0 {
+            self.head = None;
+        }
+        self.deltas.truncate(frames.saturating_sub(1));
+    }
+
+    /// 改变尺寸时调用：旧尺寸的帧无法恢复到新的网格上，全部丢弃，容量不变
+    pub(crate) fn clear(&mut self) {
+        self.trim(0);
+    }
+
+    /// 与 `other` 交换保存的帧，双方各自保留原来的容量，超出容量的最旧的帧被丢弃
+    pub(crate) fn swap_frames(&mut self, other: &mut RewindBuffer) {
+        std::mem::swap(self, other);
+        std::mem::swap(&mut self.capacity, &mut other.capacity);
+        self.trim(self.capacity);
+        other.trim(other.capacity);
+    }
+
+    /// tick 之前调用，记录第 `generation` 代的状态以及它的指标
+    pub(crate) fn push(
+        &mut self,
+        generation: u64,
+        size: (u32, u32),
+        cells: &[Cell],
+        metrics: Metrics,
+    ) {
+        if self.capacity == 0 {
+            return;
+        }
+        let packed = pack(cells);
+        let population = packed.iter().map(|byte| byte.count_ones()).sum();
+        if size != self.size {
+            self.size = size;
+            self.head = None;
+            self.deltas.clear();
+        }
+        if let Some((older, older_population, older_metrics, state)) = self.head.take() {
+            let delta = Delta::between(&state, &packed);
+            self.deltas
+                .push_front((older, older_population, older_metrics, delta));
+            self.deltas.truncate(self.capacity - 1);
+        }
+        self.head = Some((generation, population, metrics, packed));
+    }
+
+    /// 取出从新到旧的第 `frames` 帧（从 1 开始），连同比它更新的帧一起从缓冲区中移除；
+    /// 帧数不足或者记录时的尺寸与 `size` 不同时返回错误，缓冲区保持不变
+    fn pop(
+        &mut self,
+        frames: usize,
+        size: (u32, u32),
+    ) -> Result<(u64, Metrics, Vec<Cell>), UniverseError> {
+        let available = self.len();
+        if frames == 0 || frames > available {
+            return Err(UniverseError::InvalidArgument(format!(
+                "cannot rewind {} generations, only {} are available",
+                frames, available
+            )));
+        }
+        if size != self.size {
+            return Err(UniverseError::InvalidArgument(format!(
+                "cannot rewind a {}x{} universe to frames recorded at {}x{}",
+                size.0, size.1, self.size.0, self.size.1
+            )));
+        }
+        let (mut generation, _, mut metrics, mut state) = self.head.take().unwrap();
+        for _ in 1..frames {
+            let (older, _, older_metrics, delta) = self.deltas.pop_front().unwrap();
+            delta.apply(&mut state);
+            generation = older;
+            metrics = older_metrics;
+        }
+        let cells = unpack(&state, (size.0 * size.1) as usize);
+        if let Some((older, population, older_metrics, delta)) = self.deltas.pop_front() {
+            delta.apply(&mut state);
+            self.head = Some((older, population, older_metrics, state));
+        }
+        Ok((generation, metrics, cells))
+    }
+
+    fn byte_len(&self) -> usize {
+        let head = self.head.as_ref().map_or(0, |(_, _, _, state)| state.len());
+        let deltas: usize = self
+            .deltas
+            .iter()
+            .map(|(_, _, _, delta)| size_of::<(u64, u32, Metrics, Delta)>() + delta.byte_len())
+            .sum();
+        head + deltas
+    }
+
+    /// 各帧的存活数量，从旧到新
+    fn populations(&self) -> Vec<u32> {
+        let older = self
+            .deltas
+            .iter()
+            .rev()
+            .map(|&(_, population, _, _)| population);
+        older
+            .chain(self.head.iter().map(|&(_, population, _, _)| population))
+            .collect()
+    }
+
+    /// 各帧的指标，从旧到新
+    fn metrics(&self) -> Vec<Metrics> {
+        let older = self.deltas.iter().rev().map(|&(_, _, metrics, _)| metrics);
+        older
+            .chain(self.head.iter().map(|&(_, _, metrics, _)| metrics))
+            .collect()
+    }
+}
+
+pub(crate) fn pack(cells: &[Cell]) -> Vec<u8> {
+    let mut packed = vec![0u8; cells.len().div_ceil(8)];
+    for (i, &cell) in cells.iter().enumerate() {
+        packed[i / 8] |= (cell as u8) << (i % 8);
+    }
+    packed
+}
+
+fn unpack(packed: &[u8], len: usize) -> Vec<Cell> {
+    (0..len)
+        .map(|i| Cell::from(packed[i / 8] >> (i % 8) & 1 == 1))
+        .collect()
+}
+
+#[cfg_attr(feature = "web", wasm_bindgen)]
+impl Universe {
+    /// 保留最近 `capacity` 代 tick 之前的状态，之后可以用 `rewind` 像视频一样往回拖动；
+    /// 0 表示关闭并丢弃已保留的状态。与 `set_snapshot_capacity` 不同，
+    /// 除了最新的一帧之外都只保存与后一帧的差异，256×256 的网格保留 500 代通常只需要几百 KB
+    pub fn enable_rewind(&mut self, capacity: u32) {
+        self.rewind.set_capacity(capacity as usize);
+    }
+
+    /// 可以回退的代数
+    pub fn rewind_available(&self) -> u32 {
+        self.rewind.len() as u32
+    }
+
+    /// 回退缓冲区占用的字节数（估算）
+    pub fn rewind_memory_bytes(&self) -> usize {
+        self.rewind.byte_len()
+    }
+
+    /// `rewind` 缓冲区中保留的每一代的存活数量，从旧到新（不包括当前状态），
+    /// 在 JS 中是 `Uint32Array`，可以直接画出存活数量随时间变化的曲线而不必重新模拟。
+    /// 随着 tick 增长，最旧的帧被丢弃或者回退之后相应缩短；没有调用 `enable_rewind` 时为空
+    pub fn get_timeline(&self) -> Vec<u32> {
+        self.rewind.populations()
+    }
+
+    /// 回退 `generations` 代，恢复当时的 Cell、代数以及 `metrics` 中的活跃数量。经过的帧会被丢弃，
+    /// 之后的 tick 从恢复的状态重新记录；超过 `rewind_available` 时返回错误，Universe 保持不变。
+    /// 改变尺寸（包括 `set_auto_expand` 引起的扩展）会清空缓冲区
+    pub fn rewind(&mut self, generations: u32) -> Result<(), UniverseError> {
+        if generations == 0 {
+            return Ok(());
+        }
+        let (generation, metrics, cells) = self
+            .rewind
+            .pop(generations as usize, (self.width, self.height))?;
+        self.cells.copy_from_slice(&cells);
+        self.sync_cells();
+        self.generation = generation;
+        self.active_cells = metrics.active_cells;
+        self.stable = false;
+        self.snapshots.clear();
+        #[cfg(feature = "cell-ages")]
+        self.ages.clear();
+        Ok(())
+    }
+}
+
+impl Universe {
+    /// 与 `get_timeline` 对应的每一代的 `metrics`，从旧到新，可以画出密度、熵和活跃数量的曲线；
+    /// 只在开启 `enable_rewind` 时于 tick 之前计算并记录
+    pub fn get_metrics_timeline(&self) -> Vec<Metrics> {
+        self.rewind.metrics()
+    }
+}
@@ -0,0 +1,221 @@
+#[cfg(feature = "web")]
+use wasm_bindgen::prelude::*;
+
+use crate::{Boundary, Cell, Universe, UniverseError};
+
+/// 解析后的图案：大小以及所有存活 Cell 相对于左上角的 (row, column)
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Pattern {
+    pub width: u32,
+    pub height: u32,
+    pub cells: Vec<(u32, u32)>,
+}
+
+/// 解析 RLE 格式的图案，例如滑翔机：
+/// ```text
+/// #N Glider
+/// x = 3, y = 3, rule = B3/S23
+/// bob$2bo$3o!
+/// ```
+/// `#` 开头的行为注释；头部的 x/y 可以省略，此时大小由图案本身决定。
+/// 图案（包括头部声明的大小）超过 `Universe::MAX_CELLS` 时直接报错，不会先分配内存
+pub fn parse_rle(s: &str) -> Result<Pattern, UniverseError> {
+    let mut pattern = Pattern::default();
+    let (mut row, mut column) = (0u32, 0u32);
+    let mut run: Option<u32> = None;
+
+    'lines: for (line_no, line) in s.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let error = |message: String| UniverseError::ParseError {
+            line: line_no + 1,
+            message,
+        };
+        if line.starts_with('x') {
+            parse_header(line, &mut pattern).map_err(error)?;
+            check_size(pattern.width as u64, pattern.height as u64).map_err(error)?;
+            continue;
+        }
+
+        for c in line.chars() {
+            if c.is_whitespace() {
+                continue;
+            }
+            if let Some(digit) = c.to_digit(10) {
+                let count = run
+                    .unwrap_or(0)
+                    .checked_mul(10)
+                    .and_then(|v| v.checked_add(digit));
+                run = Some(count.ok_or_else(|| error("run count too large".to_string()))?);
+                continue;
+            }
+
+            let n = run.take().unwrap_or(1);
+            match c {
+                'b' | '.' => {
+                    check_size(column as u64 + n as u64, 1).map_err(error)?;
+                    column += n;
+                }
+                '$' => {
+                    check_size(1, row as u64 + n as u64).map_err(error)?;
+                    row += n;
+                    column = 0;
+                }
+                '!' => break 'lines,
+                c if c.is_ascii_alphabetic() => {
+                    let end = column as u64 + n as u64;
+                    let width = (pattern.width as u64).max(end);
+                    let height = (pattern.height as u64).max(row as u64 + 1);
+                    check_size(width, height).map_err(error)?;
+                    pattern.cells.extend((column..column + n).map(|c| (row, c)));
+                    column += n;
+                    pattern.width = width as u32;
+                    pattern.height = height as u32;
+                }
+                c => return Err(error(format!("unexpected character '{}'", c))),
+            }
+        }
+    }
+
+    Ok(pattern)
+}
+
+/// 图案的宽、高以及面积都不能超过 `Universe::MAX_CELLS`
+pub(crate) fn check_size(width: u64, height: u64) -> Result<(), String> {
+    let max = Universe::MAX_CELLS as u64;
+    if width > max || height > max || width * height > max {
+        return Err(format!("pattern is larger than {} cells", max));
+    }
+    Ok(())
+}
+
+/// 解析 `x = 3, y = 3, rule = B3/S23` 形式的头部
+fn parse_header(line: &str, pattern: &mut Pattern) -> Result<(), String> {
+    for item in line.split(',') {
+        let (key, value) = item
+            .split_once('=')
+            .ok_or_else(|| format!("invalid header item '{}'", item.trim()))?;
+        let parse = || {
+            value
+                .trim()
+                .parse::<u32>()
+                .map_err(|_| format!("invalid {} '{}'", key.trim(), value.trim()))
+        };
+        match key.trim() {
+            "x" => pattern.width = pattern.width.max(parse()?),
+            "y" => pattern.height = pattern.height.max(parse()?),
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+#[cfg_attr(feature = "web", wasm_bindgen)]
+impl Universe {
+    /// 解析 RLE 并以 (row, column) 为左上角粘贴到当前网格上，只会让 Cell 存活，不会清除已有的 Cell；
+    /// 环形宇宙中超出边缘的部分绕回另一侧，其它边界下被裁掉
+    pub fn stamp_rle(&mut self, rle: &str, row: u32, column: u32) -> Result<(), UniverseError> {
+        self.check_bounds(row, column)?;
+        let pattern = parse_rle(rle)?;
+        self.stamp_wrapped(&pattern, row, column)
+    }
+
+    /// 将整个 Universe 导出为 RLE：`x = W, y = H, rule = R` 头部加上游程编码的内容，
+    /// 每行不超过 70 个字符，行尾的死亡 Cell 和末尾的空行会被省略
+    pub fn to_rle(&self) -> String {
+        self.to_rle_named("", "")
+    }
+
+    /// 与 `to_rle` 相同，但在头部之前加上 Golly 使用的 `#N name` 和 `#C comment` 行：
+    /// `comment` 的每个非空行成为一个 `#C` 行，`name` 中的换行被替换为空格；为空时省略对应的行
+    pub fn to_rle_named(&self, name: &str, comment: &str) -> String {
+        let mut out = String::new();
+        if !name.is_empty() {
+            out.push_str(&format!("#N {}\n", name.replace(['\r', '\n'], " ")));
+        }
+        for line in comment.lines().filter(|line| !line.trim().is_empty()) {
+            out.push_str(&format!("#C {}\n", line));
+        }
+        out.push_str(&format!(
+            "x = {}, y = {}, rule = {}\n",
+            self.width, self.height, self.rule
+        ));
+        let mut line = String::new();
+        let mut push = |token: String, line: &mut String| {
+            if line.len() + token.len() > RLE_LINE_WIDTH {
+                out.push_str(line);
+                out.push('\n');
+                line.clear();
+            }
+            line.push_str(&token);
+        };
+
+        let mut last_row = 0;
+        for row in 0..self.height {
+            let mut runs: Vec<(u32, char)> = vec![];
+            for column in 0..self.width {
+                let tag = match self.cells[self.get_index(row, column)] {
+                    Cell::Alive => 'o',
+                    Cell::Dead => 'b',
+                };
+                match runs.last_mut() {
+                    Some((n, c)) if *c == tag => *n += 1,
+                    _ => runs.push((1, tag)),
+                }
+            }
+            if runs.last().is_some_and(|&(_, c)| c == 'b') {
+                runs.pop();
+            }
+            if runs.is_empty() {
+                continue;
+            }
+
+            if row > last_row {
+                push(run_token(row - last_row, '$'), &mut line);
+            }
+            for (n, c) in runs {
+                push(run_token(n, c), &mut line);
+            }
+            last_row = row;
+        }
+        push("!".to_string(), &mut line);
+        out.push_str(&line);
+        out.push('\n');
+        out
+    }
+}
+
+impl Universe {
+    /// 以 (row, column) 为左上角粘贴图案：环形宇宙中超出边缘的部分绕回另一侧，其它边界下被裁掉
+    pub(crate) fn stamp_wrapped(
+        &mut self,
+        pattern: &Pattern,
+        row: u32,
+        column: u32,
+    ) -> Result<(), UniverseError> {
+        let mut cells = Vec::with_capacity(pattern.cells.len());
+        for &(r, c) in pattern.cells.iter() {
+            let (r, c) = (row as u64 + r as u64, column as u64 + c as u64);
+            let (r, c) = match self.boundary {
+                Boundary::Toroidal => (r % self.height as u64, c % self.width as u64),
+                _ if r >= self.height as u64 || c >= self.width as u64 => continue,
+                _ => (r, c),
+            };
+            cells.push((r as u32, c as u32, Cell::Alive));
+        }
+        self.set_cells_state(&cells)
+    }
+}
+
+/// RLE 每行的最大长度
+const RLE_LINE_WIDTH: usize = 70;
+
+fn run_token(n: u32, tag: char) -> String {
+    if n == 1 {
+        tag.to_string()
+    } else {
+        format!("{}{}", n, tag)
+    }
+}
@@ -0,0 +1,90 @@
+use rand::rngs::{SmallRng, StdRng};
+use rand::{RngCore, SeedableRng};
+#[cfg(feature = "web")]
+use wasm_bindgen::prelude::*;
+
+use crate::Universe;
+
+/// 随机填充使用的随机数生成器：
+/// - Std: `StdRng`（ChaCha12），统计质量高，所有平台上的序列相同（默认）
+/// - Small: `SmallRng`（Xoshiro），更快、代码更小，适合对体积敏感的 Wasm 构建；
+///   它在 32 位和 64 位平台上是不同的算法，相同的种子在 Wasm 和原生平台上会得到不同的序列
+#[cfg_attr(feature = "web", wasm_bindgen)]
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RngKind {
+    Std = 0,
+    Small = 1,
+}
+
+/// Universe 持有的随机数生成器。用枚举而不是 `Box<dyn RngCore>`，Universe 才能保持 `Clone`；
+/// StdRng 有 300 多字节的状态，放在堆上，避免 SmallRng 也占用同样大的空间
+#[derive(Clone, Debug)]
+pub(crate) enum UniverseRng {
+    Std(Box<StdRng>),
+    Small(SmallRng),
+}
+
+impl UniverseRng {
+    pub(crate) fn seeded(kind: RngKind, seed: u64) -> UniverseRng {
+        match kind {
+            RngKind::Std => UniverseRng::Std(Box::new(StdRng::seed_from_u64(seed))),
+            RngKind::Small => UniverseRng::Small(SmallRng::seed_from_u64(seed)),
+        }
+    }
+
+    pub(crate) fn kind(&self) -> RngKind {
+        match self {
+            UniverseRng::Std(_) => RngKind::Std,
+            UniverseRng::Small(_) => RngKind::Small,
+        }
+    }
+}
+
+impl RngCore for UniverseRng {
+    fn next_u32(&mut self) -> u32 {
+        match self {
+            UniverseRng::Std(rng) => rng.next_u32(),
+            UniverseRng::Small(rng) => rng.next_u32(),
+        }
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        match self {
+            UniverseRng::Std(rng) => rng.next_u64(),
+            UniverseRng::Small(rng) => rng.next_u64(),
+        }
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        match self {
+            UniverseRng::Std(rng) => rng.fill_bytes(dest),
+            UniverseRng::Small(rng) => rng.fill_bytes(dest),
+        }
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        match self {
+            UniverseRng::Std(rng) => rng.try_fill_bytes(dest),
+            UniverseRng::Small(rng) => rng.try_fill_bytes(dest),
+        }
+    }
+}
+
+#[cfg_attr(feature = "web", wasm_bindgen)]
+impl Universe {
+    /// 选择之后所有随机操作使用的随机数生成器，包括 `start` 等使用自带生成器的操作，
+    /// 以及 `set_random_alive_cells`、`perturb` 等接受种子的操作。
+    /// 新的生成器的种子取自当前的生成器，因此只要之前的序列可以复现，之后的也可以；
+    /// 之后调用 `set_seed` 会以同一种生成器重新播种
+    pub fn set_rng(&mut self, kind: RngKind) {
+        if kind != self.rng.kind() {
+            let seed = self.rng.next_u64();
+            self.rng = UniverseRng::seeded(kind, seed);
+        }
+    }
+
+    pub fn rng_kind(&self) -> RngKind {
+        self.rng.kind()
+    }
+}
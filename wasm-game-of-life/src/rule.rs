@@ -0,0 +1,305 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::encoding::{base64_decode, base64_encode};
+use crate::{Cell, UniverseError, RULE_TABLE_LEN};
+
+/// 邻居数的集合，第 n 位表示邻居数 n，可以容纳 0..=Rule::MAX_NEIGHBORS
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Counts([u64; COUNT_WORDS]);
+
+const COUNT_WORDS: usize = Rule::MAX_NEIGHBORS as usize / 64 + 1;
+
+impl Counts {
+    const EMPTY: Counts = Counts([0; COUNT_WORDS]);
+
+    /// 只包含 0..64 的邻居数，第 n 位表示邻居数 n
+    const fn low(bits: u64) -> Counts {
+        let mut words = [0; COUNT_WORDS];
+        words[0] = bits;
+        Counts(words)
+    }
+
+    fn insert(&mut self, n: u32) {
+        self.0[n as usize / 64] |= 1 << (n % 64);
+    }
+
+    fn contains(&self, n: u32) -> bool {
+        n <= Rule::MAX_NEIGHBORS && self.0[n as usize / 64] >> (n % 64) & 1 == 1
+    }
+
+    fn union(mut self, other: Counts) -> Counts {
+        for (word, other) in self.0.iter_mut().zip(other.0.iter()) {
+            *word |= other;
+        }
+        self
+    }
+
+    /// 小端字节序的位掩码，末尾为 0 的字节被省略
+    fn to_bytes(self) -> Vec<u8> {
+        let mut bytes: Vec<u8> = self.0.iter().flat_map(|word| word.to_le_bytes()).collect();
+        while bytes.last() == Some(&0) {
+            bytes.pop();
+        }
+        bytes
+    }
+
+    /// 集合中的邻居数，升序
+    fn iter(self) -> impl Iterator<Item = u32> {
+        (0..=Rule::MAX_NEIGHBORS).filter(move |&n| self.contains(n))
+    }
+}
+
+/// B/S 记法描述的生命规则，例如康威生命游戏为 `B3/S23`
+/// birth/survival 记录邻居存活数为多少时出生/存活，
+/// 可以容纳 0..=MAX_NEIGHBORS 的邻居数，即半径不超过 MAX_RADIUS 的完整邻域。
+/// `no_death` 为 true 时是 “Life without Death” 模式：存活的 Cell 永远不会死亡
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Rule {
+    birth: Counts,
+    survival: Counts,
+    no_death: bool,
+}
+
+impl Rule {
+    /// 康威生命游戏：B3/S23
+    pub const CONWAY: Rule = Rule {
+        birth: Counts::low(1 << 3),
+        survival: Counts::low(1 << 2 | 1 << 3),
+        no_death: false,
+    };
+
+    /// HighLife：B36/S23，比康威生命游戏多了邻居数为 6 时出生
+    pub const HIGHLIFE: Rule = Rule {
+        birth: Counts::low(1 << 3 | 1 << 6),
+        survival: Counts::low(1 << 2 | 1 << 3),
+        no_death: false,
+    };
+
+    /// Day & Night：B3678/S34678，存活与死亡的 Cell 互换之后规则不变
+    pub const DAY_AND_NIGHT: Rule = Rule {
+        birth: Counts::low(1 << 3 | 1 << 6 | 1 << 7 | 1 << 8),
+        survival: Counts::low(1 << 3 | 1 << 4 | 1 << 6 | 1 << 7 | 1 << 8),
+        no_death: false,
+    };
+
+    /// Seeds：B2/S，每个 Cell 只存活一代
+    pub const SEEDS: Rule = Rule {
+        birth: Counts::low(1 << 2),
+        survival: Counts::EMPTY,
+        no_death: false,
+    };
+
+    /// 能完整表示的最大邻域半径，见 `Universe::live_neighbor_count_in_radius`
+    pub const MAX_RADIUS: u32 = 10;
+
+    /// 能表示的最大邻居数，即半径为 MAX_RADIUS 的完整邻域的大小
+    pub const MAX_NEIGHBORS: u32 = (2 * Rule::MAX_RADIUS + 1) * (2 * Rule::MAX_RADIUS + 1) - 1;
+
+    /// 由显式的邻居数列表构造规则，例如 `from_counts(&[3], &[2, 3])` 即 B3/S23；
+    /// 列表可以无序、可以重复，每个数必须在 0..=8 之间。
+    /// 列表按位存入与 B/S 记法相同的邻居数集合，tick 时判断一个邻居数是否在其中只需要一次位运算，
+    /// `Rule` 也因此可以保持 Copy；需要列表时用 `birth_counts` 和 `survival_counts` 取回
+    pub fn from_counts(birth_counts: &[u8], survival_counts: &[u8]) -> Result<Rule, UniverseError> {
+        let mask = |counts: &[u8], prefix: char| -> Result<Counts, UniverseError> {
+            let mut mask = Counts::EMPTY;
+            for &n in counts {
+                if n > 8 {
+                    return Err(UniverseError::InvalidRule {
+                        rule: format!("B{:?}/S{:?}", birth_counts, survival_counts),
+                        message: format!("{} count {} is out of range 0..=8", prefix, n),
+                    });
+                }
+                mask.insert(n as u32);
+            }
+            Ok(mask)
+        };
+        Ok(Rule {
+            birth: mask(birth_counts, 'B')?,
+            survival: mask(survival_counts, 'S')?,
+            no_death: false,
+        })
+    }
+
+    /// 会出生的邻居数，升序，与 `from_counts` 的参数类型相同
+    pub fn birth_counts(&self) -> Vec<u8> {
+        self.birth.iter().map(|n| n as u8).collect()
+    }
+
+    /// 会存活的邻居数，升序；`no_death` 模式下为 0..=8
+    pub fn survival_counts(&self) -> Vec<u8> {
+        self.effective_survival().iter().map(|n| n as u8).collect()
+    }
+
+    /// 出生条件的位掩码，按小端字节排列：第 n / 8 个字节的第 n % 8 位表示邻居数为 n 时出生，
+    /// 末尾为 0 的字节被省略，例如 B3 为 `[0b1000]`，B 为空
+    pub fn birth_mask(&self) -> Vec<u8> {
+        self.birth.to_bytes()
+    }
+
+    /// 存活条件的位掩码，格式与 `birth_mask` 相同，例如 S23 为 `[0b1100]`
+    pub fn survival_mask(&self) -> Vec<u8> {
+        self.effective_survival().to_bytes()
+    }
+
+    /// 打开或关闭 “Life without Death” 模式：出生条件不变，存活的 Cell 不论邻居数都继续存活。
+    /// 关闭后恢复原来的存活条件
+    pub fn with_no_death(self, no_death: bool) -> Rule {
+        Rule { no_death, ..self }
+    }
+
+    pub fn no_death(&self) -> bool {
+        self.no_death
+    }
+
+    /// 实际生效的存活条件：`no_death` 模式下邻居数为 0..=8 时都存活
+    fn effective_survival(&self) -> Counts {
+        if self.no_death {
+            self.survival.union(Counts::low(0x1ff))
+        } else {
+            self.survival
+        }
+    }
+
+    /// 根据当前状态和存活的邻居数计算下一代的状态
+    pub fn next_state(&self, cell: Cell, live_neighbors: u32) -> Cell {
+        let mask = match cell {
+            Cell::Alive if self.no_death => return Cell::Alive,
+            Cell::Alive => self.survival,
+            Cell::Dead => self.birth,
+        };
+        if mask.contains(live_neighbors) {
+            Cell::Alive
+        } else {
+            Cell::Dead
+        }
+    }
+}
+
+impl Default for Rule {
+    fn default() -> Rule {
+        Rule::CONWAY
+    }
+}
+
+impl FromStr for Rule {
+    type Err = UniverseError;
+
+    /// 解析 `B3/S23` 形式的规则（不区分大小写）
+    fn from_str(s: &str) -> Result<Rule, UniverseError> {
+        let invalid = |message: String| UniverseError::InvalidRule {
+            rule: s.to_string(),
+            message,
+        };
+        let (b, s_part) = s
+            .trim()
+            .split_once('/')
+            .ok_or_else(|| invalid("expected B.../S...".to_string()))?;
+
+        let parse = |part: &str, prefix: char| -> Result<Counts, UniverseError> {
+            let mut chars = part.chars();
+            match chars.next() {
+                Some(c) if c.eq_ignore_ascii_case(&prefix) => {}
+                _ => return Err(invalid(format!("expected '{}' section", prefix))),
+            }
+            let mut mask = Counts::EMPTY;
+            for c in chars {
+                match c.to_digit(10) {
+                    Some(n) if n <= 8 => mask.insert(n),
+                    _ => return Err(invalid(format!("unexpected '{}'", c))),
+                }
+            }
+            Ok(mask)
+        };
+
+        Ok(Rule {
+            birth: parse(b, 'B')?,
+            survival: parse(s_part, 'S')?,
+            no_death: false,
+        })
+    }
+}
+
+impl fmt::Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "B")?;
+        for n in self.birth.iter() {
+            write!(f, "{}", n)?;
+        }
+        write!(f, "/S")?;
+        for n in self.effective_survival().iter() {
+            write!(f, "{}", n)?;
+        }
+        Ok(())
+    }
+}
+
+/// Golly 的 MAP 记法中一个 512 位的下标依次由 NW、N、NE、W、自身、E、SW、S、SE 组成（高位在前），
+/// 这里换算为 `set_rule_table` 的下标：state << 8 | 八个邻居（NW 在第 0 位……SE 在第 7 位）
+fn map_index_to_table_index(index: usize) -> usize {
+    let bit = |n: usize| index >> n & 1;
+    bit(4) << 8
+        | bit(8)
+        | bit(7) << 1
+        | bit(6) << 2
+        | bit(5) << 3
+        | bit(3) << 4
+        | bit(2) << 5
+        | bit(1) << 6
+        | bit(0) << 7
+}
+
+/// 把 `set_rule_table` 格式的查找表编码为 Golly 的 `MAP` 记法（512 位的 base64，省略末尾的 `=`）
+pub(crate) fn table_to_map(table: &[u8]) -> String {
+    let mut bytes = vec![0u8; RULE_TABLE_LEN / 8];
+    for index in 0..RULE_TABLE_LEN {
+        if table[map_index_to_table_index(index)] == 1 {
+            bytes[index / 8] |= 0x80 >> (index % 8);
+        }
+    }
+    format!("MAP{}", base64_encode(&bytes).trim_end_matches('='))
+}
+
+/// 解析 Golly 的 `MAP` 记法，得到 `set_rule_table` 格式的查找表
+pub(crate) fn map_to_table(map: &str) -> Result<Vec<u8>, UniverseError> {
+    let invalid = |message: String| UniverseError::InvalidRule {
+        rule: map.to_string(),
+        message,
+    };
+    let encoded = map
+        .strip_prefix("MAP")
+        .ok_or_else(|| invalid("expected 'MAP'".to_string()))?;
+    let bytes = base64_decode(encoded).map_err(invalid)?;
+    if bytes.len() != RULE_TABLE_LEN / 8 {
+        return Err(invalid(format!(
+            "expected {} bits, found {}",
+            RULE_TABLE_LEN,
+            bytes.len() * 8
+        )));
+    }
+    let mut table = vec![0u8; RULE_TABLE_LEN];
+    for index in 0..RULE_TABLE_LEN {
+        table[map_index_to_table_index(index)] = bytes[index / 8] >> (7 - index % 8) & 1;
+    }
+    Ok(table)
+}
+
+/// 查找表只依赖当前状态和邻居数量时，返回等价的 B/S 规则
+pub(crate) fn totalistic_rule(table: &[u8]) -> Option<Rule> {
+    let mut counts: [Vec<u8>; 2] = [vec![], vec![]];
+    for (state, counts) in counts.iter_mut().enumerate() {
+        for n in 0..=8u32 {
+            let mut next = (0..256usize)
+                .filter(|neighbors| neighbors.count_ones() == n)
+                .map(|neighbors| table[state << 8 | neighbors]);
+            let first = next.next()?;
+            if next.any(|value| value != first) {
+                return None;
+            }
+            if first == 1 {
+                counts.push(n as u8);
+            }
+        }
+    }
+    Rule::from_counts(&counts[0], &counts[1]).ok()
+}
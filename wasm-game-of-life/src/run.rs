@@ -0,0 +1,123 @@
+#[cfg(feature = "web")]
+use wasm_bindgen::prelude::*;
+
+use crate::Universe;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ConditionKind {
+    Extinct,
+    Stable,
+    PopulationBelow(u32),
+    PopulationAbove(u32),
+    GenerationReached(u64),
+}
+
+/// `run_until` 的停止条件：
+/// - Extinct: 存活数量为 0
+/// - Stable: 一次 tick 中没有任何 Cell 发生变化
+/// - PopulationBelow(n): 存活数量小于 n
+/// - PopulationAbove(n): 存活数量大于 n
+/// - GenerationReached(g): 代数达到 g
+///
+/// 除了 Stable 之外，开始时已经满足的条件会立即停止，不执行任何 tick
+#[cfg_attr(feature = "web", wasm_bindgen)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct StopCondition {
+    kind: ConditionKind,
+}
+
+#[cfg_attr(feature = "web", wasm_bindgen)]
+impl StopCondition {
+    pub fn extinct() -> StopCondition {
+        StopCondition {
+            kind: ConditionKind::Extinct,
+        }
+    }
+
+    pub fn stable() -> StopCondition {
+        StopCondition {
+            kind: ConditionKind::Stable,
+        }
+    }
+
+    pub fn population_below(population: u32) -> StopCondition {
+        StopCondition {
+            kind: ConditionKind::PopulationBelow(population),
+        }
+    }
+
+    pub fn population_above(population: u32) -> StopCondition {
+        StopCondition {
+            kind: ConditionKind::PopulationAbove(population),
+        }
+    }
+
+    pub fn generation_reached(generation: u64) -> StopCondition {
+        StopCondition {
+            kind: ConditionKind::GenerationReached(generation),
+        }
+    }
+}
+
+impl RunResult {
+    pub(crate) fn new(condition_met: bool, generations: u32) -> RunResult {
+        RunResult {
+            condition_met,
+            generations,
+        }
+    }
+}
+
+impl StopCondition {
+    /// 不需要 tick 就能判断的条件是否已经满足，Stable 总是 false
+    fn holds(&self, universe: &Universe) -> bool {
+        match self.kind {
+            ConditionKind::Extinct => universe.population() == 0,
+            ConditionKind::Stable => false,
+            ConditionKind::PopulationBelow(n) => universe.population() < n,
+            ConditionKind::PopulationAbove(n) => universe.population() > n,
+            ConditionKind::GenerationReached(g) => universe.generation() >= g,
+        }
+    }
+}
+
+/// `run_until` 的结果
+#[cfg_attr(feature = "web", wasm_bindgen)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RunResult {
+    condition_met: bool,
+    generations: u32,
+}
+
+#[cfg_attr(feature = "web", wasm_bindgen)]
+impl RunResult {
+    /// 因为条件满足而停止时为 true，达到代数上限而停止时为 false
+    pub fn condition_met(&self) -> bool {
+        self.condition_met
+    }
+
+    /// 这次运行执行的 tick 次数
+    pub fn generations(&self) -> u32 {
+        self.generations
+    }
+}
+
+#[cfg_attr(feature = "web", wasm_bindgen)]
+impl Universe {
+    /// 持续 tick，直到 `condition` 满足或者执行了 `max_generations` 次为止，
+    /// 例如“存活数量跌破 X 或者过了 10000 代”。浏览器中长时间运行请使用 `run_until_async`
+    pub fn run_until(&mut self, condition: StopCondition, max_generations: u32) -> RunResult {
+        if condition.holds(self) {
+            return RunResult::new(true, 0);
+        }
+        for generations in 1..=max_generations {
+            let summary = self.step();
+            if condition.holds(self)
+                || (condition.kind == ConditionKind::Stable && !summary.changed)
+            {
+                return RunResult::new(true, generations);
+            }
+        }
+        RunResult::new(false, max_generations)
+    }
+}
@@ -0,0 +1,52 @@
+//! 在多个线程之间共享的 Universe：模拟线程调用 `tick`，界面线程读取状态。
+//! 只在没有 `web` 特性时编译：JS 回调和 Canvas 缓存不能跨线程，
+//! wasm-bindgen 导出的 Universe 无论如何都是单线程的
+
+use std::sync::{Arc, PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+use crate::{Cell, Universe};
+
+/// 没有 JS 句柄时引擎可以跨线程使用；在编译期检查，任何构建（包括 `cargo check`）都会发现回退
+const _: fn() = || {
+    fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<Universe>();
+    assert_send_sync::<SharedUniverse>();
+};
+
+/// `Arc<RwLock<Universe>>` 的简单封装，clone 得到的是同一个 Universe 的另一个句柄
+#[derive(Clone)]
+pub struct SharedUniverse {
+    inner: Arc<RwLock<Universe>>,
+}
+
+impl SharedUniverse {
+    pub fn new(universe: Universe) -> SharedUniverse {
+        SharedUniverse {
+            inner: Arc::new(RwLock::new(universe)),
+        }
+    }
+
+    /// 持有写锁计算一代，读取方最多只需要等待这一代
+    pub fn tick(&self) {
+        self.write().tick();
+    }
+
+    /// 持有读锁复制当前所有 Cell，顺序与 `Universe::get_cells` 相同
+    pub fn snapshot_cells(&self) -> Vec<Cell> {
+        self.read().get_cells().to_vec()
+    }
+
+    pub fn generation(&self) -> u64 {
+        self.read().generation()
+    }
+
+    /// 获取读锁；某个线程在持有锁时 panic 后仍然可以继续使用
+    pub fn read(&self) -> RwLockReadGuard<'_, Universe> {
+        self.inner.read().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// 获取写锁，用于 `tick` 以外的修改
+    pub fn write(&self) -> RwLockWriteGuard<'_, Universe> {
+        self.inner.write().unwrap_or_else(PoisonError::into_inner)
+    }
+}
@@ -0,0 +1,166 @@
+use std::collections::BTreeMap;
+
+use crate::{Universe, UniverseBuilder, UniverseError};
+
+/// 报告中“最有趣”的汤各保留几锅
+const INTERESTING_SOUPS: usize = 5;
+
+/// 一锅汤的结果。`seed` 可以用来重现这锅汤：
+/// `UniverseBuilder::new().width(w).height(h).density(d).seed(seed).build()`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SoupResult {
+    pub index: u32,
+    pub seed: u64,
+    /// 进入循环（静止或振荡）时的代数；在上限之内没有进入循环时为上限
+    pub generations: u32,
+    /// 进入循环时的周期，没有进入循环时为 None
+    pub period: Option<u32>,
+    pub final_population: u32,
+}
+
+/// `run_soup_search` 的汇总结果
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SoupReport {
+    pub soups: u32,
+    /// 在代数上限之内进入循环的汤的数量
+    pub stabilized: u32,
+    pub mean_final_population: f64,
+    pub mean_generations: f64,
+    /// 所有汤最终状态的 `census` 之和
+    pub counts: BTreeMap<String, u64>,
+    /// 存活代数最长的几锅汤，从长到短
+    pub longest_lived: Vec<SoupResult>,
+    /// 最终存活数量最多的几锅汤，从多到少
+    pub highest_population: Vec<SoupResult>,
+}
+
+/// 类似 Catagolue 的批量随机汤搜索：每锅汤以 (seed, 序号) 决定的种子随机填充，
+/// 演化到进入循环或者达到代数上限，记录最终存活数量、稳定所需的代数和物体统计。
+/// 可以用 `run` 分批执行，方便在浏览器中穿插让出事件循环
+#[derive(Clone, Debug)]
+pub struct SoupSearch {
+    width: u32,
+    height: u32,
+    density: f64,
+    soups: u32,
+    max_generations: u32,
+    seed: u64,
+    results: Vec<SoupResult>,
+    counts: BTreeMap<String, u64>,
+}
+
+impl SoupSearch {
+    /// 尺寸和密度按照 `UniverseBuilder` 的规则校验
+    pub fn new(
+        width: u32,
+        height: u32,
+        density: f64,
+        soups: u32,
+        max_generations: u32,
+        seed: u64,
+    ) -> Result<SoupSearch, UniverseError> {
+        let search = SoupSearch {
+            width,
+            height,
+            density,
+            soups,
+            max_generations,
+            seed,
+            results: Vec::with_capacity(soups as usize),
+            counts: BTreeMap::new(),
+        };
+        search.soup(0)?;
+        Ok(search)
+    }
+
+    /// 已经完成的汤的数量
+    pub fn completed(&self) -> u32 {
+        self.results.len() as u32
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.completed() >= self.soups
+    }
+
+    /// 再运行最多 `count` 锅汤，全部完成后返回 true
+    pub fn run(&mut self, count: u32) -> bool {
+        let end = self.soups.min(self.completed().saturating_add(count));
+        for index in self.completed()..end {
+            let mut universe = self.soup(index).unwrap();
+            let (generations, period) = match universe.run_until_cycle(self.max_generations) {
+                Some((start, period)) => (start, Some(period)),
+                None => (self.max_generations, None),
+            };
+            for (name, count) in universe.census().counts {
+                *self.counts.entry(name).or_insert(0) += count as u64;
+            }
+            self.results.push(SoupResult {
+                index,
+                seed: soup_seed(self.seed, index),
+                generations,
+                period,
+                final_population: universe.population(),
+            });
+        }
+        self.is_done()
+    }
+
+    /// 到目前为止完成的汤的汇总
+    pub fn report(&self) -> SoupReport {
+        let n = self.results.len();
+        if n == 0 {
+            return SoupReport::default();
+        }
+        let mean = |f: fn(&SoupResult) -> u32| {
+            self.results.iter().map(|r| f(r) as f64).sum::<f64>() / n as f64
+        };
+        let top = |f: fn(&SoupResult) -> u32| {
+            let mut sorted = self.results.clone();
+            // 稳定排序，相同时序号小的在前
+            sorted.sort_by_key(|r| std::cmp::Reverse(f(r)));
+            sorted.truncate(INTERESTING_SOUPS);
+            sorted
+        };
+        SoupReport {
+            soups: n as u32,
+            stabilized: self.results.iter().filter(|r| r.period.is_some()).count() as u32,
+            mean_final_population: mean(|r| r.final_population),
+            mean_generations: mean(|r| r.generations),
+            counts: self.counts.clone(),
+            longest_lived: top(|r| r.generations),
+            highest_population: top(|r| r.final_population),
+        }
+    }
+
+    /// 第 `index` 锅汤的初始状态
+    fn soup(&self, index: u32) -> Result<Universe, UniverseError> {
+        UniverseBuilder::new()
+            .width(self.width)
+            .height(self.height)
+            .density(self.density)
+            .seed(soup_seed(self.seed, index))
+            .build()
+    }
+}
+
+/// 一次运行全部 `soups` 锅汤并返回汇总，相同的参数总是得到相同的结果
+pub fn run_soup_search(
+    width: u32,
+    height: u32,
+    density: f64,
+    soups: u32,
+    max_generations: u32,
+    seed: u64,
+) -> Result<SoupReport, UniverseError> {
+    let mut search = SoupSearch::new(width, height, density, soups, max_generations, seed)?;
+    search.run(soups);
+    Ok(search.report())
+}
+
+/// 由总种子和序号得到每锅汤的种子（SplitMix64），相邻序号的种子互不相关
+fn soup_seed(seed: u64, index: u32) -> u64 {
+    let mut z = seed.wrapping_add((index as u64 + 1).wrapping_mul(0x9e37_79b9_7f4a_7c15));
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
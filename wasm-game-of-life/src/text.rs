@@ -0,0 +1,186 @@
+use std::fmt;
+use std::str::FromStr;
+
+#[cfg(feature = "web")]
+use wasm_bindgen::prelude::*;
+
+use crate::{Cell, Universe, UniverseError};
+
+/// 文本渲染使用的字符，默认为 ◼/◻，每行以换行结尾
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct Glyphs {
+    alive: char,
+    dead: char,
+    pub(crate) row_separator: String,
+}
+
+impl Default for Glyphs {
+    fn default() -> Glyphs {
+        Glyphs {
+            alive: '◼',
+            dead: '◻',
+            row_separator: "\n".to_string(),
+        }
+    }
+}
+
+impl Glyphs {
+    /// 两个字符必须不同，分隔符不能为空也不能包含字符，否则输出无法被无歧义地解析
+    fn new(alive: char, dead: char, row_separator: &str) -> Result<Glyphs, UniverseError> {
+        if alive == dead {
+            return Err(UniverseError::InvalidArgument(format!(
+                "alive and dead glyphs must differ, both are '{}'",
+                alive
+            )));
+        }
+        if row_separator.is_empty() || row_separator.contains(alive) || row_separator.contains(dead)
+        {
+            return Err(UniverseError::InvalidArgument(format!(
+                "invalid row separator {:?}: must be nonempty and must not contain a glyph",
+                row_separator
+            )));
+        }
+        Ok(Glyphs {
+            alive,
+            dead,
+            row_separator: row_separator.to_string(),
+        })
+    }
+
+    pub(crate) fn glyph(&self, cell: Cell) -> char {
+        match cell {
+            Cell::Alive => self.alive,
+            Cell::Dead => self.dead,
+        }
+    }
+}
+
+#[cfg_attr(feature = "web", wasm_bindgen)]
+impl Universe {
+    /// 设置 `render` 与 `Display` 使用的字符，两者必须不同，例如 `set_render_glyphs('#', '.')`
+    pub fn set_render_glyphs(&mut self, alive: char, dead: char) -> Result<(), UniverseError> {
+        self.glyphs = Glyphs::new(alive, dead, &self.glyphs.row_separator)?;
+        Ok(())
+    }
+
+    /// 设置每一行之后输出的分隔符，默认为 `\n`
+    pub fn set_render_row_separator(&mut self, separator: &str) -> Result<(), UniverseError> {
+        self.glyphs = Glyphs::new(self.glyphs.alive, self.glyphs.dead, separator)?;
+        Ok(())
+    }
+
+    /// 解析 `set_render_glyphs`、`set_render_row_separator` 设置之后 `Display` 的输出，
+    /// 只接受这两个字符；返回的 Universe 沿用这些设置，再次输出得到相同的文本
+    pub fn from_str_with_glyphs(
+        s: &str,
+        alive: char,
+        dead: char,
+        row_separator: &str,
+    ) -> Result<Universe, UniverseError> {
+        let glyphs = Glyphs::new(alive, dead, row_separator)?;
+        let rows: Vec<&str> = s
+            .strip_suffix(row_separator)
+            .unwrap_or(s)
+            .split(row_separator)
+            .collect();
+        let mut universe = Universe::parse_rows(&rows, |c| {
+            if c == alive {
+                Some(Cell::Alive)
+            } else if c == dead {
+                Some(Cell::Dead)
+            } else {
+                None
+            }
+        })?;
+        universe.glyphs = glyphs;
+        Ok(universe)
+    }
+
+    /// 只渲染 [row0, row1) x [col0, col1) 的区域，超出 Universe 的部分会被裁掉
+    pub fn render_region(&self, row0: u32, col0: u32, row1: u32, col1: u32) -> String {
+        let mut out = String::new();
+        self.push_region(&mut out, row0, col0, row1, col1);
+        out
+    }
+}
+
+impl Universe {
+    /// 与 `render` 输出相同，但清空并复用调用方的 String，每帧渲染时不需要重新分配
+    pub fn render_into(&self, buf: &mut String) {
+        buf.clear();
+        self.push_region(buf, 0, 0, self.height, self.width);
+    }
+
+    /// 每一项是一行文本，`cell` 把字符映射为 Cell，无法识别的字符返回 None
+    fn parse_rows(
+        rows: &[&str],
+        cell: impl Fn(char) -> Option<Cell>,
+    ) -> Result<Universe, UniverseError> {
+        let width = rows.first().map_or(0, |row| row.chars().count()) as u32;
+        let height = rows.len() as u32;
+        Universe::validate_dimensions(width, height)?;
+
+        let mut universe = Universe::with_size(width, height);
+        for (row, line) in rows.iter().enumerate() {
+            if line.chars().count() as u32 != width {
+                return Err(UniverseError::ParseError {
+                    line: row + 1,
+                    message: format!("expected {} cells per row", width),
+                });
+            }
+            for (column, c) in line.chars().enumerate() {
+                let index = universe.get_index(row as u32, column as u32);
+                universe.cells[index] = cell(c).ok_or_else(|| UniverseError::ParseError {
+                    line: row + 1,
+                    message: format!("unexpected character '{}'", c),
+                })?;
+            }
+        }
+        universe.sync_cells();
+        Ok(universe)
+    }
+
+    fn push_region(&self, out: &mut String, row0: u32, col0: u32, row1: u32, col1: u32) {
+        for row in row0..row1.min(self.height) {
+            for column in col0..col1.min(self.width) {
+                out.push(self.glyphs.glyph(self.cells[self.get_index(row, column)]));
+            }
+            out.push_str(&self.glyphs.row_separator);
+        }
+    }
+}
+
+impl fmt::Display for Universe {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.render_region(0, 0, self.height, self.width))
+    }
+}
+
+/// 存活 Cell 可以使用的字符，第一个为默认字符
+const ALIVE_GLYPHS: &[char] = &['◼', '#', 'O', 'o', '*', 'X', 'x', '1'];
+/// 死亡 Cell 可以使用的字符，第一个为默认字符
+const DEAD_GLYPHS: &[char] = &['◻', '.', '-', '_', ' ', '0'];
+
+impl FromStr for Universe {
+    type Err = UniverseError;
+
+    /// 解析 `Display` 的输出：每行一个网格行，各行长度必须相同；
+    /// 除默认的 ◼/◻ 外还接受常见的 ASCII 字符，例如 `#`/`O`/`*` 与 `.`/`-`。
+    /// 其它字符或者自定义的行分隔符请使用 `Universe::from_str_with_glyphs`
+    fn from_str(s: &str) -> Result<Universe, UniverseError> {
+        let rows: Vec<&str> = s
+            .lines()
+            .map(|line| line.trim_end_matches('\r'))
+            .filter(|line| !line.is_empty())
+            .collect();
+        Universe::parse_rows(&rows, |c| {
+            if ALIVE_GLYPHS.contains(&c) {
+                Some(Cell::Alive)
+            } else if DEAD_GLYPHS.contains(&c) {
+                Some(Cell::Dead)
+            } else {
+                None
+            }
+        })
+    }
+}
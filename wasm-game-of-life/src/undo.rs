@@ -0,0 +1,162 @@
+use std::collections::VecDeque;
+use std::mem::size_of;
+
+#[cfg(feature = "web")]
+use wasm_bindgen::prelude::*;
+
+use crate::{Cell, Universe};
+
+/// 默认保留的编辑次数
+const DEFAULT_CAPACITY: usize = 100;
+
+/// 编辑的撤销栈，与记录 tick 的 `RewindBuffer` 无关。每一项是一次编辑改变了的 Cell：
+/// 缓冲区下标（不受显示偏移影响）和编辑之前的状态，只占与改变的 Cell 数量成正比的内存
+#[derive(Clone, Debug)]
+pub(crate) struct UndoStack {
+    capacity: usize,
+    /// 从旧到新
+    edits: VecDeque<Vec<(u32, Cell)>>,
+}
+
+impl Default for UndoStack {
+    fn default() -> UndoStack {
+        UndoStack {
+            capacity: DEFAULT_CAPACITY,
+            edits: VecDeque::new(),
+        }
+    }
+}
+
+impl UndoStack {
+    pub(crate) fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity;
+        self.trim(capacity);
+    }
+
+    /// 只保留最近的 `depth` 次编辑，容量不变
+    fn trim(&mut self, depth: usize) {
+        while self.edits.len() > depth {
+            self.edits.pop_front();
+        }
+    }
+
+    /// 改变尺寸时调用：下标在新的网格上没有意义，全部丢弃，容量不变
+    pub(crate) fn clear(&mut self) {
+        self.edits.clear();
+    }
+
+    /// 与 `other` 交换保存的编辑，双方各自保留原来的容量
+    pub(crate) fn swap_edits(&mut self, other: &mut UndoStack) {
+        std::mem::swap(&mut self.edits, &mut other.edits);
+        self.trim(self.capacity);
+        other.trim(other.capacity);
+    }
+
+    /// 记录一次编辑；没有改变任何 Cell 的编辑不占用撤销栈
+    pub(crate) fn push(&mut self, changed: Vec<(u32, Cell)>) {
+        if self.capacity == 0 || changed.is_empty() {
+            return;
+        }
+        self.edits.push_back(changed);
+        self.trim(self.capacity);
+    }
+
+    fn byte_len(&self) -> usize {
+        self.edits
+            .iter()
+            .map(|changed| changed.len() * size_of::<(u32, Cell)>())
+            .sum()
+    }
+}
+
+#[cfg_attr(feature = "web", wasm_bindgen)]
+impl Universe {
+    /// 撤销最近一次编辑（`set_cell`、`set_cells_state`、`paint_line`、`perturb` 等修改 Cell 的操作），
+    /// 把这次编辑改变的 Cell 恢复为编辑之前的状态，其它 Cell 和代数都不变；tick 不是编辑，不会被撤销，
+    /// 回到之前的代数请使用 `rewind`。撤销栈为空时返回 false。
+    /// 改变尺寸（包括 `set_auto_expand` 引起的扩展）会清空撤销栈
+    pub fn undo(&mut self) -> bool {
+        let changed = match self.undo.edits.pop_back() {
+            Some(changed) => changed,
+            None => return false,
+        };
+        for (index, cell) in changed {
+            self.cells[index as usize] = cell;
+            self._cells[index as usize] = cell;
+            if cell == Cell::Alive {
+                self.note_alive(index);
+            }
+        }
+        self.active_cells = 0;
+        self.stable = false;
+        true
+    }
+
+    /// 撤销栈最多保留的编辑次数，默认 100；超出时丢弃最旧的编辑，0 表示不再记录并丢弃已有的编辑
+    pub fn set_undo_capacity(&mut self, capacity: u32) {
+        self.undo.set_capacity(capacity as usize);
+    }
+
+    /// 撤销栈中可以撤销的编辑次数
+    pub fn undo_stack_depth(&self) -> u32 {
+        self.undo.edits.len() as u32
+    }
+
+    /// 撤销栈占用的字节数（估算），每次编辑按改变的 Cell 数量计算
+    pub fn undo_memory_bytes(&self) -> usize {
+        self.undo.byte_len()
+    }
+
+    /// 丢弃撤销栈中的所有编辑以释放内存，之后的编辑照常记录，`set_undo_capacity` 的容量不变
+    pub fn clear_undo_stack(&mut self) {
+        self.undo.clear();
+    }
+
+    /// 丢弃撤销栈中最旧的编辑，只保留最近 `max_depth` 次，`set_undo_capacity` 的容量不变
+    pub fn trim_undo_stack_to(&mut self, max_depth: u32) {
+        self.undo.trim(max_depth as usize);
+    }
+}
+
+impl Universe {
+    /// 修改 Cell 的操作完成之后调用：编辑之前 `_cells` 与 `cells` 相同，
+    /// 两者的差异就是这次编辑改变的 Cell，记入撤销栈之后再同步 `_cells`
+    pub(crate) fn commit_edit(&mut self) {
+        if self.undo.capacity > 0 {
+            let changed = self
+                .cells
+                .iter()
+                .zip(&self._cells)
+                .enumerate()
+                .filter(|(_, (new, old))| new != old)
+                .map(|(index, (_, &old))| (index as u32, old))
+                .collect();
+            self.undo.push(changed);
+        }
+        self.sync_cells();
+        self.active_cells = 0;
+        self.stable = false;
+    }
+
+    /// 只改变了少数 Cell 的编辑：写入每个 (下标, 状态) 并只比较这些 Cell，不必比较整个网格；
+    /// 同一个下标出现多次时以最后一次为准
+    pub(crate) fn commit_cells_edit(&mut self, edits: &[(usize, Cell)]) {
+        for &(index, cell) in edits {
+            self.cells[index] = cell;
+        }
+        let mut changed = vec![];
+        for &(index, _) in edits {
+            // 同步之后重复的下标不会再被记录
+            if self._cells[index] != self.cells[index] {
+                changed.push((index as u32, self._cells[index]));
+                self._cells[index] = self.cells[index];
+                if self.cells[index] == Cell::Alive {
+                    self.note_alive(index as u32);
+                }
+            }
+        }
+        self.undo.push(changed);
+        self.active_cells = 0;
+        self.stable = false;
+    }
+}
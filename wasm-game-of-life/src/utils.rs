@@ -1,3 +1,5 @@
+use crate::EntropyMode;
+
 #[allow(dead_code)]
 pub fn set_panic_hook() {
     // When the `console_error_panic_hook` feature is enabled, we can call the
@@ -9,3 +11,71 @@ pub fn set_panic_hook() {
     #[cfg(feature = "console_error_panic_hook")]
     console_error_panic_hook::set_once();
 }
+
+#[cfg(not(all(feature = "web", target_arch = "wasm32")))]
+thread_local! {
+    static WARNINGS: std::cell::RefCell<Vec<String>> = const { std::cell::RefCell::new(vec![]) };
+}
+
+/// 输出一条警告：在浏览器中调用 `console.warn`，在原生平台上记录下来，
+/// 可以通过 `take_warnings` 取出
+pub fn warn(message: &str) {
+    #[cfg(all(feature = "web", target_arch = "wasm32"))]
+    web_sys::console::warn_1(&message.into());
+
+    #[cfg(not(all(feature = "web", target_arch = "wasm32")))]
+    WARNINGS.with(|warnings| warnings.borrow_mut().push(message.to_string()));
+}
+
+/// 取出当前线程记录的所有警告（仅原生平台）
+#[cfg(not(all(feature = "web", target_arch = "wasm32")))]
+pub fn take_warnings() -> Vec<String> {
+    WARNINGS.with(|warnings| warnings.borrow_mut().drain(..).collect())
+}
+
+thread_local! {
+    static ENTROPY: std::cell::Cell<(EntropyMode, u64)> =
+        const { std::cell::Cell::new((EntropyMode::Environment, 0)) };
+}
+
+/// 确定性模式下第一个 Universe 的种子
+const DETERMINISTIC_SEED: u64 = 0x5eed_c0de_0000_0000;
+
+pub fn set_entropy_mode(mode: EntropyMode) {
+    ENTROPY.with(|entropy| entropy.set((mode, 0)));
+}
+
+/// 新建 Universe 时随机数生成器的种子：确定性模式下为常数加计数器；否则浏览器中来自
+/// `Math.random()`，原生平台上来自系统时间，都不需要 getrandom 读取操作系统的熵
+pub fn initial_seed() -> u64 {
+    let (mode, counter) = ENTROPY.with(|entropy| entropy.get());
+    if mode == EntropyMode::Deterministic {
+        ENTROPY.with(|entropy| entropy.set((mode, counter + 1)));
+        return DETERMINISTIC_SEED.wrapping_add(counter);
+    }
+
+    #[cfg(all(feature = "web", target_arch = "wasm32"))]
+    return (js_sys::Math::random() * u64::MAX as f64) as u64;
+
+    #[cfg(not(target_arch = "wasm32"))]
+    return std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_nanos() as u64);
+
+    #[cfg(all(not(feature = "web"), target_arch = "wasm32"))]
+    0
+}
+
+/// 当前时间（毫秒）：浏览器中来自 `Date.now()`，原生平台上来自系统时间，其它环境为 0
+pub fn now_ms() -> u64 {
+    #[cfg(all(feature = "web", target_arch = "wasm32"))]
+    return js_sys::Date::now() as u64;
+
+    #[cfg(not(target_arch = "wasm32"))]
+    return std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_millis() as u64);
+
+    #[cfg(all(not(feature = "web"), target_arch = "wasm32"))]
+    0
+}
@@ -0,0 +1,111 @@
+#[cfg(feature = "web")]
+use wasm_bindgen::prelude::*;
+
+use crate::render::{ALIVE_RGBA, DEAD_RGBA};
+use crate::{Cell, Universe};
+
+/// Universe 中可见的矩形区域，坐标为逻辑坐标（已考虑环形偏移）
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct Viewport {
+    pub(crate) row: u32,
+    pub(crate) col: u32,
+    pub(crate) rows: u32,
+    pub(crate) cols: u32,
+}
+
+impl Viewport {
+    /// 覆盖整个 Universe 的视口
+    pub(crate) fn full(width: u32, height: u32) -> Viewport {
+        Viewport {
+            row: 0,
+            col: 0,
+            rows: height,
+            cols: width,
+        }
+    }
+
+    /// 将视口限制在 Universe 的范围内
+    fn clamped(self, width: u32, height: u32) -> Viewport {
+        let rows = self.rows.min(height);
+        let cols = self.cols.min(width);
+        Viewport {
+            row: self.row.min(height - rows),
+            col: self.col.min(width - cols),
+            rows,
+            cols,
+        }
+    }
+}
+
+#[cfg_attr(feature = "web", wasm_bindgen)]
+impl Universe {
+    /// 设置视口，超出范围的部分会被限制在 Universe 内；默认视口为整个 Universe
+    /// 缩放只需要设置更小或更大的视口，由渲染端负责放大
+    pub fn set_viewport(&mut self, origin_row: u32, origin_col: u32, rows: u32, cols: u32) {
+        self.viewport = Viewport {
+            row: origin_row,
+            col: origin_col,
+            rows,
+            cols,
+        }
+        .clamped(self.width, self.height);
+    }
+
+    /// 平移视口，到达边缘时停止
+    pub fn pan(&mut self, d_row: i32, d_col: i32) {
+        let row = (self.viewport.row as i64 + d_row as i64).max(0) as u32;
+        let col = (self.viewport.col as i64 + d_col as i64).max(0) as u32;
+        self.set_viewport(row, col, self.viewport.rows, self.viewport.cols);
+    }
+
+    pub fn viewport_row(&self) -> u32 {
+        self.viewport.row
+    }
+
+    pub fn viewport_col(&self) -> u32 {
+        self.viewport.col
+    }
+
+    pub fn viewport_rows(&self) -> u32 {
+        self.viewport.rows
+    }
+
+    pub fn viewport_cols(&self) -> u32 {
+        self.viewport.cols
+    }
+
+    /// 视口内每个 Cell 对应一个 RGBA 像素，按行排列
+    pub fn render_rgba(&self) -> Vec<u8> {
+        let mut rgba = Vec::with_capacity((self.viewport.rows * self.viewport.cols * 4) as usize);
+        for (_, _, cell) in self.visible_cells() {
+            rgba.extend_from_slice(if cell == Cell::Alive {
+                &ALIVE_RGBA
+            } else {
+                &DEAD_RGBA
+            });
+        }
+        rgba
+    }
+
+    /// 视口内所有存活 Cell 的坐标，展开为 [row0, col0, row1, col1, ...]
+    /// 坐标相对于视口的左上角
+    pub fn live_cells(&self) -> Vec<u32> {
+        self.visible_cells()
+            .filter(|&(_, _, cell)| cell == Cell::Alive)
+            .flat_map(|(row, col, _)| [row, col])
+            .collect()
+    }
+}
+
+impl Universe {
+    /// 按行遍历视口内的 Cell，返回 (视口内的行, 视口内的列, Cell)
+    fn visible_cells(&self) -> impl Iterator<Item = (u32, u32, Cell)> + '_ {
+        let viewport = self.viewport;
+        (0..viewport.rows).flat_map(move |row| {
+            (0..viewport.cols).map(move |col| {
+                let index = self.get_index(viewport.row + row, viewport.col + col);
+                (row, col, self.cells[index])
+            })
+        })
+    }
+}
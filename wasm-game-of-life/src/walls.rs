@@ -0,0 +1,49 @@
+#[cfg(feature = "web")]
+use wasm_bindgen::prelude::*;
+
+use crate::{Universe, UniverseError};
+
+#[cfg_attr(feature = "web", wasm_bindgen)]
+impl Universe {
+    /// 将 (row, column) 设为墙或取消墙：墙保持设置时的状态，tick 不会改变它，
+    /// 可以用来搭建迷宫一类的实验。改变尺寸时所有墙会被清除
+    pub fn set_wall(&mut self, row: u32, column: u32, wall: bool) -> Result<(), UniverseError> {
+        self.check_bounds(row, column)?;
+        if self.walls.is_empty() {
+            if !wall {
+                return Ok(());
+            }
+            self.walls = vec![false; self.cells.len()];
+        }
+        let index = self.get_index(row, column);
+        self.walls[index] = wall;
+        Ok(())
+    }
+
+    pub fn is_wall(&self, row: u32, column: u32) -> bool {
+        row < self.height && column < self.width && self.is_wall_index(self.get_index(row, column))
+    }
+
+    pub fn clear_walls(&mut self) {
+        self.walls.clear();
+    }
+
+    /// 计算邻居时墙是否一律视为死亡（默认）；为 false 时按墙自身的状态计算
+    pub fn set_walls_count_as_dead(&mut self, count_as_dead: bool) {
+        self.walls_count_as_dead = count_as_dead;
+    }
+}
+
+impl Universe {
+    pub(crate) fn is_wall_index(&self, index: usize) -> bool {
+        !self.walls.is_empty() && self.walls[index]
+    }
+
+    /// 计算邻居数时 self.cells[index] 贡献的值
+    pub(crate) fn neighbor_value(&self, index: usize) -> u32 {
+        if self.walls_count_as_dead && self.is_wall_index(index) {
+            return 0;
+        }
+        self.cells[index] as u32
+    }
+}
@@ -0,0 +1,390 @@
+//! 浏览器相关的部分：Canvas 绘制、localStorage、事件回调、Web Worker 以及调试工具，
+//! 只在启用 `web` 特性时编译；引擎本身不依赖这里的任何内容
+
+mod canvas;
+mod events;
+mod storage;
+#[cfg(feature = "worker")]
+mod worker;
+
+pub(crate) use canvas::FrameCache;
+
+use std::convert::TryFrom;
+
+use wasm_bindgen::prelude::*;
+use web_sys::console;
+
+use crate::{
+    compare_engines, Cell, Metrics, Recorder, Replay, Rule, RunResult, SoupResult, SoupSearch,
+    StopCondition, Universe, UniverseError,
+};
+
+#[wasm_bindgen]
+extern "C" {
+    fn alert(s: &str);
+}
+
+#[wasm_bindgen]
+pub fn greet() {
+    alert("Hello, wasm-game-of-life!");
+}
+
+/// 在浏览器控制台中统计一段代码的耗时，离开作用域时结束计时
+pub struct Timer<'a> {
+    name: &'a str,
+}
+
+impl<'a> Timer<'a> {
+    pub fn new(name: &'a str) -> Timer<'a> {
+        console::time_with_label(name);
+        Timer { name }
+    }
+}
+
+impl<'a> Drop for Timer<'a> {
+    fn drop(&mut self) {
+        console::time_end_with_label(self.name);
+    }
+}
+
+#[wasm_bindgen]
+impl Universe {
+    /// `simulate_and_record` 的 JS 版本，返回由 Uint8Array 组成的数组
+    #[wasm_bindgen(js_name = simulate_and_record)]
+    pub fn simulate_and_record_js(&mut self, n_ticks: u32) -> js_sys::Array {
+        self.simulate_and_record(n_ticks)
+            .iter()
+            .map(|snapshot| js_sys::Uint8Array::from(&snapshot[..]))
+            .collect()
+    }
+
+    /// `activity_centroid` 的 JS 版本，返回 `[row, column]`，没有存活 Cell 时返回 undefined
+    #[wasm_bindgen(js_name = activity_centroid)]
+    pub fn activity_centroid_js(&self) -> Option<Vec<f64>> {
+        self.activity_centroid().map(|(row, col)| vec![row, col])
+    }
+
+    /// `set_rule_schedule` 的 JS 版本，规则使用 B/S 记法，任何一个不合法时保持原来的规则表
+    #[wasm_bindgen(js_name = set_rule_schedule)]
+    pub fn set_rule_schedule_js(&mut self, rules: Vec<String>) -> Result<(), UniverseError> {
+        let rules = rules
+            .iter()
+            .map(|rule| rule.parse())
+            .collect::<Result<Vec<Rule>, UniverseError>>()?;
+        self.set_rule_schedule(rules);
+        Ok(())
+    }
+
+    /// `set_cells_state` 的 JS 版本，三个等长的数组依次给出行、列和状态（0 或 1）
+    #[wasm_bindgen(js_name = set_cells_state)]
+    pub fn set_cells_state_js(
+        &mut self,
+        rows: &[u32],
+        columns: &[u32],
+        states: &[u8],
+    ) -> Result<(), UniverseError> {
+        for len in [columns.len(), states.len()] {
+            if len != rows.len() {
+                return Err(UniverseError::LengthMismatch {
+                    expected: rows.len(),
+                    actual: len,
+                });
+            }
+        }
+        let cells = rows
+            .iter()
+            .zip(columns)
+            .zip(states)
+            .map(|((&row, &column), &state)| Ok((row, column, Cell::try_from(state)?)))
+            .collect::<Result<Vec<_>, UniverseError>>()?;
+        self.set_cells_state(&cells)
+    }
+
+    /// `detect_still_lifes` 的 JS 版本，每个包围盒依次为 row、column、height、width 四个数
+    #[wasm_bindgen(js_name = detect_still_lifes)]
+    pub fn detect_still_lifes_js(&self) -> Vec<u32> {
+        self.detect_still_lifes()
+            .into_iter()
+            .flat_map(|(row, column, height, width)| [row, column, height, width])
+            .collect()
+    }
+
+    /// `census` 的 JS 版本：`{ counts: { block: 12, blinker: 7, ... }, unidentified: [[row, column, height, width], ...] }`
+    #[wasm_bindgen(js_name = census)]
+    pub fn census_js(&self) -> JsValue {
+        let census = self.census();
+        let counts = js_sys::Object::new();
+        for (name, count) in census.counts.iter() {
+            let _ = js_sys::Reflect::set(&counts, &name.into(), &(*count).into());
+        }
+        let unidentified: js_sys::Array = census
+            .unidentified
+            .iter()
+            .map(|&(row, column, height, width)| {
+                js_sys::Array::of4(&row.into(), &column.into(), &height.into(), &width.into())
+            })
+            .collect();
+
+        let result = js_sys::Object::new();
+        let _ = js_sys::Reflect::set(&result, &"counts".into(), &counts);
+        let _ = js_sys::Reflect::set(&result, &"unidentified".into(), &unidentified);
+        result.into()
+    }
+
+    /// `metrics` 的 JS 版本：`{ density, entropy, activeCells }`
+    #[wasm_bindgen(js_name = metrics)]
+    pub fn metrics_js(&self) -> JsValue {
+        metrics_object(&self.metrics()).into()
+    }
+
+    /// `get_metrics_timeline` 的 JS 版本，每一项与 `metrics` 的格式相同
+    #[wasm_bindgen(js_name = get_metrics_timeline)]
+    pub fn get_metrics_timeline_js(&self) -> JsValue {
+        let timeline: js_sys::Array = self
+            .get_metrics_timeline()
+            .iter()
+            .map(metrics_object)
+            .collect();
+        timeline.into()
+    }
+
+    /// `live_components` 的 JS 版本：`[{ id, cellCount, row, column, height, width, centroid: [row, column] }, ...]`
+    #[wasm_bindgen(js_name = components)]
+    pub fn components_js(&self) -> JsValue {
+        let components: js_sys::Array = self
+            .live_components()
+            .iter()
+            .map(|component| {
+                let object = js_sys::Object::new();
+                for (key, value) in [
+                    ("id", component.id),
+                    ("cellCount", component.cell_count),
+                    ("row", component.row),
+                    ("column", component.column),
+                    ("height", component.height),
+                    ("width", component.width),
+                ] {
+                    let _ = js_sys::Reflect::set(&object, &key.into(), &value.into());
+                }
+                let (row, column) = component.centroid;
+                let centroid = js_sys::Array::of2(&row.into(), &column.into());
+                let _ = js_sys::Reflect::set(&object, &"centroid".into(), &centroid);
+                object
+            })
+            .collect();
+        components.into()
+    }
+
+    /// `detect_gliders` 的 JS 版本：`[{ row, column, phase, heading }, ...]`，heading 是 `Direction` 的值
+    #[wasm_bindgen(js_name = detect_gliders)]
+    pub fn detect_gliders_js(&self) -> JsValue {
+        let gliders: js_sys::Array = self
+            .detect_gliders()
+            .iter()
+            .map(|glider| {
+                let object = js_sys::Object::new();
+                for (key, value) in [
+                    ("row", glider.row),
+                    ("column", glider.column),
+                    ("phase", glider.phase as u32),
+                    ("heading", glider.heading as u32),
+                ] {
+                    let _ = js_sys::Reflect::set(&object, &key.into(), &value.into());
+                }
+                object
+            })
+            .collect();
+        gliders.into()
+    }
+
+    /// `from_fn` 的 JS 版本：对每个 (row, column) 调用 `f(row, column)`，返回值为真时 Cell 存活。
+    /// 每个 Cell 都要跨越一次 JS 边界，速度较慢，只适合用于初始化；`f` 抛出的异常会原样抛出
+    pub fn from_js_fn(width: u32, height: u32, f: &js_sys::Function) -> Result<Universe, JsValue> {
+        Universe::try_from_fn(width, height, |row, column| {
+            let alive = f.call2(&JsValue::NULL, &row.into(), &column.into())?;
+            Ok(Cell::from(alive.is_truthy()))
+        })
+    }
+}
+
+fn metrics_object(metrics: &Metrics) -> js_sys::Object {
+    let object = js_sys::Object::new();
+    let entries: [(&str, JsValue); 3] = [
+        ("density", metrics.density.into()),
+        ("entropy", metrics.entropy.into()),
+        ("activeCells", metrics.active_cells.into()),
+    ];
+    for (key, value) in entries.iter() {
+        let _ = js_sys::Reflect::set(&object, &(*key).into(), value);
+    }
+    object
+}
+
+/// `compare_engines` 的 JS 版本，供演示页面的引擎下拉菜单使用，返回数组，每一项为
+/// `{ engine, skipped, generations, elapsedMs, generationsPerSec }`，
+/// 当前构建中不可用的引擎 skipped 为 true
+#[wasm_bindgen(js_name = compare_engines)]
+pub fn compare_engines_js(
+    width: u32,
+    height: u32,
+    density: f64,
+    seed: u64,
+    generations: u32,
+) -> Result<JsValue, UniverseError> {
+    let results: js_sys::Array = compare_engines(width, height, density, seed, generations)?
+        .iter()
+        .map(|result| {
+            let object = js_sys::Object::new();
+            let entries: [(&str, JsValue); 5] = [
+                ("engine", result.engine.name().into()),
+                ("skipped", result.skipped.into()),
+                ("generations", result.generations.into()),
+                ("elapsedMs", (result.elapsed_ms as f64).into()),
+                ("generationsPerSec", result.generations_per_sec.into()),
+            ];
+            for (key, value) in entries.iter() {
+                let _ = js_sys::Reflect::set(&object, &(*key).into(), value);
+            }
+            object
+        })
+        .collect();
+    Ok(results.into())
+}
+
+/// `run_soup_search` 每运行这么多锅汤就让出一次事件循环
+const SOUPS_PER_YIELD: u32 = 4;
+
+/// `run_soup_search` 的 JS 版本，返回的 Promise 完成时得到
+/// `{ soups, stabilized, meanFinalPopulation, meanGenerations, counts, longestLived, highestPopulation }`，
+/// 后两项的每一项为 `{ index, seed, generations, period, finalPopulation }`，seed 是 BigInt。
+/// 每运行几锅汤就通过 `setTimeout` 让出事件循环，上千锅汤也不会让页面失去响应
+#[wasm_bindgen(js_name = run_soup_search)]
+pub async fn run_soup_search_js(
+    width: u32,
+    height: u32,
+    density: f64,
+    soups: u32,
+    max_generations: u32,
+    seed: u64,
+) -> Result<JsValue, JsValue> {
+    let mut search = SoupSearch::new(width, height, density, soups, max_generations, seed)?;
+    while !search.run(SOUPS_PER_YIELD) {
+        yield_to_event_loop().await?;
+    }
+    let report = search.report();
+
+    let counts = js_sys::Object::new();
+    for (name, &count) in report.counts.iter() {
+        let _ = js_sys::Reflect::set(&counts, &name.into(), &(count as f64).into());
+    }
+    let soups_js = |results: &[SoupResult]| -> js_sys::Array {
+        results
+            .iter()
+            .map(|result| {
+                let object = js_sys::Object::new();
+                let entries: [(&str, JsValue); 5] = [
+                    ("index", result.index.into()),
+                    ("seed", result.seed.into()),
+                    ("generations", result.generations.into()),
+                    ("period", result.period.map_or(JsValue::NULL, JsValue::from)),
+                    ("finalPopulation", result.final_population.into()),
+                ];
+                for (key, value) in entries.iter() {
+                    let _ = js_sys::Reflect::set(&object, &(*key).into(), value);
+                }
+                object
+            })
+            .collect()
+    };
+
+    let result = js_sys::Object::new();
+    let entries: [(&str, JsValue); 7] = [
+        ("soups", report.soups.into()),
+        ("stabilized", report.stabilized.into()),
+        ("meanFinalPopulation", report.mean_final_population.into()),
+        ("meanGenerations", report.mean_generations.into()),
+        ("counts", counts.into()),
+        ("longestLived", soups_js(&report.longest_lived).into()),
+        (
+            "highestPopulation",
+            soups_js(&report.highest_population).into(),
+        ),
+    ];
+    for (key, value) in entries.iter() {
+        let _ = js_sys::Reflect::set(&result, &(*key).into(), value);
+    }
+    Ok(result.into())
+}
+
+/// `run_until_async` 每执行这么多代就让出一次事件循环
+const GENERATIONS_PER_YIELD: u32 = 500;
+
+#[wasm_bindgen]
+impl Universe {
+    /// `run_until` 的异步版本：与 `run_in_worker` 一样在当前状态的副本上运行，
+    /// 每执行几百代就通过 `setTimeout` 让出事件循环，长时间运行也不会让页面失去响应。
+    /// Promise 完成时得到 `{ universe, result }`，universe 是运行之后的副本，
+    /// 可以用 `universe.swap(copy)` 换回，result 是 `RunResult`
+    pub fn run_until_async(
+        &self,
+        condition: StopCondition,
+        max_generations: u32,
+    ) -> js_sys::Promise {
+        let mut universe = self.duplicate();
+        wasm_bindgen_futures::future_to_promise(async move {
+            let mut generations = 0;
+            let result = loop {
+                let chunk = (max_generations - generations).min(GENERATIONS_PER_YIELD);
+                let result = universe.run_until(condition, chunk);
+                generations += result.generations();
+                if result.condition_met() || generations == max_generations {
+                    break RunResult::new(result.condition_met(), generations);
+                }
+                yield_to_event_loop().await?;
+            };
+            let object = js_sys::Object::new();
+            let _ = js_sys::Reflect::set(&object, &"universe".into(), &universe.into());
+            let _ = js_sys::Reflect::set(&object, &"result".into(), &result.into());
+            Ok(object.into())
+        })
+    }
+}
+
+/// 通过 `setTimeout(0)` 让出一次事件循环，在页面和 Worker 中都可以使用
+async fn yield_to_event_loop() -> Result<(), JsValue> {
+    let promise = js_sys::Promise::new(&mut |resolve, reject| {
+        let global = js_sys::global();
+        let scheduled = js_sys::Reflect::get(&global, &"setTimeout".into())
+            .and_then(|set_timeout| set_timeout.dyn_into::<js_sys::Function>())
+            .and_then(|set_timeout| set_timeout.call2(&global, &resolve, &0.into()));
+        if let Err(err) = scheduled {
+            let _ = reject.call1(&JsValue::NULL, &err);
+        }
+    });
+    wasm_bindgen_futures::JsFuture::from(promise).await?;
+    Ok(())
+}
+
+#[wasm_bindgen]
+impl Recorder {
+    /// 当前状态的副本
+    #[wasm_bindgen(js_name = universe)]
+    pub fn universe_js(&self) -> Universe {
+        self.universe().duplicate()
+    }
+}
+
+#[wasm_bindgen]
+impl Replay {
+    /// 重放到当前位置的 Universe 的副本
+    #[wasm_bindgen(js_name = universe)]
+    pub fn universe_js(&self) -> Universe {
+        self.universe().duplicate()
+    }
+}
+
+impl From<UniverseError> for JsValue {
+    fn from(err: UniverseError) -> JsValue {
+        js_sys::Error::new(&err.to_string()).into()
+    }
+}
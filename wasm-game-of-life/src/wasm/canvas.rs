@@ -0,0 +1,264 @@
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::Clamped;
+#[cfg(feature = "offscreen")]
+use web_sys::OffscreenCanvasRenderingContext2d;
+use web_sys::{CanvasRenderingContext2d, ImageData};
+
+use crate::render::{ALIVE_RGBA, DEAD_RGBA};
+use crate::{Cell, Universe};
+
+const GRID_COLOR: &str = "#CCCCCC";
+const DEAD_COLOR: &str = "#FFFFFF";
+const ALIVE_COLOR: &str = "#000000";
+
+/// `tick_and_write_canvas` 缓存的上一帧，只有发生变化的 Cell 需要重新写入像素
+#[derive(Clone)]
+pub(crate) struct FrameCache {
+    /// 绘制时网格的 (width, height)，自动扩展之后与当前尺寸不同，需要整体重绘
+    size: (u32, u32),
+    cell_px: u32,
+    row_offset: u32,
+    col_offset: u32,
+    cells: Vec<Cell>,
+    pixels: Vec<u8>,
+}
+
+/// 绘制时用到的 Canvas 方法，主线程的 CanvasRenderingContext2d 和
+/// Worker 中的 OffscreenCanvasRenderingContext2d 都实现了这些方法
+pub(crate) trait CanvasContext {
+    fn begin_path(&self);
+    fn set_fill_color(&self, color: &str);
+    fn set_stroke_color(&self, color: &str);
+    fn move_to(&self, x: f64, y: f64);
+    fn line_to(&self, x: f64, y: f64);
+    fn stroke(&self);
+    fn fill_rect(&self, x: f64, y: f64, w: f64, h: f64);
+}
+
+macro_rules! impl_canvas_context {
+    ( $ctx:ty ) => {
+        impl CanvasContext for $ctx {
+            fn begin_path(&self) {
+                <$ctx>::begin_path(self)
+            }
+
+            fn set_fill_color(&self, color: &str) {
+                self.set_fill_style_str(color)
+            }
+
+            fn set_stroke_color(&self, color: &str) {
+                self.set_stroke_style_str(color)
+            }
+
+            fn move_to(&self, x: f64, y: f64) {
+                <$ctx>::move_to(self, x, y)
+            }
+
+            fn line_to(&self, x: f64, y: f64) {
+                <$ctx>::line_to(self, x, y)
+            }
+
+            fn stroke(&self) {
+                <$ctx>::stroke(self)
+            }
+
+            fn fill_rect(&self, x: f64, y: f64, w: f64, h: f64) {
+                <$ctx>::fill_rect(self, x, y, w, h)
+            }
+        }
+    };
+}
+
+impl_canvas_context!(CanvasRenderingContext2d);
+#[cfg(feature = "offscreen")]
+impl_canvas_context!(OffscreenCanvasRenderingContext2d);
+
+impl Universe {
+    /// 与 www/index.js 中的 drawGrid/drawCells 相同：每个 Cell 占 cell_size 像素，
+    /// Cell 之间留 1 像素的网格线
+    pub(crate) fn draw_with<C: CanvasContext>(&self, ctx: &C, cell_size: u32) {
+        let step = (cell_size + 1) as f64;
+        let size = cell_size as f64;
+
+        ctx.begin_path();
+        ctx.set_stroke_color(GRID_COLOR);
+        for i in 0..=self.width {
+            ctx.move_to(i as f64 * step + 1.0, 0.0);
+            ctx.line_to(i as f64 * step + 1.0, step * self.height as f64 + 1.0);
+        }
+        for j in 0..=self.height {
+            ctx.move_to(0.0, j as f64 * step + 1.0);
+            ctx.line_to(step * self.width as f64 + 1.0, j as f64 * step + 1.0);
+        }
+        ctx.stroke();
+
+        ctx.begin_path();
+        for (state, color) in [(Cell::Alive, ALIVE_COLOR), (Cell::Dead, DEAD_COLOR)] {
+            ctx.set_fill_color(color);
+            for row in 0..self.height {
+                for column in 0..self.width {
+                    if self.cells[self.get_index(row, column)] == state {
+                        ctx.fill_rect(
+                            column as f64 * step + 1.0,
+                            row as f64 * step + 1.0,
+                            size,
+                            size,
+                        );
+                    }
+                }
+            }
+        }
+        ctx.stroke();
+    }
+
+    /// 将 (row, column) 处 Cell 对应的 cell_px × cell_px 个像素填充为 color
+    fn fill_cell_pixels(
+        &self,
+        buf: &mut [u8],
+        row: u32,
+        column: u32,
+        cell_px: u32,
+        color: [u8; 4],
+    ) {
+        let cell_px = cell_px as usize;
+        let row_bytes = self.width as usize * cell_px * 4;
+        for y in 0..cell_px {
+            let start = (row as usize * cell_px + y) * row_bytes + column as usize * cell_px * 4;
+            for pixel in buf[start..start + cell_px * 4].chunks_exact_mut(4) {
+                pixel.copy_from_slice(&color);
+            }
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl Universe {
+    /// 在主线程的 Canvas 上绘制网格和所有 Cell
+    pub fn draw(&self, ctx: &CanvasRenderingContext2d, cell_size: u32) {
+        self.draw_with(ctx, cell_size);
+    }
+
+    /// 在 Rust 中生成整个 Universe 的 ImageData 并通过 `putImageData` 一次性绘制，
+    /// 每个 Cell 占 cell_px × cell_px 像素，不绘制网格线
+    pub fn write_state_to_canvas(
+        &self,
+        ctx: &CanvasRenderingContext2d,
+        cell_px: u32,
+    ) -> Result<(), JsValue> {
+        let (width, height) = self.pixel_size(cell_px)?;
+        let mut buf = vec![0u8; width as usize * height as usize * 4];
+        self.write_pixels(&mut buf, cell_px, ALIVE_RGBA, DEAD_RGBA);
+
+        let image = ImageData::new_with_u8_clamped_array_and_sh(Clamped(&buf), width, height)?;
+        ctx.put_image_data(&image, 0.0, 0.0)
+    }
+
+    /// 在一次 Wasm 调用中完成 tick 和绘制：与上一帧相比只重写发生变化的 Cell，
+    /// 并只把变化区域通过 `putImageData` 的 dirty 矩形提交给 Canvas。
+    /// tick 引起自动扩展时按新的尺寸整体重绘，Canvas 需要随之调整大小
+    pub fn tick_and_write_canvas(
+        &mut self,
+        ctx: &CanvasRenderingContext2d,
+        cell_px: u32,
+    ) -> Result<(), JsValue> {
+        // 先校验 cell_px，不合法时不 tick；tick 可能扩展网格，像素尺寸在 tick 之后重新计算
+        self.pixel_size(cell_px)?;
+        self.tick();
+        let (width, height) = self.pixel_size(cell_px)?;
+
+        let mut frame = match self.frame.take() {
+            Some(frame)
+                if frame.size == (self.width, self.height)
+                    && frame.cell_px == cell_px
+                    && frame.row_offset == self.row_offset
+                    && frame.col_offset == self.col_offset
+                    && frame.cells.len() == self.cells.len() =>
+            {
+                frame
+            }
+            _ => {
+                // 第一次绘制或者参数发生变化，整体重绘
+                let mut pixels = vec![0u8; width as usize * height as usize * 4];
+                self.write_pixels(&mut pixels, cell_px, ALIVE_RGBA, DEAD_RGBA);
+                let image =
+                    ImageData::new_with_u8_clamped_array_and_sh(Clamped(&pixels), width, height)?;
+                ctx.put_image_data(&image, 0.0, 0.0)?;
+
+                self.frame = Some(FrameCache {
+                    size: (self.width, self.height),
+                    cell_px,
+                    row_offset: self.row_offset,
+                    col_offset: self.col_offset,
+                    cells: self.cells.clone(),
+                    pixels,
+                });
+                return Ok(());
+            }
+        };
+
+        // 变化区域的包围盒 (row0, col0, row1, col1)
+        let mut dirty: Option<(u32, u32, u32, u32)> = None;
+        for row in 0..self.height {
+            for column in 0..self.width {
+                let index = self.get_index(row, column);
+                let cell = self.cells[index];
+                if frame.cells[index] == cell {
+                    continue;
+                }
+                frame.cells[index] = cell;
+                let color = if cell == Cell::Alive {
+                    ALIVE_RGBA
+                } else {
+                    DEAD_RGBA
+                };
+                self.fill_cell_pixels(&mut frame.pixels, row, column, cell_px, color);
+                dirty = Some(match dirty {
+                    None => (row, column, row, column),
+                    Some((r0, c0, r1, c1)) => {
+                        (r0.min(row), c0.min(column), r1.max(row), c1.max(column))
+                    }
+                });
+            }
+        }
+
+        let result = match dirty {
+            None => Ok(()),
+            Some((r0, c0, r1, c1)) => ImageData::new_with_u8_clamped_array_and_sh(
+                Clamped(&frame.pixels),
+                width,
+                height,
+            )
+            .and_then(|image| {
+                let px = cell_px as f64;
+                ctx.put_image_data_with_dirty_x_and_dirty_y_and_dirty_width_and_dirty_height(
+                    &image,
+                    0.0,
+                    0.0,
+                    c0 as f64 * px,
+                    r0 as f64 * px,
+                    (c1 - c0 + 1) as f64 * px,
+                    (r1 - r0 + 1) as f64 * px,
+                )
+            }),
+        };
+        self.frame = Some(frame);
+        result
+    }
+
+    /// 在 Worker 中的 OffscreenCanvas 上绘制，绘制完成后可以通过
+    /// `OffscreenCanvas.transferToImageBitmap()` 将结果交给主线程显示
+    #[cfg(feature = "offscreen")]
+    pub fn draw_to_offscreen(&self, ctx: &OffscreenCanvasRenderingContext2d, cell_size: u32) {
+        self.draw_with(ctx, cell_size);
+    }
+
+    /// 与 `render_rgba` 相同，但直接生成可以 `putImageData` 的 ImageData
+    pub fn to_image_data(&self) -> Result<ImageData, JsValue> {
+        let rgba = self.render_rgba();
+        ImageData::new_with_u8_clamped_array_and_sh(
+            Clamped(&rgba),
+            self.viewport.cols,
+            self.viewport.rows,
+        )
+    }
+}
@@ -0,0 +1,47 @@
+use js_sys::{Function, Object, Reflect};
+use wasm_bindgen::prelude::*;
+use web_sys::console;
+
+use crate::events::TickSummary;
+use crate::{Universe, UniverseEvent};
+
+#[wasm_bindgen]
+impl Universe {
+    /// 注册事件回调，同一事件可以注册多个回调
+    /// 回调的参数为 `{ generation, population }`
+    pub fn on_event(&mut self, event: UniverseEvent, callback: Function) {
+        self.callbacks.push((event, callback));
+    }
+
+    /// 移除之前通过 `on_event` 注册的回调
+    pub fn off_event(&mut self, event: UniverseEvent, callback: &Function) {
+        self.callbacks
+            .retain(|(e, f)| !(*e == event && JsValue::from(f) == JsValue::from(callback)));
+    }
+}
+
+impl Universe {
+    /// 在 tick 完成后调用所有满足条件的回调
+    /// 回调中抛出的异常只会被打印，不会影响 Universe 的状态
+    pub(crate) fn dispatch_events(&self, summary: &TickSummary) {
+        if self.callbacks.is_empty() {
+            return;
+        }
+
+        let info = Object::new();
+        let _ = Reflect::set(
+            &info,
+            &"generation".into(),
+            &(summary.generation as f64).into(),
+        );
+        let _ = Reflect::set(&info, &"population".into(), &summary.population.into());
+
+        for (event, callback) in self.callbacks.iter() {
+            if event.triggered_by(summary) {
+                if let Err(err) = callback.call1(&JsValue::NULL, &info) {
+                    console::error_2(&"Universe event callback threw:".into(), &err);
+                }
+            }
+        }
+    }
+}
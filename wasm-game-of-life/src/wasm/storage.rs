@@ -0,0 +1,77 @@
+use wasm_bindgen::prelude::*;
+use web_sys::Storage;
+
+use crate::Universe;
+
+const SLOT_PREFIX: &str = "wasm-gol:";
+
+/// localStorage 的常见配额为 5MB（按 UTF-16 计算，每个字符两个字节）
+const STORAGE_QUOTA: usize = 5 * 1024 * 1024;
+
+fn local_storage() -> Result<Storage, JsValue> {
+    let window = web_sys::window().ok_or_else(|| JsValue::from_str("no window available"))?;
+    match window.local_storage() {
+        Ok(Some(storage)) => Ok(storage),
+        _ => Err(JsValue::from_str(
+            "localStorage is unavailable (private browsing or disabled storage?)",
+        )),
+    }
+}
+
+fn slot_key(slot: &str) -> String {
+    format!("{}{}", SLOT_PREFIX, slot)
+}
+
+#[wasm_bindgen]
+impl Universe {
+    /// 将当前状态以 `encode_state` 的格式保存到 localStorage 的 `wasm-gol:{slot}` 中
+    pub fn save_slot(&self, slot: &str) -> Result<(), JsValue> {
+        let storage = local_storage()?;
+        let key = slot_key(slot);
+        let value = self.encode_state();
+
+        let size = (key.len() + value.len()) * 2;
+        if size > STORAGE_QUOTA {
+            return Err(JsValue::from_str(&format!(
+                "save slot '{}' needs {} bytes, which exceeds the {} byte localStorage quota",
+                slot, size, STORAGE_QUOTA
+            )));
+        }
+
+        storage.set_item(&key, &value).map_err(|_| {
+            JsValue::from_str(&format!(
+                "failed to save slot '{}' ({} bytes): storage quota exceeded?",
+                slot, size
+            ))
+        })
+    }
+
+    /// 从 localStorage 读取之前保存的 Universe
+    pub fn load_slot(slot: &str) -> Result<Universe, JsValue> {
+        let storage = local_storage()?;
+        let value = storage
+            .get_item(&slot_key(slot))?
+            .ok_or_else(|| JsValue::from_str(&format!("save slot '{}' does not exist", slot)))?;
+
+        Ok(Universe::decode_state(&value)?)
+    }
+
+    /// 列出所有已保存的存档名（不包含前缀）
+    pub fn list_slots() -> Result<Vec<String>, JsValue> {
+        let storage = local_storage()?;
+        let mut slots = vec![];
+        for i in 0..storage.length()? {
+            if let Some(key) = storage.key(i)? {
+                if let Some(slot) = key.strip_prefix(SLOT_PREFIX) {
+                    slots.push(slot.to_string());
+                }
+            }
+        }
+        Ok(slots)
+    }
+
+    /// 删除存档，存档不存在时不做任何事
+    pub fn delete_slot(slot: &str) -> Result<(), JsValue> {
+        local_storage()?.remove_item(&slot_key(slot))
+    }
+}
@@ -0,0 +1,263 @@
+use js_sys::{Array, Object, Promise, Reflect, Uint8Array};
+use wasm_bindgen::prelude::*;
+use web_sys::{MessageEvent, Worker};
+
+use crate::{Boundary, Cell, Universe, UniverseError};
+
+/// Worker 脚本的路径，见 www/worker.js
+const WORKER_SCRIPT: &str = "./worker.js";
+
+fn set(object: &Object, key: &str, value: &JsValue) {
+    let _ = Reflect::set(object, &key.into(), value);
+}
+
+fn get(object: &JsValue, key: &str) -> Result<JsValue, JsValue> {
+    let value = Reflect::get(object, &key.into())?;
+    if value.is_undefined() {
+        return Err(
+            UniverseError::InvalidArgument(format!("worker message is missing {}", key)).into(),
+        );
+    }
+    Ok(value)
+}
+
+fn get_u32(object: &JsValue, key: &str) -> Result<u32, JsValue> {
+    get(object, key)?
+        .as_f64()
+        .filter(|value| value.fract() == 0.0 && (0.0..=u32::MAX as f64).contains(value))
+        .map(|value| value as u32)
+        .ok_or_else(|| {
+            UniverseError::InvalidArgument(format!("worker message field {} is not a u32", key))
+                .into()
+        })
+}
+
+fn get_i32(object: &JsValue, key: &str) -> Result<i32, JsValue> {
+    get(object, key)?
+        .as_f64()
+        .filter(|value| value.fract() == 0.0 && (i32::MIN as f64..=i32::MAX as f64).contains(value))
+        .map(|value| value as i32)
+        .ok_or_else(|| {
+            UniverseError::InvalidArgument(format!("worker message field {} is not an i32", key))
+                .into()
+        })
+}
+
+fn get_bytes(object: &JsValue, key: &str) -> Result<Vec<u8>, JsValue> {
+    Ok(Uint8Array::new(&get(object, key)?).to_vec())
+}
+
+#[wasm_bindgen]
+impl Universe {
+    /// 把当前状态交给 Web Worker 执行 `n_ticks` 次 tick，避免阻塞主线程。
+    /// 传给 Worker 的是完整的模拟状态：Cell、代数、规则（包括规则序列和查找表）、边界、墙、
+    /// 显示偏移、接缝错位和自动扩展，Worker 中的结果与在主线程上 tick 相同。
+    ///
+    /// Promise 完成时得到 Worker 的结果，交给 `load_worker_result` 写回；Worker 无法启动、
+    /// 脚本出错或者无法恢复状态时 Promise 被拒绝。
+    /// 由于 `atomics` 需要 nightly 和 build-std，这里传递的是一份可转移的 ArrayBuffer
+    /// 拷贝，而不是 SharedArrayBuffer
+    pub fn run_in_worker(&self, n_ticks: u32) -> Promise {
+        let job = self.worker_job(n_ticks);
+
+        Promise::new(&mut |resolve, reject| {
+            let worker = match Worker::new(WORKER_SCRIPT) {
+                Ok(worker) => worker,
+                Err(err) => {
+                    let _ = reject.call1(&JsValue::NULL, &err);
+                    return;
+                }
+            };
+
+            let done = worker.clone();
+            let on_error = reject.clone();
+            let onmessage = Closure::once_into_js(move |event: MessageEvent| {
+                done.terminate();
+                let data = event.data();
+                match Reflect::get(&data, &"error".into()) {
+                    Ok(error) if !error.is_undefined() => {
+                        let _ = on_error.call1(&JsValue::NULL, &error);
+                    }
+                    _ => {
+                        let _ = resolve.call1(&JsValue::NULL, &data);
+                    }
+                }
+            });
+            worker.set_onmessage(Some(onmessage.unchecked_ref()));
+
+            let failed = worker.clone();
+            let on_failure = reject.clone();
+            let onerror = Closure::once_into_js(move |event: JsValue| {
+                failed.terminate();
+                let _ = on_failure.call1(&JsValue::NULL, &event);
+            });
+            worker.set_onerror(Some(onerror.unchecked_ref()));
+
+            let cells = Reflect::get(&job, &"cells".into()).unwrap();
+            let transfer = Array::of1(&Uint8Array::new(&cells).buffer());
+            if let Err(err) = worker.post_message_with_transfer(&job, &transfer) {
+                worker.terminate();
+                let _ = reject.call1(&JsValue::NULL, &err);
+            }
+        })
+    }
+
+    /// 在 Worker 中由 `run_in_worker` 发来的消息恢复 Universe
+    pub fn from_worker_job(job: &JsValue) -> Result<Universe, JsValue> {
+        let (width, height) = (get_u32(job, "width")?, get_u32(job, "height")?);
+        let mut universe = Universe::new_with_size(width, height)?;
+        universe.load_cells(&get_bytes(job, "cells")?)?;
+        universe.generation = get(job, "generation")?
+            .as_f64()
+            .ok_or_else(|| UniverseError::InvalidArgument("invalid generation".to_string()))?
+            as u64;
+
+        let rules: Vec<String> = Array::from(&get(job, "rules")?)
+            .iter()
+            .map(|rule| rule.as_string().unwrap_or_default())
+            .collect();
+        match rules.as_slice() {
+            [rule] => universe.set_rule(rule)?,
+            _ => universe.set_rule_schedule_js(rules)?,
+        }
+        universe.set_life_without_death(get(job, "lifeWithoutDeath")?.is_truthy());
+        let table = get_bytes(job, "ruleTable")?;
+        if !table.is_empty() {
+            universe.set_rule_table(&table)?;
+        }
+
+        universe.boundary = match get_u32(job, "boundary")? {
+            0 => Boundary::Toroidal,
+            1 => Boundary::Dead,
+            2 => Boundary::Alive,
+            other => {
+                return Err(
+                    UniverseError::InvalidArgument(format!("invalid boundary {}", other)).into(),
+                )
+            }
+        };
+        let walls = get_bytes(job, "walls")?;
+        if !walls.is_empty() {
+            if walls.len() != universe.cells.len() {
+                return Err(UniverseError::LengthMismatch {
+                    expected: universe.cells.len(),
+                    actual: walls.len(),
+                }
+                .into());
+            }
+            universe.walls = walls.iter().map(|&wall| wall != 0).collect();
+        }
+        universe.walls_count_as_dead = get(job, "wallsCountAsDead")?.is_truthy();
+        universe.set_toroidal_offset(get_i32(job, "rowOffset")?, get_i32(job, "colOffset")?);
+        universe.set_wrap_shift(get_i32(job, "rowShift")?, get_i32(job, "colShift")?);
+        universe.auto_expand = get(job, "autoExpand")?.is_truthy();
+        Ok(universe)
+    }
+
+    /// Worker 完成之后发回主线程的结果：
+    /// `{ ticks, width, height, rowOffset, colOffset, generation, activeCells, cells }`
+    pub fn worker_reply(&self, ticks: u32) -> Object {
+        let reply = Object::new();
+        set(&reply, "ticks", &ticks.into());
+        set(&reply, "width", &self.width.into());
+        set(&reply, "height", &self.height.into());
+        set(&reply, "rowOffset", &self.row_offset.into());
+        set(&reply, "colOffset", &self.col_offset.into());
+        set(&reply, "generation", &(self.generation as f64).into());
+        set(&reply, "activeCells", &self.active_cells.into());
+        let cells: Vec<u8> = self.cells.iter().map(|&cell| cell as u8).collect();
+        set(&reply, "cells", &Uint8Array::from(cells.as_slice()));
+        reply
+    }
+
+    /// 写回 `run_in_worker` 的结果：Cell、代数和活跃数量；Worker 中自动扩展过时，
+    /// 先像 `resize` 一样改变尺寸（墙会被清除），再恢复扩展之后的显示偏移
+    pub fn load_worker_result(&mut self, result: &JsValue) -> Result<(), JsValue> {
+        let (width, height) = (get_u32(result, "width")?, get_u32(result, "height")?);
+        let cells = get_bytes(result, "cells")?;
+        let generation = get(result, "generation")?
+            .as_f64()
+            .ok_or_else(|| UniverseError::InvalidArgument("invalid generation".to_string()))?;
+        let active_cells = get_u32(result, "activeCells")?;
+        let (row_offset, col_offset) =
+            (get_i32(result, "rowOffset")?, get_i32(result, "colOffset")?);
+        if cells.len() as u64 != width as u64 * height as u64 {
+            return Err(UniverseError::LengthMismatch {
+                expected: width as usize * height as usize,
+                actual: cells.len(),
+            }
+            .into());
+        }
+
+        if (width, height) != (self.width, self.height) {
+            self.resize(width, height)?;
+        }
+        self.set_toroidal_offset(row_offset, col_offset);
+        // Worker 中执行的是 tick 而不是编辑，不记入撤销栈
+        for (cell, &byte) in self.cells.iter_mut().zip(&cells) {
+            *cell = if byte == 0 { Cell::Dead } else { Cell::Alive };
+        }
+        self.sync_cells();
+        self.generation = generation as u64;
+        self.active_cells = active_cells;
+        self.stable = false;
+        Ok(())
+    }
+
+    /// 用 0/1 字节缓冲区覆盖当前所有 Cell，长度必须为 width * height
+    pub fn load_cells(&mut self, cells: &[u8]) -> Result<(), UniverseError> {
+        if cells.len() != self.cells.len() {
+            return Err(UniverseError::LengthMismatch {
+                expected: self.cells.len(),
+                actual: cells.len(),
+            });
+        }
+
+        for (cell, &byte) in self.cells.iter_mut().zip(cells) {
+            *cell = if byte == 0 { Cell::Dead } else { Cell::Alive };
+        }
+        self.commit_edit();
+
+        Ok(())
+    }
+}
+
+impl Universe {
+    /// 发给 Worker 的消息，见 `from_worker_job`
+    fn worker_job(&self, n_ticks: u32) -> Object {
+        let job = Object::new();
+        set(&job, "ticks", &n_ticks.into());
+        set(&job, "width", &self.width.into());
+        set(&job, "height", &self.height.into());
+        set(&job, "generation", &(self.generation as f64).into());
+        let cells: Vec<u8> = self.cells.iter().map(|&cell| cell as u8).collect();
+        set(&job, "cells", &Uint8Array::from(cells.as_slice()));
+
+        let rules: Array = if self.rule_schedule.is_empty() {
+            Array::of1(&self.rule.to_string().into())
+        } else {
+            self.rule_schedule
+                .iter()
+                .map(|rule| JsValue::from(rule.to_string()))
+                .collect()
+        };
+        set(&job, "rules", &rules);
+        set(&job, "lifeWithoutDeath", &self.life_without_death().into());
+        set(
+            &job,
+            "ruleTable",
+            &Uint8Array::from(self.rule_table.as_slice()),
+        );
+
+        set(&job, "boundary", &(self.boundary as u8).into());
+        let walls: Vec<u8> = self.walls.iter().map(|&wall| wall as u8).collect();
+        set(&job, "walls", &Uint8Array::from(walls.as_slice()));
+        set(&job, "wallsCountAsDead", &self.walls_count_as_dead.into());
+        set(&job, "rowOffset", &self.row_offset.into());
+        set(&job, "colOffset", &self.col_offset.into());
+        set(&job, "rowShift", &self.wrap_shift.0.into());
+        set(&job, "colShift", &self.wrap_shift.1.into());
+        set(&job, "autoExpand", &self.auto_expand.into());
+        job
+    }
+}
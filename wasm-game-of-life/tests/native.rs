@@ -0,0 +1,830 @@
+//! Test suite for native (non-wasm) targets.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+extern crate wasm_game_of_life;
+use std::fs;
+use std::path::Path;
+
+use wasm_game_of_life::{
+    line_cells, parse_macrocell, parse_rle, pinch_scale, pointer_distance, run_soup_search,
+    take_warnings, Cell, SoupSearch, Universe, UniverseBuilder,
+};
+
+pub fn empty_universe(width: u32, height: u32) -> Universe {
+    let mut universe = Universe::new();
+    universe.set_width(width).unwrap();
+    universe.set_height(height).unwrap();
+
+    universe
+}
+
+#[test]
+fn test_verbose_warns_out_of_bounds() {
+    let mut universe = empty_universe(6, 6);
+    take_warnings();
+
+    universe.set_cells(&[(10, 1)]);
+    assert!(take_warnings().is_empty());
+
+    universe.set_verbose(true);
+    universe.set_cells(&[(1, 1), (10, 1)]);
+    assert_eq!(
+        take_warnings(),
+        vec!["ignoring out-of-bounds cell (10, 1) in a 6x6 universe".to_string()]
+    );
+}
+
+#[test]
+fn test_line_cells_has_no_gaps() {
+    let cells = line_cells(0, 0, 3, 7);
+    assert_eq!(cells.first(), Some(&(0, 0)));
+    assert_eq!(cells.last(), Some(&(3, 7)));
+    for pair in cells.windows(2) {
+        let (r0, c0) = pair[0];
+        let (r1, c1) = pair[1];
+        assert!((r1 - r0).abs() <= 1 && (c1 - c0).abs() <= 1);
+    }
+
+    assert_eq!(line_cells(2, 2, 2, 2), vec![(2, 2)]);
+    assert_eq!(line_cells(3, 0, 0, 0), vec![(3, 0), (2, 0), (1, 0), (0, 0)]);
+}
+
+#[test]
+fn test_pinch_scale() {
+    let start = pointer_distance(0.0, 0.0, 3.0, 4.0);
+    assert_eq!(start, 5.0);
+    assert_eq!(pinch_scale(start, 10.0), 2.0);
+    assert_eq!(pinch_scale(start, 0.0), 0.1);
+    assert_eq!(pinch_scale(0.0, 10.0), 1.0);
+}
+
+/// 读取 fuzz/corpus/<target> 下的所有输入，保证种子语料在 `cargo test` 中也能被解析
+fn fuzz_corpus(target: &str) -> Vec<Vec<u8>> {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("fuzz/corpus")
+        .join(target);
+    let mut inputs: Vec<Vec<u8>> = fs::read_dir(dir)
+        .unwrap()
+        .map(|entry| fs::read(entry.unwrap().path()).unwrap())
+        .collect();
+    inputs.sort();
+    assert!(!inputs.is_empty());
+    inputs
+}
+
+#[test]
+fn test_fuzz_corpus_round_trips() {
+    for input in fuzz_corpus("rle") {
+        let pattern = parse_rle(std::str::from_utf8(&input).unwrap()).unwrap();
+        let mut universe = Universe::new_with_size(pattern.width, pattern.height).unwrap();
+        universe.set_cells(&pattern.cells);
+        assert_eq!(parse_rle(&universe.to_rle()).unwrap(), pattern);
+    }
+
+    for input in fuzz_corpus("macrocell") {
+        let pattern = parse_macrocell(std::str::from_utf8(&input).unwrap()).unwrap();
+        let mut universe = Universe::new_with_size(pattern.width, pattern.height).unwrap();
+        universe.stamp_pattern(&pattern, 0, 0).unwrap();
+        assert_eq!(universe.population() as usize, pattern.cells.len());
+    }
+
+    for input in fuzz_corpus("plaintext") {
+        let universe: Universe = std::str::from_utf8(&input).unwrap().parse().unwrap();
+        let reparsed: Universe = universe.to_string().parse().unwrap();
+        assert_eq!(reparsed.get_cells(), universe.get_cells());
+    }
+
+    for input in fuzz_corpus("base64") {
+        let universe = Universe::decode_state(std::str::from_utf8(&input).unwrap()).unwrap();
+        let reparsed = Universe::decode_state(&universe.encode_state()).unwrap();
+        assert_eq!(reparsed.get_cells(), universe.get_cells());
+    }
+
+    for input in fuzz_corpus("snapshot") {
+        let universe = Universe::from_compressed_snapshot(&input).unwrap();
+        let reparsed = Universe::from_compressed_snapshot(&universe.compress_snapshot()).unwrap();
+        assert_eq!(reparsed.get_cells(), universe.get_cells());
+    }
+}
+
+#[test]
+fn test_parse_rle_rejects_huge_patterns() {
+    // fuzz 发现的输入：声明或展开后过大的图案必须在分配之前报错，而不是溢出或耗尽内存
+    for rle in [
+        "x = 4000000000, y = 1\no!",
+        "x = 65536, y = 65536\no!",
+        "4000000000o!",
+        "4000000000b4000000000bo!",
+        "4000000000$4000000000$o!",
+        "67108864o$o!",
+    ] {
+        assert!(parse_rle(rle).is_err(), "{}", rle);
+    }
+    let pattern = parse_rle("x = 8192, y = 8192\n8192o!").unwrap();
+    assert_eq!((pattern.width, pattern.height), (8192, 8192));
+    assert_eq!(pattern.cells.len(), 8192);
+}
+
+#[test]
+fn test_deterministic_entropy_mode_needs_no_environment() {
+    use wasm_game_of_life::{set_entropy_mode, EntropyMode};
+
+    // 与 tests/web.rs 中 wasm 上的结果相同
+    set_entropy_mode(EntropyMode::Deterministic);
+    let mut universe = Universe::new_with_size(8, 8).unwrap();
+    universe.start();
+    assert_eq!(universe.encode_state(), "8x8:lVy3fNmX1lY=");
+
+    set_entropy_mode(EntropyMode::Deterministic);
+    let first = Universe::new();
+    set_entropy_mode(EntropyMode::Deterministic);
+    assert_eq!(Universe::new().get_cells(), first.get_cells());
+    set_entropy_mode(EntropyMode::Environment);
+}
+
+#[cfg(not(feature = "web"))]
+#[test]
+fn test_shared_universe_across_threads() {
+    use std::thread;
+    use wasm_game_of_life::SharedUniverse;
+
+    let mut universe = empty_universe(8, 8);
+    universe.set_cells(&[(1, 2), (2, 2), (3, 2)]);
+    let shared = SharedUniverse::new(universe);
+
+    let ticker = shared.clone();
+    let simulation = thread::spawn(move || {
+        for _ in 0..10 {
+            ticker.tick();
+        }
+    });
+    // 界面线程在模拟进行时读取，每次看到的都是完整的一代
+    for _ in 0..10 {
+        let cells = shared.snapshot_cells();
+        assert_eq!(cells.len(), 64);
+        assert_eq!(cells.iter().filter(|cell| cell.is_alive()).count(), 3);
+    }
+    simulation.join().unwrap();
+
+    assert_eq!(shared.generation(), 10);
+    assert_eq!(shared.snapshot_cells(), {
+        let mut expected = empty_universe(8, 8);
+        expected.set_cells(&[(1, 2), (2, 2), (3, 2)]);
+        expected.get_cells().to_vec()
+    });
+}
+
+#[test]
+fn test_rule_table_matches_counting_on_small_tori() {
+    // 宽或高小于 3 的环形宇宙中，八个方向会指向同一个 Cell（甚至自身），
+    // 查找表和 B/S 计数都只把每个不同的邻居计算一次
+    for &(width, height) in [(1, 1), (2, 5), (7, 1), (2, 2), (1, 6)].iter() {
+        for seed in 0..8 {
+            let mut counting = UniverseBuilder::new()
+                .width(width)
+                .height(height)
+                .density(0.5)
+                .seed(seed)
+                .build()
+                .unwrap();
+            let mut table = counting.clone();
+            table.set_rule_table(&table.get_rule_table()).unwrap();
+            for _ in 0..10 {
+                counting.tick();
+                table.tick();
+                assert_eq!(counting.get_cells(), table.get_cells());
+            }
+        }
+    }
+
+    // 1x1 中唯一的 Cell 没有邻居：B0 规则下死亡的 Cell 会诞生，S0 规则下存活的 Cell 会存活
+    let mut universe = empty_universe(1, 1);
+    universe.set_rule("B0/S0").unwrap();
+    universe.set_rule_table(&universe.get_rule_table()).unwrap();
+    universe.tick();
+    assert_eq!(universe.population(), 1);
+    universe.tick();
+    assert_eq!(universe.population(), 1);
+}
+
+#[test]
+fn test_run_soup_search() {
+    let report = run_soup_search(12, 12, 0.35, 8, 200, 2024).unwrap();
+    assert_eq!(report.soups, 8);
+    // 相同的总种子总是得到相同的结果，不同的总种子得到不同的汤
+    assert_eq!(run_soup_search(12, 12, 0.35, 8, 200, 2024).unwrap(), report);
+    assert_ne!(run_soup_search(12, 12, 0.35, 8, 200, 2025).unwrap(), report);
+
+    assert!(report.stabilized <= 8);
+    assert_eq!(report.longest_lived.len(), 5);
+    assert!(report
+        .longest_lived
+        .windows(2)
+        .all(|pair| pair[0].generations >= pair[1].generations));
+    assert!(report
+        .highest_population
+        .windows(2)
+        .all(|pair| pair[0].final_population >= pair[1].final_population));
+
+    // 报告中的种子可以重现那一锅汤
+    let best = &report.highest_population[0];
+    let mut soup = UniverseBuilder::new()
+        .width(12)
+        .height(12)
+        .density(0.35)
+        .seed(best.seed)
+        .build()
+        .unwrap();
+    let cycle = soup.run_until_cycle(200);
+    assert_eq!(cycle.map(|(_, period)| period), best.period);
+    assert_eq!(soup.population(), best.final_population);
+
+    // 分批运行与一次运行的结果相同
+    let mut search = SoupSearch::new(12, 12, 0.35, 8, 200, 2024).unwrap();
+    assert!(!search.run(5));
+    assert_eq!(search.completed(), 5);
+    assert!(search.run(100));
+    assert_eq!(search.report(), report);
+
+    assert!(run_soup_search(0, 16, 0.35, 1, 10, 0).is_err());
+    assert!(run_soup_search(16, 16, 1.5, 1, 10, 0).is_err());
+}
+
+#[test]
+fn test_rewind_after_resize() {
+    use wasm_game_of_life::UniverseError;
+
+    let mut universe = Universe::new_with_size(32, 32).unwrap();
+    universe.enable_rewind(10);
+    universe.tick();
+    universe.tick();
+    // 改变尺寸会丢弃旧尺寸的帧，而不是把它们恢复到新的网格上
+    for &(width, height) in [(16, 16), (48, 40)].iter() {
+        universe.resize(width, height).unwrap();
+        assert_eq!(universe.rewind_available(), 0);
+        assert!(matches!(
+            universe.rewind(1),
+            Err(UniverseError::InvalidArgument(_))
+        ));
+    }
+    // 容量不变，之后的 tick 按新尺寸记录
+    universe.set_random_alive_cells(500, 3).unwrap();
+    let mut states = vec![];
+    for _ in 0..12 {
+        states.push(universe.get_cells().to_vec());
+        universe.tick();
+    }
+    assert_eq!(universe.rewind_available(), 10);
+    universe.rewind(10).unwrap();
+    assert_eq!(universe.generation(), 2);
+    assert_eq!(universe.get_cells(), &states[2][..]);
+    assert!(universe.rewind(1).is_err());
+
+    // 自动扩展同样会清空缓冲区
+    let mut universe = Universe::new_with_size(8, 8).unwrap();
+    universe.stamp_rle("bo$2bo$3o!", 2, 2).unwrap();
+    universe.set_auto_expand(true);
+    universe.enable_rewind(50);
+    let mut expanded = false;
+    for _ in 0..20 {
+        let size = (universe.width(), universe.height());
+        universe.tick();
+        if (universe.width(), universe.height()) != size {
+            expanded = true;
+            assert_eq!(universe.rewind_available(), 0);
+        }
+    }
+    assert!(expanded);
+    let available = universe.rewind_available();
+    assert!(available > 0);
+    universe.rewind(available).unwrap();
+}
+
+#[test]
+fn test_render_rejects_bad_cell_px() {
+    use wasm_game_of_life::UniverseError;
+
+    let mut universe = Universe::new_with_size(8, 8).unwrap();
+    universe.stamp_rle("3o!", 3, 3).unwrap();
+    let mut buf = vec![0u8; 0];
+    assert!(matches!(
+        universe.write_state_to_image_data(&mut buf, 0, 0, 0),
+        Err(UniverseError::InvalidArgument(_))
+    ));
+    // 出错时不会先 tick
+    assert!(matches!(
+        universe.run_and_write_frames_to_buffer(2, 0, &mut buf, 0, 0),
+        Err(UniverseError::InvalidArgument(_))
+    ));
+    assert_eq!(universe.generation(), 0);
+    // 像素数量溢出
+    assert!(matches!(
+        universe.write_state_to_image_data(&mut buf, u32::MAX, 0, 0),
+        Err(UniverseError::InvalidArgument(_))
+    ));
+
+    let mut buf = vec![0u8; 8 * 8 * 4];
+    universe
+        .write_state_to_image_data(&mut buf, 1, 0xff0000ff, 0x00ff00ff)
+        .unwrap();
+    assert_eq!(&buf[(3 * 8 + 4) * 4..][..4], &[0xff, 0, 0, 0xff]);
+    assert_eq!(&buf[..4], &[0, 0xff, 0, 0xff]);
+}
+
+#[test]
+fn test_compute_entropy_per_row() {
+    let mut universe = empty_universe(4, 4);
+    // 第 0 行全死，第 1 行全活，第 2、3 行各有一半存活
+    universe.stamp_rle("4o$o2bo$b2o!", 1, 0).unwrap();
+    let entropy = universe.compute_entropy_per_row();
+    assert_eq!(entropy, vec![0.0, 0.0, 1.0, 1.0]);
+
+    let entropy = empty_universe(3, 2).compute_entropy_per_row();
+    assert_eq!(entropy, vec![0.0, 0.0]);
+}
+
+#[test]
+fn test_compute_mutual_information() {
+    use wasm_game_of_life::{Cell, UniverseError};
+
+    // 只存活不出生的规则，tick 不改变状态
+    let mut universe = empty_universe(4, 4);
+    universe.set_rule("B/S012345678").unwrap();
+    universe.set_snapshot_capacity(1);
+    universe.stamp_rle("4o$4o!", 0, 0).unwrap();
+    assert!(matches!(
+        universe.compute_mutual_information(1),
+        Err(UniverseError::InvalidArgument(_))
+    ));
+    universe.tick();
+
+    // 上半部分存活：当前状态与上一代完全相关，互信息等于一个比特的熵
+    assert!(universe.compute_mutual_information(0).is_err());
+    assert_eq!(universe.compute_mutual_information(1).unwrap(), 1.0);
+
+    // 改成左半部分存活：与上一代的上半部分相互独立，互信息为 0
+    let mut cells = vec![];
+    for row in 0..4 {
+        for column in 0..4 {
+            cells.push((row, column, Cell::from(column < 2)));
+        }
+    }
+    universe.set_cells_state(&cells).unwrap();
+    assert_eq!(universe.compute_mutual_information(1).unwrap(), 0.0);
+    // 超出快照容量
+    assert!(matches!(
+        universe.compute_mutual_information(2),
+        Err(UniverseError::InvalidArgument(_))
+    ));
+}
+
+#[test]
+fn test_from_str_with_glyphs_round_trips() {
+    let mut universe = empty_universe(5, 4);
+    universe.stamp_rle("bo$2bo$3o!", 0, 1).unwrap();
+    universe.set_render_glyphs('@', '~').unwrap();
+    universe.set_render_row_separator(" | ").unwrap();
+    let text = universe.to_string();
+    assert_eq!(text, "~~@~~ | ~~~@~ | ~@@@~ | ~~~~~ | ");
+    // 默认的解析只认识常见字符
+    assert!(text.parse::<Universe>().is_err());
+
+    let parsed = Universe::from_str_with_glyphs(&text, '@', '~', " | ").unwrap();
+    assert_eq!((parsed.width(), parsed.height()), (5, 4));
+    assert_eq!(parsed.get_cells(), universe.get_cells());
+    assert_eq!(parsed.to_string(), text);
+    // 最后一个分隔符可以省略
+    let trimmed = Universe::from_str_with_glyphs(text.trim_end_matches(" | "), '@', '~', " | ");
+    assert_eq!(trimmed.unwrap().get_cells(), universe.get_cells());
+
+    // 空格也可以作为死亡 Cell 的字符，行首行尾的空格不会被丢掉
+    assert!(universe.set_render_glyphs('x', ' ').is_err());
+    universe.set_render_row_separator("\n").unwrap();
+    universe.set_render_glyphs('x', ' ').unwrap();
+    let text = universe.to_string();
+    let parsed = Universe::from_str_with_glyphs(&text, 'x', ' ', "\n").unwrap();
+    assert_eq!(parsed.get_cells(), universe.get_cells());
+
+    assert!(Universe::from_str_with_glyphs("@~\n@", '@', '~', "\n").is_err());
+    assert!(Universe::from_str_with_glyphs("@#\n", '@', '~', "\n").is_err());
+    assert!(Universe::from_str_with_glyphs("@~\n", '@', '@', "\n").is_err());
+    assert!(Universe::from_str_with_glyphs("@~", '@', '~', "").is_err());
+    assert!(Universe::from_str_with_glyphs("", '@', '~', "\n").is_err());
+}
+
+#[test]
+fn test_neighbor_count_in_radius_limits() {
+    use wasm_game_of_life::{Boundary, Rule, UniverseError};
+
+    // 比邻域小的环面：绕回之后每个 Cell 只计算一次，不计算自身
+    let mut universe = empty_universe(5, 3);
+    universe.fill_from_mask(&[1.0; 15], 0).unwrap();
+    assert_eq!(universe.live_neighbor_count_in_radius(1, 2, 8), Ok(14));
+    assert_eq!(universe.live_neighbor_count_in_radius(0, 0, 2), Ok(14));
+    assert_eq!(universe.live_neighbor_count_in_radius(0, 0, 1), Ok(8));
+    universe
+        .set_cells_state(&[(1, 2, wasm_game_of_life::Cell::Dead)])
+        .unwrap();
+    assert_eq!(universe.live_neighbor_count_in_radius(1, 2, 8), Ok(14));
+    assert_eq!(universe.live_neighbor_count_in_radius(0, 0, 8), Ok(13));
+
+    // 邻域超过规则能表示的邻居数时报错
+    let radius = Rule::MAX_RADIUS;
+    let mut universe = empty_universe(40, 40);
+    assert_eq!(
+        universe.live_neighbor_count_in_radius(20, 20, radius),
+        Ok(0)
+    );
+    assert!(matches!(
+        universe.live_neighbor_count_in_radius(20, 20, radius + 1),
+        Err(UniverseError::InvalidArgument(_))
+    ));
+    universe.set_boundary(Boundary::Alive);
+    assert!(universe
+        .live_neighbor_count_in_radius(20, 20, radius + 1)
+        .is_err());
+    assert!(universe.live_neighbor_count_in_radius(40, 0, 1).is_err());
+    // 小环面上更大的半径也只有有限个不同的邻居
+    let universe = empty_universe(12, 12);
+    assert_eq!(universe.live_neighbor_count_in_radius(0, 0, 1000), Ok(0));
+}
+
+#[test]
+fn test_rule_string_reflects_schedule_and_table() {
+    use wasm_game_of_life::Rule;
+
+    let mut universe = empty_universe(8, 8);
+    universe.set_rule_schedule(vec![Rule::CONWAY, Rule::HIGHLIFE, Rule::SEEDS]);
+    assert_eq!(universe.rule_string(), "B3/S23,B36/S23,B2/S");
+    universe.set_rule_schedule(vec![]);
+    assert_eq!(universe.rule_string(), "B3/S23");
+
+    // 只依赖邻居数量的查找表给出等价的 B/S 记法
+    universe.set_rule("B36/S23").unwrap();
+    let highlife = universe.get_rule_table();
+    universe.set_rule("B3/S23").unwrap();
+    universe.set_rule_table(&highlife).unwrap();
+    assert_eq!(universe.rule_string(), "B36/S23");
+
+    // Golly 中康威生命游戏的 MAP 记法
+    let conway_map =
+        "MAPARYXfhZofugWaH7oaIDogBZofuhogOiAaIDogIAAgAAWaH7oaIDogGiA6ICAAIAAaIDogIAAgACAAIAAAAAAAA";
+    let conway_table = empty_universe(1, 1).get_rule_table();
+    universe.set_rule(conway_map).unwrap();
+    assert_eq!(universe.get_rule_table(), conway_table);
+    assert_eq!(universe.rule_string(), "B3/S23");
+
+    // 其它查找表输出 MAP 记法，并且可以原样设置回去：下一代的状态是西北邻居的状态
+    let table: Vec<u8> = (0..512).map(|index| (index & 1) as u8).collect();
+    universe.set_rule_table(&table).unwrap();
+    let map = universe.rule_string();
+    assert!(map.starts_with("MAP"));
+    assert_eq!(map.len(), 3 + 86);
+    let mut other = empty_universe(8, 8);
+    other.set_rule(&map).unwrap();
+    assert_eq!(other.get_rule_table(), table);
+    assert_eq!(other.rule_string(), map);
+    assert!(other.set_rule("MAPAAAA").is_err());
+    assert_eq!(other.get_rule_table(), table);
+}
+
+#[test]
+fn test_component_ids_under_scroll_and_wrap_shift() {
+    let mut universe = empty_universe(6, 5);
+    universe.set_cells(&[(1, 1), (1, 2), (4, 4)]);
+    universe.scroll(2, 3);
+    // 滚动之后 id 仍按显示的坐标计算
+    let ids: Vec<u32> = universe.live_components().iter().map(|c| c.id).collect();
+    let expected: Vec<u32> = ids
+        .iter()
+        .map(|&id| universe.component_at(id / 6, id % 6).unwrap().unwrap())
+        .collect();
+    assert_eq!(ids, expected);
+    assert_eq!(ids.len(), 2);
+    for id in ids.into_iter().rev() {
+        universe.erase_component(id).unwrap();
+    }
+    assert_eq!(universe.population(), 0);
+
+    // 错位的接缝把 (0, 5) 和 (1, 0) 连成一个簇
+    let mut universe = empty_universe(6, 5);
+    universe.set_wrap_shift(1, 0);
+    universe.set_cells(&[(0, 5), (1, 0)]);
+    assert_eq!(universe.component_at(1, 0).unwrap(), Some(5));
+    universe.erase_component(5).unwrap();
+    assert_eq!(universe.population(), 0);
+}
+
+#[test]
+fn test_swap_keeps_local_settings() {
+    let mut a = empty_universe(6, 6);
+    let mut b = empty_universe(6, 6);
+    a.enable_rewind(2);
+    b.enable_rewind(8);
+    b.set_cells(&[(2, 1), (2, 2), (2, 3)]);
+    for _ in 0..5 {
+        b.tick();
+    }
+    assert_eq!(b.rewind_available(), 5);
+
+    // 墙跟着各自的 Cell 和显示偏移走
+    b.scroll(1, 1);
+    b.set_wall(0, 0, true).unwrap();
+    a.swap(&mut b).unwrap();
+    assert!(a.is_wall(0, 0));
+    assert!(!b.is_wall(0, 0));
+
+    // 回退缓冲区的容量各自保持不变，超出的旧帧被丢弃
+    assert_eq!(a.rewind_available(), 2);
+    assert_eq!(b.rewind_available(), 0);
+    a.tick();
+    a.tick();
+    a.tick();
+    assert_eq!(a.rewind_available(), 2);
+    a.rewind(2).unwrap();
+    assert_eq!(a.generation(), 6);
+}
+
+#[cfg(feature = "cell-ages")]
+#[test]
+fn test_swap_clamps_ages_to_local_cap() {
+    let mut a = empty_universe(6, 6);
+    let mut b = empty_universe(6, 6);
+    a.set_age_cap(2);
+    b.set_cells(&[(1, 1), (1, 2), (2, 1), (2, 2)]);
+    for _ in 0..5 {
+        b.tick();
+    }
+    a.swap(&mut b).unwrap();
+    assert!(a.ages().iter().all(|&age| age <= 2));
+    assert_eq!(a.ages().iter().max(), Some(&2));
+}
+
+#[test]
+fn test_metrics_timeline_and_active_cells() {
+    let mut universe = empty_universe(16, 16);
+    assert!(universe.get_metrics_timeline().is_empty());
+    universe.enable_rewind(4);
+    universe.set_cells(&[(5, 4), (5, 5), (5, 6)]);
+    let mut metrics = vec![];
+    for _ in 0..6 {
+        metrics.push(universe.metrics());
+        universe.tick();
+    }
+    // 与存活数量的时间线一一对应，保留最近的 4 帧
+    assert_eq!(universe.get_metrics_timeline(), &metrics[2..]);
+    assert_eq!(universe.get_timeline().len(), 4);
+    assert_eq!(metrics[0].active_cells, 0);
+    assert!(metrics[1..].iter().all(|m| m.active_cells == 4));
+
+    // 回退恢复当时记录的活跃数量
+    universe.rewind(4).unwrap();
+    assert_eq!(universe.metrics(), metrics[2]);
+
+    // 编辑、重置和改变尺寸之后上一次 tick 的活跃数量不再适用
+    universe.tick();
+    assert_eq!(universe.metrics().active_cells, 4);
+    universe.toggle_cell(0, 0).unwrap();
+    assert_eq!(universe.metrics().active_cells, 0);
+    universe.tick();
+    assert!(universe.metrics().active_cells > 0);
+    universe.reset();
+    assert_eq!(universe.metrics().active_cells, 0);
+    universe.set_cells(&[(5, 4), (5, 5), (5, 6)]);
+    universe.tick();
+    universe.resize(8, 8).unwrap();
+    assert_eq!(universe.metrics().active_cells, 0);
+}
+
+#[test]
+fn test_import_macrocell() {
+    let two = "[M2] (golly 4.2)\n#R B36/S23\n#G 120\n.*$..*$***$\n4 1 0 0 1\n";
+    let universe = Universe::import_macrocell(two).unwrap();
+    assert_eq!((universe.width(), universe.height()), (11, 11));
+    assert_eq!(universe.population(), 10);
+    assert_eq!(universe.rule(), "B36/S23");
+    assert_eq!(universe.generation(), 120);
+    assert_eq!(universe.get(10, 10), Some(wasm_game_of_life::Cell::Alive));
+
+    // 没有 #R、#G 时使用康威规则，从第 0 代开始
+    let glider = Universe::import_macrocell("[M2]\n.*$..*$***$\n").unwrap();
+    assert_eq!(glider.to_string(), "◻◼◻\n◻◻◼\n◼◼◼\n");
+    assert_eq!(glider.rule(), "B3/S23");
+    assert_eq!(glider.generation(), 0);
+
+    assert!(Universe::import_macrocell("[M2]\n").is_err());
+    assert!(Universe::import_macrocell("[M2]\n#R Life\n.*$\n").is_err());
+    assert!(Universe::import_macrocell("[M2]\n#G many\n.*$\n").is_err());
+    assert!(Universe::import_macrocell("bo$2bo$3o!").is_err());
+}
+
+#[test]
+fn test_record_all_supported_mutators() {
+    use wasm_game_of_life::{Boundary, Cell, Recorder, Replay, UniverseError};
+
+    let mut recorder = Recorder::new(20, 16, 3).unwrap();
+    recorder.set_random_alive_cells(60, 11).unwrap();
+    recorder.set_cell(0, 0, Cell::Alive).unwrap();
+    recorder.set_boundary(Boundary::Dead);
+    recorder.set_wall(5, 5, true).unwrap();
+    recorder.tick(4);
+    recorder.sprinkle(15, 2);
+    recorder.mirror_to_quadrants();
+    recorder.set_boundary(Boundary::Alive);
+    recorder.tick(3);
+    assert!(recorder.set_cell(16, 0, Cell::Alive).is_err());
+    assert!(recorder.set_random_alive_cells(1000, 1).is_err());
+    assert_eq!(recorder.op_count(), 9);
+
+    let log = recorder.export();
+    for line in [
+        "set 0 0 1",
+        "boundary 1",
+        "wall 5 5 1",
+        "sprinkle 15 2",
+        "mirror",
+    ] {
+        assert!(log.contains(line), "{} not in {}", line, log);
+    }
+    let mut replay = Replay::from_log(&log).unwrap();
+    replay.finish().unwrap();
+    let (replayed, recorded) = (replay.universe(), recorder.universe());
+    assert_eq!(replayed.get_cells(), recorded.get_cells());
+    assert_eq!(replayed.boundary(), Boundary::Alive);
+    assert!(replayed.is_wall(5, 5));
+
+    // 超出 u32 的参数是解析错误，而不是被截断
+    for op in [
+        "resize 4294967297 4",
+        "tick 4294967296",
+        "set 0 0 2",
+        "boundary 3",
+    ] {
+        assert!(matches!(
+            Replay::from_log(&format!("gol-log 1 4 4 0\n0 {}", op)),
+            Err(UniverseError::ParseError { line: 2, .. })
+        ));
+    }
+}
+
+#[test]
+fn test_run_until_cycle_long_period() {
+    // 8×8 环面上的滑翔机每 32 代回到原位，循环从第 0 代开始
+    let mut universe = empty_universe(8, 8);
+    universe.stamp_rle("bo$2bo$3o!", 0, 0).unwrap();
+    assert_eq!(universe.run_until_cycle(31), None);
+    assert_eq!(universe.generation(), 31);
+    universe.reset();
+    universe.stamp_rle("bo$2bo$3o!", 0, 0).unwrap();
+    assert_eq!(universe.run_until_cycle(100), Some((0, 32)));
+    assert_eq!(universe.generation(), 32);
+
+    // 宽度不是 8 的倍数时按位打包的最后一个字节不满，结果不受影响；
+    // 先 tick 几代，循环从当时的代数开始
+    let mut universe = empty_universe(13, 11);
+    universe.set_cells(&[(5, 5), (5, 6), (5, 7)]);
+    for _ in 0..3 {
+        universe.tick();
+    }
+    assert_eq!(universe.run_until_cycle(10), Some((3, 2)));
+}
+
+#[test]
+fn test_render_frames_stops_when_the_grid_expands() {
+    use wasm_game_of_life::UniverseError;
+
+    let mut universe = Universe::new_with_size(8, 8).unwrap();
+    universe.stamp_rle("bo$2bo$3o!", 4, 4).unwrap();
+    universe.set_auto_expand(true);
+    let mut buf = vec![0u8; 8 * 8 * 4 * 10];
+    assert!(matches!(
+        universe.run_and_write_frames_to_buffer(10, 1, &mut buf, 0xffffffff, 0x000000ff),
+        Err(UniverseError::InvalidArgument(_))
+    ));
+    // 停在扩展之后的那一代
+    assert!(universe.width() > 8);
+    assert!(universe.generation() < 10);
+
+    // 关闭自动扩展时照常写完所有帧
+    let mut universe = Universe::new_with_size(8, 8).unwrap();
+    universe.stamp_rle("bo$2bo$3o!", 4, 4).unwrap();
+    universe
+        .run_and_write_frames_to_buffer(10, 1, &mut buf, 0xffffffff, 0x000000ff)
+        .unwrap();
+    assert_eq!(universe.generation(), 10);
+}
+
+#[test]
+fn test_analysis_does_not_expand_or_record() {
+    // 8×8 环面上的滑翔机周期为 32；分析用的副本不会因为碰到边缘而扩展
+    let mut universe = Universe::new_with_size(8, 8).unwrap();
+    universe.stamp_rle("bo$2bo$3o!", 0, 0).unwrap();
+    universe.set_auto_expand(true);
+    universe.enable_rewind(50);
+    assert_eq!(universe.compute_period(40), Some(32));
+    assert!(universe.spaceship_direction().is_some());
+    assert_eq!((universe.width(), universe.height()), (8, 8));
+    assert_eq!(universe.rewind_available(), 0);
+
+    let mut universe = Universe::new_with_size(8, 8).unwrap();
+    universe.stamp_rle("2o$2o!", 0, 0).unwrap();
+    universe.set_auto_expand(true);
+    assert_eq!(universe.detect_still_lifes(), [(0, 0, 2, 2)]);
+}
+
+#[test]
+fn test_undo_reverts_edits_but_not_ticks() {
+    let mut universe = empty_universe(8, 8);
+    universe.stamp_rle("3o!", 2, 2).unwrap();
+    universe.set_cell(6, 6, Cell::Alive).unwrap();
+    // 没有改变任何 Cell 的编辑不会入栈
+    universe.set_cell(6, 6, Cell::Alive).unwrap();
+    assert_eq!(universe.undo_stack_depth(), 2);
+
+    universe.tick();
+    universe.set_cell(6, 6, Cell::Alive).unwrap();
+    assert!(universe.undo());
+    assert_eq!(universe[(6, 6)], Cell::Dead);
+    assert!(universe.undo());
+    assert_eq!(universe.generation(), 1);
+    assert_eq!(universe[(6, 6)], Cell::Dead);
+    // 一次 `stamp_rle` 作为一次编辑撤销，只恢复它改变过的 Cell
+    assert!(universe.undo());
+    assert_eq!(universe.live_cells(), [1, 3, 3, 3]);
+    assert!(!universe.undo());
+
+    universe.set_undo_capacity(0);
+    universe.toggle_cell(0, 0).unwrap();
+    assert_eq!(universe.undo_stack_depth(), 0);
+
+    universe.set_undo_capacity(5);
+    universe.paint_line(0, 0, 0, 7);
+    universe.resize(4, 4).unwrap();
+    assert_eq!(universe.undo_stack_depth(), 0);
+}
+
+#[test]
+fn test_rule_counts_round_trip() {
+    let mut universe = empty_universe(4, 4);
+    universe.set_rule("B3678/S34678").unwrap();
+    let (birth, survival) = (universe.birth_counts(), universe.survival_counts());
+    assert_eq!(birth, [3, 6, 7, 8]);
+
+    universe.set_rule("B3/S23").unwrap();
+    universe.apply_totalistic_rule(&birth, &survival).unwrap();
+    assert_eq!(universe.rule(), "B3678/S34678");
+}
+
+#[test]
+fn test_live_components_follow_every_change() {
+    // 每个存活 Cell 都属于恰好一个簇
+    fn check(universe: &Universe) {
+        let counted: u32 = universe
+            .live_components()
+            .iter()
+            .map(|c| c.cell_count)
+            .sum();
+        assert_eq!(counted, universe.population());
+        let ids: Vec<u32> = universe.live_components().iter().map(|c| c.id).collect();
+        assert!(ids.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+
+    let mut universe = empty_universe(16, 16);
+    universe.stamp_rle("b2o$2o$bo!", 6, 6).unwrap();
+    check(&universe);
+    universe.enable_rewind(10);
+    for _ in 0..5 {
+        universe.tick();
+        check(&universe);
+    }
+    // 反复翻转同一个 Cell，死亡和重复的下标都会被跳过
+    for _ in 0..600 {
+        universe.toggle_cell(0, 0).unwrap();
+    }
+    check(&universe);
+    universe.set_cells_dead(&[(0, 0)]);
+    universe.paint_line(15, 0, 15, 15);
+    check(&universe);
+    assert!(universe.undo());
+    check(&universe);
+    universe.rewind(3).unwrap();
+    check(&universe);
+    universe.perturb(40, 7);
+    check(&universe);
+
+    let mut other = Universe::decode_state(&universe.encode_state()).unwrap();
+    check(&other);
+    other.reset();
+    universe.swap(&mut other).unwrap();
+    check(&universe);
+    check(&other);
+
+    // 自动扩展之后下标全部改变
+    let mut universe = empty_universe(8, 8);
+    universe.stamp_rle("bo$2bo$3o!", 4, 4).unwrap();
+    universe.set_auto_expand(true);
+    universe.tick();
+    assert!(universe.width() > 8);
+    check(&universe);
+}
@@ -0,0 +1,132 @@
+//! 模拟不变量的性质测试（仅原生平台）：`cargo test --test properties`
+//! 失败时 proptest 会把输入收缩为尽量小的 (width, height, cells)
+
+#![cfg(not(target_arch = "wasm32"))]
+
+use proptest::collection::vec;
+use proptest::prelude::*;
+use wasm_game_of_life::{parse_rle, Boundary, Cell, Universe};
+
+/// 随机尺寸以及按行排列的 Cell 状态
+fn grid(max: u32) -> impl Strategy<Value = (u32, u32, Vec<bool>)> {
+    (1..=max, 1..=max).prop_flat_map(|(width, height)| {
+        (
+            Just(width),
+            Just(height),
+            vec(any::<bool>(), (width * height) as usize),
+        )
+    })
+}
+
+fn universe_from(width: u32, height: u32, alive: &[bool]) -> Universe {
+    let mut universe = Universe::new_with_size(width, height).unwrap();
+    for row in 0..height {
+        for col in 0..width {
+            let cell = Cell::from(alive[(row * width + col) as usize]);
+            universe.set_cell(row, col, cell).unwrap();
+        }
+    }
+    universe
+}
+
+/// 对每个 Cell 应用坐标变换 f，得到新的 Universe（尺寸为 width x height）
+fn transform(
+    universe: &Universe,
+    width: u32,
+    height: u32,
+    f: impl Fn(u32, u32) -> (u32, u32),
+) -> Universe {
+    let mut out = Universe::new_with_size(width, height).unwrap();
+    out.set_boundary(universe.boundary());
+    for row in 0..universe.height() {
+        for col in 0..universe.width() {
+            let (r, c) = f(row, col);
+            out.set_cell(r, c, universe[(row, col)]).unwrap();
+        }
+    }
+    out
+}
+
+fn rotate(universe: &Universe) -> Universe {
+    let height = universe.height();
+    transform(universe, height, universe.width(), |row, col| {
+        (col, height - 1 - row)
+    })
+}
+
+fn flip(universe: &Universe) -> Universe {
+    let width = universe.width();
+    transform(universe, width, universe.height(), |row, col| {
+        (row, width - 1 - col)
+    })
+}
+
+fn ticked(universe: &Universe) -> Universe {
+    let mut universe = universe.clone();
+    universe.tick();
+    universe
+}
+
+proptest! {
+    #[test]
+    fn tick_preserves_dimensions((width, height, alive) in grid(16)) {
+        let universe = ticked(&universe_from(width, height, &alive));
+        prop_assert_eq!(universe.width(), width);
+        prop_assert_eq!(universe.height(), height);
+        prop_assert_eq!(universe.get_cells().len(), (width * height) as usize);
+        prop_assert!(universe.population() <= width * height);
+    }
+
+    #[test]
+    fn dead_universe_stays_dead(width in 1u32..32, height in 1u32..32, ticks in 1u32..8) {
+        let mut universe = Universe::new_with_size(width, height).unwrap();
+        for _ in 0..ticks {
+            universe.tick();
+        }
+        prop_assert_eq!(universe.population(), 0);
+    }
+
+    #[test]
+    fn simulation_is_deterministic((width, height, alive) in grid(16), ticks in 1u32..8) {
+        let mut a = universe_from(width, height, &alive);
+        let mut b = a.clone();
+        for _ in 0..ticks {
+            a.tick();
+            b.tick();
+            prop_assert_eq!(a.get_cells(), b.get_cells());
+        }
+    }
+
+    #[test]
+    fn symmetry_commutes_with_tick(
+        (width, height, alive) in grid(12),
+        boundary in prop_oneof![Just(Boundary::Toroidal), Just(Boundary::Dead)],
+    ) {
+        let mut universe = universe_from(width, height, &alive);
+        universe.set_boundary(boundary);
+
+        let (expected, actual) = (rotate(&ticked(&universe)), ticked(&rotate(&universe)));
+        prop_assert_eq!(actual.get_cells(), expected.get_cells());
+        let (expected, actual) = (flip(&ticked(&universe)), ticked(&flip(&universe)));
+        prop_assert_eq!(actual.get_cells(), expected.get_cells());
+    }
+
+    #[test]
+    fn encodings_round_trip((width, height, alive) in grid(24)) {
+        let universe = universe_from(width, height, &alive);
+
+        let decoded = Universe::decode_state(&universe.encode_state()).unwrap();
+        prop_assert_eq!(decoded.get_cells(), universe.get_cells());
+
+        let parsed: Universe = universe.to_string().parse().unwrap();
+        prop_assert_eq!(parsed.get_cells(), universe.get_cells());
+
+        let pattern = parse_rle(&universe.to_rle()).unwrap();
+        let mut stamped = Universe::new_with_size(width, height).unwrap();
+        stamped.stamp_pattern(&pattern, 0, 0).unwrap();
+        prop_assert_eq!(stamped.get_cells(), universe.get_cells());
+
+        let restored = Universe::from_compressed_snapshot(&universe.compress_snapshot()).unwrap();
+        prop_assert_eq!(restored.get_cells(), universe.get_cells());
+    }
+}
@@ -1,6 +1,10 @@
 //! Test suite for the Web and headless browsers.
+//!
+//! 在无头浏览器中运行：`wasm-pack test --headless --chrome`（或 `--firefox`），
+//! 也可以在配置了 `wasm-bindgen-test-runner` 之后直接使用
+//! `cargo test --target wasm32-unknown-unknown`（见 .cargo/config.toml）
 
-#![cfg(target_arch = "wasm32")]
+#![cfg(all(target_arch = "wasm32", feature = "web"))]
 
 extern crate wasm_bindgen_test;
 use wasm_bindgen_test::*;
@@ -13,14 +17,17 @@ fn pass() {
 }
 
 extern crate wasm_game_of_life;
-use wasm_game_of_life::Universe;
+use wasm_game_of_life::{
+    compare_engines, Boundary, Cell, Direction, Metrics, RngKind, StopCondition, TickStatus,
+    Universe, UniverseBuilder, UniverseError, UniverseGroup,
+};
 
 #[cfg(test)]
 pub fn input_spaceship() -> Universe {
     let mut universe = Universe::new();
-    universe.set_width(6);
-    universe.set_height(6);
-    universe.set_cells(&[(1,2), (2,3), (3,1), (3,2), (3,3)]);
+    universe.set_width(6).unwrap();
+    universe.set_height(6).unwrap();
+    universe.set_cells(&[(1, 2), (2, 3), (3, 1), (3, 2), (3, 3)]);
 
     universe
 }
@@ -28,9 +35,9 @@ pub fn input_spaceship() -> Universe {
 #[cfg(test)]
 pub fn expected_spaceship() -> Universe {
     let mut universe = Universe::new();
-    universe.set_width(6);
-    universe.set_height(6);
-    universe.set_cells(&[(2,1), (2,3), (3,2), (3,3), (4,2)]);
+    universe.set_width(6).unwrap();
+    universe.set_height(6).unwrap();
+    universe.set_cells(&[(2, 1), (2, 3), (3, 2), (3, 3), (4, 2)]);
 
     universe
 }
@@ -43,5 +50,2732 @@ pub fn test_tick() {
 
     input_spaceship.tick();
 
-    assert_eq!(&input_spaceship.get_cells(), &expected_spaceship.get_cells())
+    assert_eq!(
+        &input_spaceship.get_cells(),
+        &expected_spaceship.get_cells()
+    )
+}
+
+/// 像 JS 一样通过 `cells()` 指针读取 Wasm 内存中的 Cell
+fn memory_view(universe: &Universe) -> &[u8] {
+    let len = (universe.width() * universe.height()) as usize;
+    unsafe { std::slice::from_raw_parts(universe.cells() as *const u8, len) }
+}
+
+#[wasm_bindgen_test]
+pub fn test_cells_memory_contract() {
+    let mut universe = Universe::new_with_size(7, 5).unwrap();
+    universe.set_cells(&[(0, 6), (2, 3), (4, 0)]);
+
+    // JS 端按 row * width + column 的方式还原网格，每个 Cell 占一个字节，0 为死亡，1 为存活
+    let view = memory_view(&universe);
+    assert_eq!(view.len(), 35);
+    for row in 0..universe.height() {
+        for col in 0..universe.width() {
+            let byte = view[(row * universe.width() + col) as usize];
+            assert_eq!(byte, universe[(row, col)] as u8);
+        }
+    }
+    assert_eq!(view.iter().filter(|&&b| b == 1).count(), 3);
+
+    universe.toggle_cell(1, 1).unwrap();
+    universe.toggle_cell(0, 6).unwrap();
+    let view = memory_view(&universe);
+    assert_eq!(view[8], 1);
+    assert_eq!(view[6], 0);
+}
+
+#[wasm_bindgen_test]
+pub fn test_errors_reach_js() {
+    use wasm_bindgen::{JsCast, JsValue};
+
+    let mut universe = Universe::new_with_size(4, 4).unwrap();
+    let err = JsValue::from(universe.toggle_cell(4, 0).unwrap_err());
+    let err: js_sys::Error = err.dyn_into().unwrap();
+    assert_eq!(
+        String::from(err.message()),
+        "cell (4, 0) is out of bounds for a 4x4 universe"
+    );
+
+    let err = JsValue::from(universe.set_width(0).unwrap_err());
+    assert!(err.is_instance_of::<js_sys::Error>());
+    assert_eq!(universe.width(), 4);
+}
+
+#[wasm_bindgen_test]
+pub fn test_render_lines() {
+    let universe = Universe::new_with_size(9, 4).unwrap();
+    let rendered = universe.render();
+    assert_eq!(rendered.lines().count(), 4);
+    assert!(rendered.lines().all(|line| line.chars().count() == 9));
+    assert!(rendered.ends_with('\n'));
+}
+
+#[wasm_bindgen_test]
+pub fn test_fill_from_mask() {
+    let mut universe = Universe::new();
+    universe.set_width(8).unwrap();
+    universe.set_height(8).unwrap();
+
+    universe.fill_from_mask(&[1.0; 64], 42).unwrap();
+    assert!(universe.get_cells().iter().all(|&cell| cell == Cell::Alive));
+
+    universe.fill_from_mask(&[0.0; 64], 42).unwrap();
+    assert!(universe.get_cells().iter().all(|&cell| cell == Cell::Dead));
+
+    assert!(universe.fill_from_mask(&[0.5; 63], 42).is_err());
+}
+
+#[wasm_bindgen_test]
+pub fn test_extinct_event() {
+    use std::cell::Cell as StdCell;
+    use std::rc::Rc;
+    use wasm_bindgen::prelude::*;
+    use wasm_bindgen::JsCast;
+    use wasm_game_of_life::UniverseEvent;
+
+    let mut universe = Universe::new();
+    universe.set_width(6).unwrap();
+    universe.set_height(6).unwrap();
+    // 对角线上的三个 Cell：第一代只剩中间一个，第二代全部死亡
+    universe.set_cells(&[(1, 1), (2, 2), (3, 3)]);
+
+    let extinct_at = Rc::new(StdCell::new(None));
+    let recorded = extinct_at.clone();
+    let callback = Closure::wrap(Box::new(move |info: JsValue| {
+        let generation = js_sys::Reflect::get(&info, &"generation".into()).unwrap();
+        recorded.set(generation.as_f64());
+    }) as Box<dyn FnMut(JsValue)>);
+
+    // 抛出异常的回调不应影响模拟
+    let throwing = js_sys::Function::new_no_args("throw new Error('boom')");
+    universe.on_event(UniverseEvent::extinct(), throwing);
+    universe.on_event(
+        UniverseEvent::extinct(),
+        callback
+            .as_ref()
+            .unchecked_ref::<js_sys::Function>()
+            .clone(),
+    );
+
+    universe.tick();
+    assert_eq!(extinct_at.get(), None);
+    universe.tick();
+    assert_eq!(extinct_at.get(), Some(2.0));
+    assert_eq!(universe.generation(), 2);
+    assert!(universe.get_cells().iter().all(|&cell| cell == Cell::Dead));
+}
+
+#[wasm_bindgen_test]
+pub fn test_stabilized_event() {
+    use std::rc::Rc;
+    use wasm_bindgen::prelude::*;
+    use wasm_bindgen::JsCast;
+    use wasm_game_of_life::UniverseEvent;
+
+    // 方块是静物：第一次 tick 之后就不再变化
+    let mut universe = Universe::new_with_size(6, 6).unwrap();
+    universe.set_cells(&[(1, 1), (1, 2), (2, 1), (2, 2)]);
+
+    let stabilized = Rc::new(std::cell::RefCell::new(vec![]));
+    let recorded = stabilized.clone();
+    let callback = Closure::wrap(Box::new(move |info: JsValue| {
+        let generation = js_sys::Reflect::get(&info, &"generation".into()).unwrap();
+        recorded.borrow_mut().push(generation.as_f64().unwrap());
+    }) as Box<dyn FnMut(JsValue)>);
+    universe.on_event(
+        UniverseEvent::stabilized(),
+        callback
+            .as_ref()
+            .unchecked_ref::<js_sys::Function>()
+            .clone(),
+    );
+
+    // 保持稳定的 tick 不会重复触发
+    for _ in 0..3 {
+        universe.tick();
+    }
+    assert_eq!(*stabilized.borrow(), [1.0]);
+
+    // 编辑之后重新变化，再次稳定时才会触发
+    universe.set_cells(&[(4, 4)]);
+    universe.tick();
+    universe.tick();
+    universe.tick();
+    assert_eq!(*stabilized.borrow(), [1.0, 5.0]);
+
+    // 副本不带回调
+    let mut copy = universe.duplicate();
+    copy.set_cells(&[(4, 4)]);
+    copy.tick();
+    copy.tick();
+    assert_eq!(*stabilized.borrow(), [1.0, 5.0]);
+}
+
+#[wasm_bindgen_test]
+pub fn test_run_until_cycle() {
+    let mut universe = Universe::new();
+    universe.set_width(5).unwrap();
+    universe.set_height(5).unwrap();
+    universe.set_cells(&[(2, 1), (2, 2), (2, 3)]);
+
+    assert_eq!(universe.run_until_cycle(10), Some((0, 2)));
+}
+
+#[wasm_bindgen_test]
+pub fn test_encode_decode_state() {
+    let universe = input_spaceship();
+    let decoded = Universe::decode_state(&universe.encode_state()).unwrap();
+
+    assert_eq!(decoded.width(), 6);
+    assert_eq!(decoded.height(), 6);
+    assert_eq!(decoded.get_cells(), universe.get_cells());
+    assert!(Universe::decode_state("6x6:AAAA").is_err());
+}
+
+#[wasm_bindgen_test]
+pub fn test_save_load_slot() {
+    let mut universe = input_spaceship();
+    universe.save_slot("test").unwrap();
+    let saved = universe.get_cells().to_vec();
+
+    universe.tick();
+    assert_ne!(universe.get_cells(), saved.as_slice());
+
+    let loaded = Universe::load_slot("test").unwrap();
+    assert_eq!(loaded.get_cells(), saved.as_slice());
+    assert!(Universe::list_slots()
+        .unwrap()
+        .contains(&"test".to_string()));
+
+    Universe::delete_slot("test").unwrap();
+    assert!(Universe::load_slot("test").is_err());
+}
+
+#[wasm_bindgen_test]
+pub fn test_set_cells_dead() {
+    let mut universe = Universe::new();
+    universe.set_width(6).unwrap();
+    universe.set_height(6).unwrap();
+    universe.set_cells_alive(&[(1, 1), (1, 2), (2, 1), (2, 2)]);
+    universe.set_cells_dead(&[(1, 1), (2, 2), (10, 10)]);
+
+    let mut expected = Universe::new();
+    expected.set_width(6).unwrap();
+    expected.set_height(6).unwrap();
+    expected.set_cells(&[(1, 2), (2, 1)]);
+
+    assert_eq!(universe.get_cells(), expected.get_cells());
+}
+
+#[wasm_bindgen_test]
+pub fn test_write_cells_into() {
+    let universe = input_spaceship();
+
+    let mut out = vec![0xff; 36];
+    universe.write_cells_into(&mut out).unwrap();
+    let expected: Vec<u8> = universe
+        .get_cells()
+        .iter()
+        .map(|&cell| cell as u8)
+        .collect();
+    assert_eq!(out, expected);
+
+    let mut too_small = vec![0; 35];
+    assert!(universe.write_cells_into(&mut too_small).is_err());
+}
+
+#[wasm_bindgen_test]
+pub fn test_viewport() {
+    let mut universe = Universe::new();
+    universe.set_width(8).unwrap();
+    universe.set_height(8).unwrap();
+    universe.set_cells(&[(3, 3), (3, 6)]);
+
+    universe.set_viewport(2, 2, 4, 4);
+    assert_eq!(universe.live_cells(), vec![1, 1]);
+    assert_eq!(universe.render_rgba().len(), 4 * 4 * 4);
+
+    universe.pan(10, -10);
+    assert_eq!(universe.viewport_row(), 4);
+    assert_eq!(universe.viewport_col(), 0);
+
+    universe.set_viewport(0, 0, 100, 100);
+    assert_eq!(universe.viewport_rows(), 8);
+    assert_eq!(universe.viewport_cols(), 8);
+    assert_eq!(universe.live_cells(), vec![3, 3, 3, 6]);
+}
+
+#[wasm_bindgen_test]
+pub fn test_builder_defaults() {
+    let universe = UniverseBuilder::new().build().unwrap();
+    assert_eq!(universe.width(), 64);
+    assert_eq!(universe.height(), 64);
+    assert_eq!(universe.rule(), "B3/S23");
+    assert_eq!(universe.boundary(), Boundary::Toroidal);
+}
+
+#[wasm_bindgen_test]
+pub fn test_builder_pattern() {
+    let universe = UniverseBuilder::new()
+        .width(6)
+        .height(6)
+        .boundary(Boundary::Dead)
+        .cells_from_rle("x = 3, y = 3\nbob$2bo$3o!")
+        .build()
+        .unwrap();
+    assert_eq!(
+        universe.get_cells(),
+        input_spaceship_at_origin().get_cells()
+    );
+
+    let seeded = |seed| {
+        UniverseBuilder::new()
+            .seed(seed)
+            .density(0.3)
+            .build()
+            .unwrap()
+    };
+    assert_eq!(seeded(7).get_cells(), seeded(7).get_cells());
+}
+
+fn input_spaceship_at_origin() -> Universe {
+    let mut universe = Universe::new();
+    universe.set_width(6).unwrap();
+    universe.set_height(6).unwrap();
+    universe.set_cells(&[(0, 1), (1, 2), (2, 0), (2, 1), (2, 2)]);
+
+    universe
+}
+
+#[wasm_bindgen_test]
+pub fn test_builder_errors() {
+    let error = |builder: UniverseBuilder| builder.build().err().unwrap();
+
+    let err = error(UniverseBuilder::new().width(0));
+    assert_eq!(
+        err,
+        UniverseError::InvalidDimensions {
+            width: 0,
+            height: 64
+        }
+    );
+    assert_eq!(
+        err.to_string(),
+        "invalid dimensions 0x64: width and height must be nonzero"
+    );
+
+    let err = error(UniverseBuilder::new().width(u32::MAX));
+    assert_eq!(
+        err,
+        UniverseError::InvalidDimensions {
+            width: u32::MAX,
+            height: 64
+        }
+    );
+    assert_eq!(
+        err.to_string(),
+        "invalid dimensions 4294967295x64: at most 67108864 cells are supported"
+    );
+
+    let err = error(UniverseBuilder::new().rule("B3S23"));
+    assert!(matches!(err, UniverseError::InvalidRule { .. }));
+    assert_eq!(err.to_string(), "invalid rule 'B3S23': expected B.../S...");
+
+    let err = error(UniverseBuilder::new().density(1.5));
+    assert!(matches!(err, UniverseError::InvalidArgument(_)));
+    assert_eq!(err.to_string(), "density 1.5 is out of range [0, 1]");
+
+    let err = error(UniverseBuilder::new().density(0.5).pattern("glider", 0, 0));
+    assert!(matches!(err, UniverseError::InvalidArgument(_)));
+    assert_eq!(
+        err.to_string(),
+        "density cannot be combined with explicit cells"
+    );
+
+    let err = error(UniverseBuilder::new().pattern("nothing", 0, 0));
+    assert_eq!(err, UniverseError::UnknownPattern("nothing".to_string()));
+    assert_eq!(err.to_string(), "unknown pattern 'nothing'");
+
+    let err = error(
+        UniverseBuilder::new()
+            .width(4)
+            .height(4)
+            .pattern("glider", 2, 2),
+    );
+    assert_eq!(
+        err,
+        UniverseError::OutOfBounds {
+            row: 4,
+            col: 4,
+            width: 4,
+            height: 4
+        }
+    );
+    assert_eq!(
+        err.to_string(),
+        "cell (4, 4) is out of bounds for a 4x4 universe"
+    );
+}
+
+#[wasm_bindgen_test]
+pub fn test_errors() {
+    let mut universe = input_spaceship();
+
+    let err = universe.toggle_cell(6, 0).unwrap_err();
+    assert_eq!(
+        err,
+        UniverseError::OutOfBounds {
+            row: 6,
+            col: 0,
+            width: 6,
+            height: 6
+        }
+    );
+    assert_eq!(
+        err.to_string(),
+        "cell (6, 0) is out of bounds for a 6x6 universe"
+    );
+    assert_eq!(universe.get_cells(), input_spaceship().get_cells());
+
+    let err = universe.set_rule("B3/S2x").unwrap_err();
+    assert_eq!(
+        err,
+        UniverseError::InvalidRule {
+            rule: "B3/S2x".to_string(),
+            message: "unexpected 'x'".to_string()
+        }
+    );
+    assert_eq!(err.to_string(), "invalid rule 'B3/S2x': unexpected 'x'");
+    assert_eq!(
+        universe.set_rule("S23/B3").unwrap_err().to_string(),
+        "invalid rule 'S23/B3': expected 'B' section"
+    );
+
+    let err = wasm_game_of_life::parse_rle("#C comment\nx = 3, y = 3\nbo?$2bo!").unwrap_err();
+    assert_eq!(
+        err,
+        UniverseError::ParseError {
+            line: 3,
+            message: "unexpected character '?'".to_string()
+        }
+    );
+    assert_eq!(err.to_string(), "line 3: unexpected character '?'");
+    assert_eq!(
+        wasm_game_of_life::parse_rle("x = three")
+            .unwrap_err()
+            .to_string(),
+        "line 1: invalid x 'three'"
+    );
+
+    let err = universe.write_cells_into(&mut [0; 3]).unwrap_err();
+    assert_eq!(
+        err,
+        UniverseError::LengthMismatch {
+            expected: 36,
+            actual: 3
+        }
+    );
+    assert_eq!(
+        err.to_string(),
+        "buffer length 3 does not match expected length 36"
+    );
+
+    let err = Universe::decode_state("6x6").unwrap_err();
+    assert_eq!(
+        err,
+        UniverseError::InvalidEncoding("missing ':' separator".to_string())
+    );
+    assert_eq!(err.to_string(), "invalid encoding: missing ':' separator");
+    assert_eq!(
+        Universe::decode_state("0x6:").unwrap_err(),
+        UniverseError::InvalidDimensions {
+            width: 0,
+            height: 6
+        }
+    );
+}
+
+#[wasm_bindgen_test]
+pub fn test_write_state_to_image_data() {
+    let mut universe = Universe::new();
+    universe.set_width(2).unwrap();
+    universe.set_height(1).unwrap();
+    universe.set_cells(&[(0, 1)]);
+
+    let mut buf = vec![0; 4 * 2 * 4];
+    universe
+        .write_state_to_image_data(&mut buf, 2, 0x112233ff, 0xffffffff)
+        .unwrap();
+    let dead = [0xff, 0xff, 0xff, 0xff];
+    let alive = [0x11, 0x22, 0x33, 0xff];
+    assert_eq!(
+        buf,
+        [dead, dead, alive, alive, dead, dead, alive, alive].concat()
+    );
+
+    assert!(universe
+        .write_state_to_image_data(&mut buf[1..], 2, 0, 0)
+        .is_err());
+}
+
+#[wasm_bindgen_test]
+pub fn test_run_and_write_frames_to_buffer() {
+    // 3x3 的闪烁器，每个 Cell 一个像素
+    let mut universe = Universe::new_with_size(3, 3).unwrap();
+    universe.set_cells(&[(1, 0), (1, 1), (1, 2)]);
+    let mut expected = vec![];
+    let mut reference = universe.duplicate();
+    for _ in 0..3 {
+        reference.tick();
+        let mut frame = vec![0; 3 * 3 * 4];
+        reference
+            .write_state_to_image_data(&mut frame, 1, 0x000000ff, 0xffffffff)
+            .unwrap();
+        expected.extend(frame);
+    }
+
+    let mut buf = vec![0; 3 * 3 * 3 * 4];
+    universe
+        .run_and_write_frames_to_buffer(3, 1, &mut buf, 0x000000ff, 0xffffffff)
+        .unwrap();
+    assert_eq!(buf, expected);
+    assert_eq!(universe.generation(), 3);
+    assert_ne!(buf[..36], buf[36..72]);
+
+    // 大小不对时不会前进
+    assert!(universe
+        .run_and_write_frames_to_buffer(2, 1, &mut buf, 0, 0)
+        .is_err());
+    assert_eq!(universe.generation(), 3);
+}
+
+#[wasm_bindgen_test]
+pub fn test_tick_and_write_canvas() {
+    use wasm_bindgen::JsCast;
+    use web_sys::CanvasRenderingContext2d;
+
+    let ctx: CanvasRenderingContext2d = js_sys::Function::new_no_args(
+        "const canvas = document.createElement('canvas');\
+         canvas.width = 10;\
+         canvas.height = 10;\
+         return canvas.getContext('2d');",
+    )
+    .call0(&wasm_bindgen::JsValue::NULL)
+    .unwrap()
+    .dyn_into()
+    .unwrap();
+    let canvas_pixels =
+        |ctx: &CanvasRenderingContext2d| ctx.get_image_data(0.0, 0.0, 10.0, 10.0).unwrap().data().0;
+
+    // 5x5 的闪烁器，每个 Cell 2x2 像素：第一次整体重绘，之后只重写变化的 Cell
+    let mut universe = Universe::new_with_size(5, 5).unwrap();
+    universe.set_cells(&[(2, 1), (2, 2), (2, 3)]);
+    for generation in 1..=3 {
+        universe.tick_and_write_canvas(&ctx, 2).unwrap();
+        assert_eq!(universe.generation(), generation);
+        let mut expected = vec![0; 10 * 10 * 4];
+        universe
+            .write_state_to_image_data(&mut expected, 2, 0x000000ff, 0xffffffff)
+            .unwrap();
+        assert_eq!(canvas_pixels(&ctx), expected);
+    }
+
+    // cell_px 为 0 时报错，不会 tick
+    assert!(universe.tick_and_write_canvas(&ctx, 0).is_err());
+    assert_eq!(universe.generation(), 3);
+
+    // 自动扩展之后按新的尺寸整体重绘，之后继续只重写变化的 Cell；
+    // Canvas 只有 10x10，比较它与整帧左上角的部分
+    let mut universe = Universe::new_with_size(5, 5).unwrap();
+    universe.set_cells(&[(0, 1), (0, 2), (0, 3)]);
+    universe.set_auto_expand(true);
+    universe.tick_and_write_canvas(&ctx, 2).unwrap();
+    universe.tick_and_write_canvas(&ctx, 1).unwrap();
+    assert!(universe.width() > 5);
+    for _ in 0..2 {
+        let (width, height) = (universe.width() as usize, universe.height() as usize);
+        let mut frame = vec![0; width * height * 4];
+        universe
+            .write_state_to_image_data(&mut frame, 1, 0x000000ff, 0xffffffff)
+            .unwrap();
+        let expected: Vec<u8> = (0..10)
+            .flat_map(|y| frame[y * width * 4..][..10 * 4].to_vec())
+            .collect();
+        assert_eq!(canvas_pixels(&ctx), expected);
+        universe.tick_and_write_canvas(&ctx, 1).unwrap();
+    }
+}
+
+#[wasm_bindgen_test]
+pub fn test_render_wrapped() {
+    let universe = input_spaceship();
+    let rendered = universe.render_wrapped();
+    let lines: Vec<&str> = rendered.lines().collect();
+
+    assert_eq!(lines.len(), 7);
+    assert_eq!(lines[6], lines[0]);
+    for (line, plain) in lines.iter().zip(universe.render().lines()) {
+        assert!(line.starts_with(plain));
+        assert_eq!(line.chars().last(), plain.chars().next());
+    }
+}
+
+#[wasm_bindgen_test]
+pub fn test_duplicate() {
+    let mut universe = input_spaceship();
+    let copy = universe.duplicate();
+
+    universe.tick();
+    assert_eq!(copy.get_cells(), input_spaceship().get_cells());
+    assert_eq!(copy.generation(), 0);
+    assert_eq!(universe.get_cells(), expected_spaceship().get_cells());
+    assert_eq!(
+        format!("{:?}", copy),
+        "Universe { width: 6, height: 6, generation: 0, population: 5, rule: \"B3/S23\" }"
+    );
+}
+
+#[wasm_bindgen_test]
+pub fn test_neighbor_count_large_radius() {
+    let mut universe = Universe::new();
+    universe.set_width(20).unwrap();
+    universe.set_height(20).unwrap();
+    universe.fill_from_mask(&[1.0; 400], 0).unwrap();
+
+    // 17 * 17 - 1 = 288，超过了 u8 的范围
+    assert_eq!(universe.live_neighbor_count_in_radius(10, 10, 8), Ok(288));
+    assert_eq!(universe.live_neighbor_count_in_radius(10, 10, 1), Ok(8));
+
+    universe.set_boundary(Boundary::Dead);
+    universe.fill_from_mask(&[1.0; 400], 0).unwrap();
+    assert_eq!(
+        universe.live_neighbor_count_in_radius(0, 0, 8),
+        Ok(9 * 9 - 1)
+    );
+}
+
+#[wasm_bindgen_test]
+pub fn test_alive_boundary() {
+    let edge_cell_after_tick = |boundary| {
+        let mut universe = Universe::new();
+        universe.set_width(5).unwrap();
+        universe.set_height(5).unwrap();
+        universe.set_boundary(boundary);
+        universe.set_cells(&[(0, 2)]);
+        universe.tick();
+        universe.get_cells()[2]
+    };
+
+    // 顶部边缘的 Cell 有 3 个网格外的邻居
+    assert_eq!(edge_cell_after_tick(Boundary::Alive), Cell::Alive);
+    assert_eq!(edge_cell_after_tick(Boundary::Dead), Cell::Dead);
+}
+
+#[wasm_bindgen_test]
+pub fn test_dimension_validation() {
+    let err = Universe::new_with_size(0, 5).err().unwrap();
+    assert_eq!(
+        err,
+        UniverseError::InvalidDimensions {
+            width: 0,
+            height: 5
+        }
+    );
+    assert_eq!(
+        err.to_string(),
+        "invalid dimensions 0x5: width and height must be nonzero"
+    );
+
+    let universe = Universe::new_with_size(1, 1).unwrap();
+    assert_eq!(universe.get_cells(), &[Cell::Dead]);
+
+    assert!(Universe::validate_dimensions(8192, 8192).is_ok());
+    assert!(Universe::validate_dimensions(1, Universe::MAX_CELLS).is_ok());
+    assert_eq!(
+        Universe::validate_dimensions(8192, 8193),
+        Err(UniverseError::InvalidDimensions {
+            width: 8192,
+            height: 8193
+        })
+    );
+    assert!(Universe::validate_dimensions(Universe::MAX_CELLS + 1, 1).is_err());
+
+    let mut universe = input_spaceship();
+    assert!(universe.set_width(0).is_err());
+    assert!(universe.set_height(u32::MAX).is_err());
+    assert!(universe.resize(u32::MAX, u32::MAX).is_err());
+    assert_eq!(universe.get_cells(), input_spaceship().get_cells());
+    universe.tick();
+}
+
+#[wasm_bindgen_test]
+pub fn test_set_random_alive_cells() {
+    let mut universe = Universe::new_with_size(10, 10).unwrap();
+    universe.set_random_alive_cells(37, 1).unwrap();
+    assert_eq!(universe.population(), 37);
+
+    universe.set_random_alive_cells(100, 1).unwrap();
+    assert_eq!(universe.population(), 100);
+
+    assert!(universe.set_random_alive_cells(101, 1).is_err());
+}
+
+#[wasm_bindgen_test]
+pub fn test_load_library_patterns() {
+    let patterns = Universe::available_patterns();
+    assert!(patterns.contains(&"gosper_glider_gun".to_string()));
+
+    for name in patterns {
+        let mut universe = Universe::new_with_size(64, 64).unwrap();
+        universe.load_pattern(&name, 1, 1).unwrap();
+        assert!(universe.population() > 0, "{} is empty", name);
+    }
+
+    let mut universe = Universe::new_with_size(64, 64).unwrap();
+    universe.load_pattern("gosper_glider_gun", 0, 0).unwrap();
+    assert_eq!(universe.population(), 36);
+    assert!(universe.load_pattern("unknown", 0, 0).is_err());
+}
+
+/// 宽或高小于 3 的环形宇宙中，重复的邻居只计算一次，且 Cell 不是自己的邻居
+#[wasm_bindgen_test]
+pub fn test_degenerate_grids() {
+    let after_tick = |width, height, cells: &[(u32, u32)]| {
+        let mut universe = Universe::new_with_size(width, height).unwrap();
+        universe.set_cells(cells);
+        universe.tick();
+        universe
+    };
+
+    // 1×1：唯一的 Cell 没有邻居
+    assert_eq!(after_tick(1, 1, &[(0, 0)]).population(), 0);
+
+    // 1×5：每个 Cell 只有左右两个邻居
+    let universe = after_tick(5, 1, &[(0, 0), (0, 1), (0, 2)]);
+    assert_eq!(universe.live_cells(), vec![0, 1]);
+
+    // 2×2：每个 Cell 的邻居是另外三个 Cell
+    assert_eq!(after_tick(2, 2, &[(0, 0), (0, 1), (1, 1)]).population(), 4);
+    assert_eq!(
+        after_tick(2, 2, &[(0, 0), (0, 1), (1, 0), (1, 1)]).population(),
+        4
+    );
+
+    // 3×3：八个邻居各不相同，横向的 blinker 会填满整个网格
+    let mut universe = after_tick(3, 3, &[(1, 0), (1, 1), (1, 2)]);
+    assert_eq!(universe.population(), 9);
+    universe.tick();
+    assert_eq!(universe.population(), 0);
+}
+
+#[wasm_bindgen_test]
+pub fn test_compute_period_in_region() {
+    let mut universe = Universe::new_with_size(20, 10).unwrap();
+    // 左边是周期为 2 的 blinker，右边是静止的 block
+    universe.set_cells(&[(4, 2), (4, 3), (4, 4), (4, 12), (4, 13), (5, 12), (5, 13)]);
+
+    assert_eq!(
+        universe.compute_period_in_region(2, 1, 5, 5, 10),
+        Ok(Some(2))
+    );
+    assert_eq!(
+        universe.compute_period_in_region(3, 11, 4, 4, 10),
+        Ok(Some(1))
+    );
+    assert_eq!(universe.compute_period(10), Some(2));
+    assert_eq!(universe.compute_period_in_region(2, 1, 5, 5, 1), Ok(None));
+    assert!(universe.compute_period_in_region(8, 18, 5, 5, 10).is_err());
+    assert_eq!(universe.generation(), 0);
+}
+
+#[wasm_bindgen_test]
+pub fn test_tick_status() {
+    let mut universe = Universe::new_with_size(8, 8).unwrap();
+    universe.set_cells(&[(3, 2), (3, 3), (3, 4)]);
+    assert_eq!(universe.tick_status(), TickStatus::Changed);
+
+    let mut universe = Universe::new_with_size(8, 8).unwrap();
+    universe.set_cells(&[(2, 2), (2, 3), (3, 2), (3, 3)]);
+    assert_eq!(universe.tick_status(), TickStatus::Stable);
+
+    let mut universe = Universe::new_with_size(8, 8).unwrap();
+    universe.set_cells(&[(2, 2), (5, 5)]);
+    assert_eq!(universe.tick_status(), TickStatus::Extinct);
+    assert_eq!(universe.tick_status(), TickStatus::Extinct);
+    assert_eq!(universe.generation(), 2);
+}
+
+#[wasm_bindgen_test]
+pub fn test_spaceship_direction() {
+    let gliders = [
+        (
+            vec![(0, 1), (1, 2), (2, 0), (2, 1), (2, 2)],
+            Direction::SouthEast,
+        ),
+        (
+            vec![(0, 1), (1, 0), (2, 0), (2, 1), (2, 2)],
+            Direction::SouthWest,
+        ),
+        (
+            vec![(0, 0), (0, 1), (0, 2), (1, 2), (2, 1)],
+            Direction::NorthEast,
+        ),
+        (
+            vec![(0, 0), (0, 1), (0, 2), (1, 0), (2, 1)],
+            Direction::NorthWest,
+        ),
+    ];
+    for (cells, direction) in gliders.iter() {
+        let mut universe = Universe::new_with_size(16, 16).unwrap();
+        let cells: Vec<(u32, u32)> = cells.iter().map(|&(r, c)| (r + 6, c + 6)).collect();
+        universe.set_cells(&cells);
+        assert_eq!(universe.spaceship_direction(), Some(*direction));
+        assert_eq!(universe.generation(), 0);
+    }
+
+    let mut universe = Universe::new_with_size(16, 16).unwrap();
+    assert_eq!(universe.spaceship_direction(), None);
+    universe.set_cells(&[(5, 4), (5, 5), (5, 6)]);
+    assert_eq!(universe.spaceship_direction(), None);
+}
+
+#[wasm_bindgen_test]
+pub fn test_cell_conversions() {
+    use std::convert::TryFrom;
+
+    for &cell in [Cell::Dead, Cell::Alive].iter() {
+        assert_eq!(Cell::from(bool::from(cell)), cell);
+        assert_eq!(Cell::try_from(cell as u8), Ok(cell));
+        assert_eq!(!!cell, cell);
+        assert_eq!(cell.is_alive(), bool::from(cell));
+        assert_eq!(wasm_game_of_life::cell_toggled(cell), !cell);
+    }
+    for &alive in [false, true].iter() {
+        assert_eq!(bool::from(Cell::from(alive)), alive);
+        assert_eq!(wasm_game_of_life::cell_from_bool(alive).is_alive(), alive);
+    }
+    for value in 2..=u8::MAX {
+        assert!(matches!(
+            Cell::try_from(value),
+            Err(UniverseError::InvalidArgument(_))
+        ));
+    }
+    assert_eq!(Cell::default(), Cell::Dead);
+    assert_eq!(!Cell::Dead, Cell::Alive);
+
+    let mut cell = Cell::Dead;
+    cell.toggle();
+    assert!(cell.is_alive());
+}
+
+#[wasm_bindgen_test]
+pub fn test_rule_counts() {
+    let mut universe = Universe::new_with_size(8, 8).unwrap();
+    universe.set_rule("B36/S23").unwrap();
+    assert_eq!(universe.birth_counts(), vec![3, 6]);
+    assert_eq!(universe.survival_counts(), vec![2, 3]);
+
+    universe.apply_totalistic_rule(&[6, 3, 3], &[3, 2]).unwrap();
+    assert_eq!(universe.rule(), "B36/S23");
+    assert!(universe.apply_totalistic_rule(&[9], &[]).is_err());
+    assert!(universe.apply_totalistic_rule(&[3], &[2, 9]).is_err());
+    // Moore 邻域最多只有 8 个邻居，B/S 记法中的 9 同样会被拒绝而不是被忽略
+    assert!(universe.set_rule("B39/S23").is_err());
+    assert!(universe.set_rule("B3/S239").is_err());
+    assert_eq!(universe.rule(), "B36/S23");
+
+    let mut blinker = Universe::new_with_size(5, 5).unwrap();
+    blinker.apply_totalistic_rule(&[3], &[2, 3]).unwrap();
+    blinker.set_cells(&[(2, 1), (2, 2), (2, 3)]);
+    blinker.tick();
+    let mut expected = Universe::new_with_size(5, 5).unwrap();
+    expected.set_cells(&[(1, 2), (2, 2), (3, 2)]);
+    assert_eq!(blinker.get_cells(), expected.get_cells());
+}
+
+#[wasm_bindgen_test]
+pub fn test_render_glyphs() {
+    let mut universe = Universe::new_with_size(5, 4).unwrap();
+    universe.set_cells(&[(0, 1), (1, 2), (2, 0), (2, 1), (2, 2)]);
+    assert_eq!(universe.render_region(0, 0, 1, 3), "◻◼◻\n");
+
+    assert!(universe.set_render_glyphs('#', '#').is_err());
+    universe.set_render_glyphs('#', '.').unwrap();
+    assert_eq!(universe.render(), ".#...\n..#..\n###..\n.....\n");
+
+    let parsed: Universe = universe.render().parse().unwrap();
+    assert_eq!(parsed.width(), 5);
+    assert_eq!(parsed.height(), 4);
+    assert_eq!(parsed.get_cells(), universe.get_cells());
+    let parsed: Universe = Universe::new_with_size(5, 4)
+        .unwrap()
+        .render()
+        .parse()
+        .unwrap();
+    assert_eq!(parsed.population(), 0);
+    assert!("#.\n#".parse::<Universe>().is_err());
+    assert!("#?\n".parse::<Universe>().is_err());
+
+    // 区域超出边界时裁掉多余的部分
+    assert_eq!(universe.render_region(2, 3, 10, 10), "..\n..\n");
+    assert_eq!(universe.render_region(1, 1, 3, 3), ".#\n##\n");
+    assert_eq!(universe.render_region(4, 0, 10, 10), "");
+
+    assert!(universe.set_render_row_separator("").is_err());
+    universe.set_render_row_separator("|").unwrap();
+    assert_eq!(universe.render_region(1, 1, 3, 3), ".#|##|");
+}
+
+#[wasm_bindgen_test]
+pub fn test_step_symmetric() {
+    let mut universe = Universe::new_with_size(10, 10).unwrap();
+    // 一个不对称的 R-pentomino
+    universe.set_cells(&[(3, 4), (3, 5), (4, 3), (4, 4), (5, 4)]);
+    for _ in 0..5 {
+        universe.step_symmetric().unwrap();
+        let cells = universe.get_cells();
+        for row in 0..10 {
+            for col in 0..10 {
+                assert_eq!(cells[row * 10 + col], cells[col * 10 + (9 - row)]);
+            }
+        }
+    }
+    assert_eq!(universe.generation(), 5);
+
+    let mut universe = Universe::new_with_size(10, 8).unwrap();
+    assert!(universe.step_symmetric().is_err());
+    assert_eq!(universe.generation(), 0);
+}
+
+#[wasm_bindgen_test]
+pub fn test_stamp_rle() {
+    let glider = "x = 3, y = 3\nbob$2bo$3o!";
+    let mut universe = Universe::new_with_size(12, 12).unwrap();
+    universe.set_cells(&[(1, 1), (1, 2), (2, 1), (2, 2)]);
+    universe.stamp_rle(glider, 6, 6).unwrap();
+    assert_eq!(universe.population(), 9);
+    assert_eq!(
+        universe.render_region(1, 1, 3, 3) + &universe.render_region(6, 6, 9, 9),
+        "◼◼\n◼◼\n◻◼◻\n◻◻◼\n◼◼◼\n"
+    );
+
+    // 环形宇宙绕回，有边界时裁掉
+    let mut universe = Universe::new_with_size(12, 12).unwrap();
+    universe.stamp_rle(glider, 11, 11).unwrap();
+    assert_eq!(universe.population(), 5);
+    assert_eq!(universe.render_region(0, 0, 2, 2), "◻◼\n◼◼\n");
+    let mut universe = Universe::new_with_size(12, 12).unwrap();
+    universe.set_boundary(Boundary::Dead);
+    universe.stamp_rle(glider, 11, 11).unwrap();
+    assert_eq!(universe.population(), 0);
+
+    assert!(universe.stamp_rle(glider, 12, 0).is_err());
+    assert!(universe.stamp_rle("bo$?!", 0, 0).is_err());
+}
+
+#[wasm_bindgen_test]
+pub fn test_index_and_set_cell() {
+    let mut universe = Universe::new_with_size(6, 4).unwrap();
+    universe.set_cell(1, 2, Cell::Alive).unwrap();
+    assert_eq!(universe[(1, 2)], Cell::Alive);
+    assert_eq!(universe[(2, 1)], Cell::Dead);
+    assert_eq!(universe.get(1, 2), Some(Cell::Alive));
+    assert_eq!(universe.get(4, 0), None);
+    assert_eq!(universe.get(0, 6), None);
+    assert!(universe.set_cell(4, 0, Cell::Alive).is_err());
+
+    // 索引使用与 set_cells 相同的逻辑坐标，偏移后也保持一致
+    universe.set_toroidal_offset(1, 1);
+    universe.set_cell(0, 0, Cell::Alive).unwrap();
+    assert_eq!(universe[(0, 0)], Cell::Alive);
+
+    // set_cell 之后 tick 看到的是最新的状态
+    let mut blinker = Universe::new_with_size(5, 5).unwrap();
+    for col in 1..4 {
+        blinker.set_cell(2, col, Cell::Alive).unwrap();
+    }
+    blinker.tick();
+    assert_eq!(blinker[(1, 2)], Cell::Alive);
+    assert_eq!(blinker[(2, 1)], Cell::Dead);
+}
+
+#[wasm_bindgen_test]
+#[should_panic(expected = "out of bounds")]
+pub fn test_index_out_of_bounds() {
+    let universe = Universe::new_with_size(6, 4).unwrap();
+    let _ = universe[(4, 0)];
+}
+
+#[wasm_bindgen_test]
+pub fn test_walls() {
+    let mut universe = Universe::new_with_size(6, 6).unwrap();
+    universe.set_cells(&[(2, 1), (2, 2), (2, 3)]);
+    // (1, 2) 在 blinker 翻转时本应出生，(2, 1) 本应死亡
+    universe.set_wall(1, 2, true).unwrap();
+    universe.set_wall(2, 1, true).unwrap();
+    assert!(universe.is_wall(1, 2));
+    assert!(!universe.is_wall(3, 2));
+    for _ in 0..4 {
+        universe.tick();
+        assert_eq!(universe[(1, 2)], Cell::Dead);
+        assert_eq!(universe[(2, 1)], Cell::Alive);
+    }
+    assert!(universe.set_wall(6, 0, true).is_err());
+
+    // 墙默认视为死亡：存活的墙 (2, 1) 不会帮助 (1, 1)/(3, 1) 出生
+    let mut universe = Universe::new_with_size(6, 6).unwrap();
+    universe.set_cells(&[(2, 1), (2, 2), (2, 3)]);
+    universe.set_wall(2, 1, true).unwrap();
+    universe.tick();
+    assert_eq!(universe.population(), 1);
+
+    let mut universe = Universe::new_with_size(6, 6).unwrap();
+    universe.set_cells(&[(2, 1), (2, 2), (2, 3)]);
+    universe.set_wall(2, 1, true).unwrap();
+    universe.set_walls_count_as_dead(false);
+    universe.tick();
+    assert_eq!(universe[(2, 1)], Cell::Alive);
+    assert_eq!(universe[(1, 2)], Cell::Alive);
+    assert_eq!(universe[(3, 2)], Cell::Alive);
+
+    universe.clear_walls();
+    assert!(!universe.is_wall(2, 1));
+}
+
+#[wasm_bindgen_test]
+pub fn test_encode_pattern_fingerprint() {
+    let glider = [(0, 1), (1, 2), (2, 0), (2, 1), (2, 2)];
+    let at = |row: u32, col: u32, width: u32| {
+        let mut universe = Universe::new_with_size(width, 20).unwrap();
+        let cells: Vec<(u32, u32)> = glider.iter().map(|&(r, c)| (r + row, c + col)).collect();
+        universe.set_cells(&cells);
+        universe.encode_pattern_fingerprint()
+    };
+
+    let fingerprint = at(0, 0, 20);
+    assert_eq!(fingerprint.len(), 32);
+    assert_eq!(at(7, 11, 20), fingerprint);
+    assert_eq!(at(3, 5, 30), fingerprint);
+
+    let mut blinker = Universe::new_with_size(20, 20).unwrap();
+    blinker.set_cells(&[(4, 4), (4, 5), (4, 6)]);
+    assert_ne!(blinker.encode_pattern_fingerprint(), fingerprint);
+    blinker.tick();
+    assert_ne!(
+        blinker.encode_pattern_fingerprint(),
+        Universe::new_with_size(20, 20)
+            .unwrap()
+            .encode_pattern_fingerprint()
+    );
+}
+
+#[wasm_bindgen_test]
+pub fn test_normalize_position() {
+    let glider = [(0, 1), (1, 2), (2, 0), (2, 1), (2, 2)];
+    let mut origin = Universe::new_with_size(16, 16).unwrap();
+    origin.set_cells(&glider);
+
+    let mut universe = Universe::new_with_size(16, 16).unwrap();
+    let cells: Vec<(u32, u32)> = glider.iter().map(|&(r, c)| (r + 9, c + 4)).collect();
+    universe.set_cells(&cells);
+    universe.normalize_position();
+    assert_eq!(universe.get_cells(), origin.get_cells());
+
+    // 44 代后滑翔机的包围盒跨越左右边界，归位后仍然与原点处的相同
+    let mut universe = Universe::new_with_size(16, 16).unwrap();
+    universe.set_cells(&cells);
+    for _ in 0..44 {
+        universe.tick();
+    }
+    universe.normalize_position();
+    assert_eq!(universe.get_cells(), origin.get_cells());
+    assert_eq!(
+        universe.encode_pattern_fingerprint(),
+        origin.encode_pattern_fingerprint()
+    );
+
+    let mut empty = Universe::new_with_size(4, 4).unwrap();
+    empty.normalize_position();
+    assert_eq!(empty.population(), 0);
+}
+
+#[wasm_bindgen_test]
+pub fn test_render_into() {
+    let mut universe = Universe::new_with_size(7, 5).unwrap();
+    universe.set_cells(&[(1, 2), (2, 3), (3, 1), (3, 2), (3, 3)]);
+
+    let mut buf = String::from("stale contents");
+    universe.render_into(&mut buf);
+    assert_eq!(buf, universe.render());
+
+    let capacity = buf.capacity();
+    universe.tick();
+    universe.render_into(&mut buf);
+    assert_eq!(buf, universe.render());
+    assert_eq!(buf.capacity(), capacity);
+}
+
+#[wasm_bindgen_test]
+pub fn test_wrap_shift() {
+    // 向东南飞行的滑翔机从右边界穿出，16 代后完全回到左侧
+    let glider: Vec<(u32, u32)> = [(0, 1), (1, 2), (2, 0), (2, 1), (2, 2)]
+        .iter()
+        .map(|&(r, c)| (r + 2, c + 13))
+        .collect();
+    let mut plain = Universe::new_with_size(16, 16).unwrap();
+    plain.set_cells(&glider);
+    let mut shifted = Universe::new_with_size(16, 16).unwrap();
+    shifted.set_cells(&glider);
+    shifted.set_wrap_shift(5, 0);
+
+    for _ in 0..16 {
+        plain.tick();
+        shifted.tick();
+    }
+    assert_eq!(shifted.population(), 5);
+    for row in 0..16 {
+        for col in 0..16 {
+            assert_eq!(shifted[((row + 5) % 16, col)], plain[(row, col)]);
+        }
+    }
+}
+
+#[wasm_bindgen_test]
+pub fn test_simulate_and_record() {
+    let mut universe = UniverseBuilder::new()
+        .width(32)
+        .height(32)
+        .pattern("glider", 1, 1)
+        .build()
+        .unwrap();
+    let mut expected = universe.clone();
+
+    let snapshots = universe.simulate_and_record(8);
+    assert_eq!(snapshots.len(), 8);
+    for snapshot in snapshots.iter() {
+        expected.tick();
+        // 32x32 的 Cell 原始数据需要 1024 字节
+        assert!(snapshot.len() < 64);
+        let restored = Universe::from_compressed_snapshot(snapshot).unwrap();
+        assert_eq!(restored.get_cells(), expected.get_cells());
+        assert_eq!(restored.generation(), expected.generation());
+    }
+
+    let full = Universe::new_with_size(300, 2).unwrap();
+    let restored = Universe::from_compressed_snapshot(&full.compress_snapshot()).unwrap();
+    assert_eq!(restored.get_cells(), full.get_cells());
+
+    let snapshot = &snapshots[0];
+    assert!(Universe::from_compressed_snapshot(&snapshot[..10]).is_err());
+    assert!(Universe::from_compressed_snapshot(&snapshot[..snapshot.len() - 1]).is_err());
+    let mut extra = snapshot.clone();
+    extra.push(1);
+    assert!(Universe::from_compressed_snapshot(&extra).is_err());
+}
+
+#[wasm_bindgen_test]
+pub fn test_tick_many_stats() {
+    let build = || {
+        UniverseBuilder::new()
+            .width(32)
+            .height(32)
+            .seed(7)
+            .build()
+            .unwrap()
+    };
+    let mut universe = build();
+    let stats = universe.tick_many_stats(20);
+    assert_eq!(stats.len(), 20);
+    assert_eq!(universe.generation(), 20);
+
+    let mut expected = build();
+    for &population in stats.iter() {
+        expected.tick();
+        assert_eq!(population, expected.population());
+    }
+    assert!(universe.tick_many_stats(0).is_empty());
+}
+
+#[wasm_bindgen_test]
+pub fn test_compute_neighborhood_variety() {
+    let empty = Universe::new_with_size(16, 16).unwrap();
+    assert_eq!(empty.compute_neighborhood_variety(), 1.0 / 512.0);
+
+    let mut block = Universe::new_with_size(16, 16).unwrap();
+    block.set_cells(&[(5, 5), (5, 6), (6, 5), (6, 6)]);
+    let still_life = block.compute_neighborhood_variety();
+    assert!(still_life > 1.0 / 512.0 && still_life < 0.05);
+
+    let random = UniverseBuilder::new()
+        .width(64)
+        .height(64)
+        .seed(3)
+        .build()
+        .unwrap();
+    assert!(random.compute_neighborhood_variety() > 0.95);
+}
+
+#[wasm_bindgen_test]
+pub fn test_seeded_fills_are_reproducible() {
+    let fill = |seed: u64| {
+        let mut universe = Universe::new_with_size(16, 16).unwrap();
+        universe.set_seed(seed);
+        universe.start();
+        let first = universe.get_cells().to_vec();
+        universe.start();
+        (first, universe.get_cells().to_vec())
+    };
+    let (first, second) = fill(11);
+    assert_eq!(fill(11), (first.clone(), second.clone()));
+    assert_ne!(first, second);
+    assert_ne!(fill(12).0, first);
+
+    let build = |seed: u64| {
+        let mut universe = UniverseBuilder::new()
+            .width(16)
+            .height(16)
+            .seed(seed)
+            .build()
+            .unwrap();
+        universe.start();
+        universe.get_cells().to_vec()
+    };
+    assert_eq!(build(5), build(5));
+}
+
+#[wasm_bindgen_test]
+pub fn test_to_rle() {
+    let mut universe = Universe::new_with_size(6, 6).unwrap();
+    universe.set_cells(&[(0, 1), (1, 2), (2, 0), (2, 1), (2, 2), (5, 5)]);
+    assert_eq!(
+        universe.to_rle(),
+        "x = 6, y = 6, rule = B3/S23\nbo$2bo$3o3$5bo!\n"
+    );
+
+    let mut wide = Universe::new_with_size(200, 1).unwrap();
+    let cells: Vec<(u32, u32)> = (0..200).step_by(2).map(|c| (0, c)).collect();
+    wide.set_cells(&cells);
+    let rle = wide.to_rle();
+    assert!(rle.lines().all(|line| line.len() <= 70));
+    let pattern = wasm_game_of_life::parse_rle(&rle).unwrap();
+    assert_eq!(pattern.cells.len(), 100);
+}
+
+#[wasm_bindgen_test]
+pub fn test_clamp_population() {
+    let clamped = |seed: u64| {
+        let mut universe = Universe::new_with_size(16, 16).unwrap();
+        universe.fill_from_mask(&[0.5; 256], 9).unwrap();
+        let before = universe.get_cells().to_vec();
+        universe.set_seed(seed);
+        universe.clamp_population(20);
+        assert_eq!(universe.population(), 20);
+        // 只会杀死原本存活的 Cell
+        for (after, before) in universe.get_cells().iter().zip(before.iter()) {
+            assert!(*after == Cell::Dead || *before == Cell::Alive);
+        }
+        universe.get_cells().to_vec()
+    };
+    assert_eq!(clamped(1), clamped(1));
+    assert_ne!(clamped(1), clamped(2));
+
+    let mut universe = Universe::new_with_size(8, 8).unwrap();
+    universe.set_cells(&[(1, 1), (2, 2)]);
+    universe.clamp_population(5);
+    assert_eq!(universe.population(), 2);
+    universe.clamp_population(0);
+    assert_eq!(universe.population(), 0);
+}
+
+#[wasm_bindgen_test]
+pub fn test_perturb() {
+    let perturbed = |seed: u64| {
+        let mut universe = Universe::new_with_size(16, 16).unwrap();
+        universe.fill_from_mask(&[0.5; 256], 3).unwrap();
+        let before = universe.get_cells().to_vec();
+        universe.perturb(10, seed);
+        let changed = universe
+            .get_cells()
+            .iter()
+            .zip(before.iter())
+            .filter(|(after, before)| after != before)
+            .count();
+        assert_eq!(changed, 10);
+        universe.get_cells().to_vec()
+    };
+    assert_eq!(perturbed(1), perturbed(1));
+    assert_ne!(perturbed(1), perturbed(2));
+
+    let mut universe = Universe::new_with_size(4, 4).unwrap();
+    universe.perturb(100, 0);
+    assert_eq!(universe.population(), 16);
+}
+
+#[wasm_bindgen_test]
+pub fn test_population_in() {
+    let mut universe = Universe::new_with_size(8, 8).unwrap();
+    universe
+        .stamp_rle("x = 3, y = 3\nbob$2bo$3o!", 2, 3)
+        .unwrap();
+    universe.set_cells(&[(7, 7)]);
+
+    let manual = |row: u32, column: u32, height: u32, width: u32| {
+        let mut count = 0;
+        for r in row..(row + height).min(8) {
+            for c in column..(column + width).min(8) {
+                count += universe[(r, c)].is_alive() as u32;
+            }
+        }
+        count
+    };
+    for &(row, column, height, width) in &[(0, 0, 8, 8), (2, 3, 3, 3), (3, 4, 2, 2), (4, 3, 1, 3)] {
+        assert_eq!(
+            universe.population_in(row, column, height, width),
+            manual(row, column, height, width)
+        );
+    }
+    assert_eq!(universe.population_in(2, 3, 3, 3), 5);
+    assert_eq!(universe.population_in(4, 3, 1, 3), 3);
+    // 超出边界的部分被裁掉
+    assert_eq!(universe.population_in(6, 6, 100, 100), 1);
+    assert_eq!(universe.population_in(20, 20, 3, 3), 0);
+    assert_eq!(universe.population_in(0, 0, u32::MAX, u32::MAX), 6);
+}
+
+#[wasm_bindgen_test]
+pub fn test_deterministic_entropy_mode() {
+    use wasm_game_of_life::{set_entropy_mode, EntropyMode};
+
+    set_entropy_mode(EntropyMode::Deterministic);
+    let mut first = Universe::new_with_size(8, 8).unwrap();
+    first.start();
+    let mut second = Universe::new_with_size(8, 8).unwrap();
+    second.start();
+    // 计数器让相继创建的 Universe 不同，但序列本身在所有平台上都固定
+    assert_eq!(first.encode_state(), "8x8:lVy3fNmX1lY=");
+    assert_ne!(first.get_cells(), second.get_cells());
+
+    set_entropy_mode(EntropyMode::Deterministic);
+    let mut again = Universe::new_with_size(8, 8).unwrap();
+    again.start();
+    assert_eq!(again.get_cells(), first.get_cells());
+    // 不需要任何外部熵就可以创建随机的 Universe
+    assert!(Universe::new().population() > 0);
+    set_entropy_mode(EntropyMode::Environment);
+}
+
+#[wasm_bindgen_test]
+pub fn test_rows() {
+    let mut universe = Universe::new_with_size(5, 3).unwrap();
+    universe.set_cells(&[(0, 1), (1, 4), (2, 0), (2, 2)]);
+    let rows: Vec<Vec<Cell>> = universe.rows().collect();
+    assert_eq!(rows.len(), 3);
+    assert!(rows.iter().all(|row| row.len() == 5));
+    assert_eq!(rows[1][4], Cell::Alive);
+    assert_eq!(rows.concat(), universe.get_cells());
+
+    // 平移之后按逻辑坐标遍历，而不是按存储顺序
+    universe.set_toroidal_offset(1, 2);
+    let rows: Vec<Vec<Cell>> = universe.rows().collect();
+    for (row, cells) in rows.iter().enumerate() {
+        for (column, &cell) in cells.iter().enumerate() {
+            assert_eq!(Some(cell), universe.get(row as u32, column as u32));
+        }
+    }
+    assert_ne!(rows.concat(), universe.get_cells());
+}
+
+#[wasm_bindgen_test]
+pub fn test_mirror_to_quadrants() {
+    let mut universe = Universe::new_with_size(6, 4).unwrap();
+    universe.set_cells(&[(0, 0), (1, 1), (1, 2), (3, 5), (2, 3)]);
+    universe.mirror_to_quadrants();
+    assert_eq!(
+        universe.render_region(0, 0, 4, 6),
+        "◼◻◻◻◻◼\n◻◼◼◼◼◻\n◻◼◼◼◼◻\n◼◻◻◻◻◼\n"
+    );
+
+    // 奇数尺寸时中间的行和列保持不变
+    let mut universe = Universe::new_with_size(5, 3).unwrap();
+    universe.set_cells(&[(0, 1), (1, 2), (2, 4)]);
+    universe.mirror_to_quadrants();
+    assert_eq!(universe.render_region(0, 0, 3, 5), "◻◼◻◼◻\n◻◻◼◻◻\n◻◼◻◼◻\n");
+}
+
+#[cfg(feature = "cell-ages")]
+#[wasm_bindgen_test]
+pub fn test_age_distribution() {
+    let mut universe = Universe::new_with_size(8, 8).unwrap();
+    assert!(universe.age_distribution().is_empty());
+
+    // 方块一直存活，闪烁器两端的 Cell 每代都重新出生，中间的一直存活
+    universe.set_cells(&[(0, 0), (0, 1), (1, 0), (1, 1), (5, 3), (5, 4), (5, 5)]);
+    assert_eq!(universe.age_distribution(), vec![7]);
+    universe.tick();
+    assert_eq!(universe.age_distribution(), vec![2, 5]);
+    universe.tick();
+    universe.tick();
+    assert_eq!(universe.age_distribution(), vec![2, 0, 0, 5]);
+
+    universe.reset();
+    assert!(universe.age_distribution().is_empty());
+}
+
+#[cfg(feature = "cell-ages")]
+#[wasm_bindgen_test]
+pub fn test_age_cap() {
+    let mut universe = Universe::new_with_size(8, 8).unwrap();
+    universe.set_cells(&[(2, 2), (2, 3), (3, 2), (3, 3)]);
+    for _ in 0..3 {
+        universe.tick();
+    }
+    assert_eq!(universe.age_distribution(), vec![0, 0, 0, 4]);
+
+    // 设置上限时已经超过的年龄被截断，之后一直停在上限
+    universe.set_age_cap(2);
+    assert_eq!(universe.age_cap(), 2);
+    assert_eq!(universe.age_distribution(), vec![0, 0, 4]);
+    for _ in 0..50 {
+        universe.tick();
+        assert!(universe.ages().iter().all(|&age| age <= 2));
+    }
+    let ages = universe.ages();
+    assert_eq!(ages.len(), 64);
+    assert_eq!(ages.iter().filter(|&&age| age == 2).count(), 4);
+    assert_eq!(ages.iter().filter(|&&age| age == 0).count(), 60);
+}
+
+#[wasm_bindgen_test]
+pub fn test_from_fn() {
+    let stripes = Universe::from_fn(6, 3, |_, column| Cell::from(column % 3 == 1)).unwrap();
+    assert_eq!(stripes.to_string(), "◻◼◻◻◼◻\n◻◼◻◻◼◻\n◻◼◻◻◼◻\n");
+
+    let mut calls = vec![];
+    Universe::from_fn(3, 2, |row, column| {
+        calls.push((row, column));
+        Cell::Dead
+    })
+    .unwrap();
+    assert_eq!(calls, vec![(0, 0), (0, 1), (0, 2), (1, 0), (1, 1), (1, 2)]);
+
+    assert!(Universe::from_fn(0, 3, |_, _| Cell::Alive).is_err());
+}
+
+#[wasm_bindgen_test]
+pub fn test_from_js_fn() {
+    let checkerboard =
+        js_sys::Function::new_with_args("row, column", "return (row + column) % 2 === 0");
+    let universe = Universe::from_js_fn(4, 2, &checkerboard).unwrap();
+    assert_eq!(universe.to_string(), "◼◻◼◻\n◻◼◻◼\n");
+
+    // JS 抛出的异常变成 Err，而不是穿过 FFI 边界
+    let throwing = js_sys::Function::new_with_args(
+        "row, column",
+        "if (row > 0) throw new Error('boom'); return true",
+    );
+    assert!(Universe::from_js_fn(4, 2, &throwing).is_err());
+    assert!(Universe::from_js_fn(0, 2, &checkerboard).is_err());
+}
+
+#[wasm_bindgen_test]
+pub fn test_activity_centroid() {
+    let mut universe = Universe::new_with_size(10, 10).unwrap();
+    assert_eq!(universe.activity_centroid(), None);
+
+    // 以 (4, 5) 为中心的十字是对称的，质心就是几何中心
+    universe.set_cells(&[(3, 5), (4, 4), (4, 5), (4, 6), (5, 5)]);
+    assert_eq!(universe.activity_centroid(), Some((4.0, 5.0)));
+
+    // 2x2 方块的中心落在 Cell 之间
+    let mut block = Universe::new_with_size(10, 10).unwrap();
+    block.set_cells(&[(6, 1), (6, 2), (7, 1), (7, 2)]);
+    assert_eq!(block.activity_centroid(), Some((6.5, 1.5)));
+}
+
+#[wasm_bindgen_test]
+pub fn test_rule_schedule() {
+    let conway: wasm_game_of_life::Rule = "B3/S23".parse().unwrap();
+    let highlife: wasm_game_of_life::Rule = "B36/S23".parse().unwrap();
+    // (2, 2) 周围有 6 个存活邻居：只有 B36 会让它出生
+    let ring = [(1, 1), (1, 2), (1, 3), (3, 1), (3, 2), (3, 3)];
+
+    let mut even = Universe::new_with_size(7, 7).unwrap();
+    even.set_rule_schedule(vec![conway, highlife]);
+    even.set_cells(&ring);
+    even.tick();
+    assert_eq!(even[(2, 2)], Cell::Dead);
+
+    let mut odd = Universe::new_with_size(7, 7).unwrap();
+    odd.set_rule_schedule(vec![conway, highlife]);
+    odd.tick();
+    odd.set_cells(&ring);
+    assert_eq!(odd.current_rule(), highlife);
+    odd.tick();
+    assert_eq!(odd[(2, 2)], Cell::Alive);
+
+    // 每一代都与单独使用对应规则的结果一致
+    let mut universe = UniverseBuilder::new()
+        .width(16)
+        .height(16)
+        .seed(7)
+        .build()
+        .unwrap();
+    universe.set_rule_schedule(vec![conway, highlife]);
+    for generation in 0..6 {
+        let mut reference = universe.duplicate();
+        reference.set_rule_schedule(vec![]);
+        reference
+            .set_rule(if generation % 2 == 0 {
+                "B3/S23"
+            } else {
+                "B36/S23"
+            })
+            .unwrap();
+        reference.tick();
+        universe.tick();
+        assert_eq!(universe.get_cells(), reference.get_cells());
+    }
+
+    universe.set_rule_schedule(vec![]);
+    assert_eq!(universe.current_rule(), conway);
+}
+
+#[wasm_bindgen_test]
+pub fn test_rule_table() {
+    // B/S 规则导出的查找表与原规则演化结果相同
+    let mut universe = UniverseBuilder::new()
+        .width(12)
+        .height(12)
+        .seed(3)
+        .build()
+        .unwrap();
+    let mut reference = universe.duplicate();
+    let conway = universe.get_rule_table();
+    assert_eq!(conway.len(), 512);
+    assert_eq!(conway[0b0000_0111], 1);
+    assert_eq!(conway[1 << 8 | 0b1000_0001], 1);
+    assert_eq!(conway[1 << 8 | 0b1000_0000], 0);
+    universe.set_rule_table(&conway).unwrap();
+    for _ in 0..5 {
+        universe.tick();
+        reference.tick();
+    }
+    assert_eq!(universe.get_cells(), reference.get_cells());
+
+    // 不依赖邻居数量的规则：每个 Cell 复制西边邻居（第 3 位）的状态，整体向东移动
+    let shift_east: Vec<u8> = (0..512).map(|index| (index >> 3 & 1) as u8).collect();
+    let mut universe = Universe::new_with_size(6, 4).unwrap();
+    universe.set_cells(&[(0, 0), (1, 2), (3, 5)]);
+    universe.set_rule_table(&shift_east).unwrap();
+    assert_eq!(universe.get_rule_table(), shift_east);
+    universe.tick();
+    let mut expected = Universe::new_with_size(6, 4).unwrap();
+    expected.set_cells(&[(0, 1), (1, 3), (3, 0)]);
+    assert_eq!(universe.get_cells(), expected.get_cells());
+
+    assert!(universe.set_rule_table(&shift_east[1..]).is_err());
+    let mut invalid = shift_east.clone();
+    invalid[7] = 2;
+    assert!(universe.set_rule_table(&invalid).is_err());
+    assert_eq!(universe.get_rule_table(), shift_east);
+
+    universe.set_rule("B3/S23").unwrap();
+    assert_eq!(universe.get_rule_table(), conway);
+}
+
+#[wasm_bindgen_test]
+pub fn test_set_cells_state() {
+    let mut universe = Universe::new_with_size(5, 5).unwrap();
+    universe.set_cells(&[(0, 0), (1, 1), (2, 2)]);
+    universe
+        .set_cells_state(&[(0, 0, Cell::Dead), (1, 1, Cell::Alive), (4, 4, Cell::Alive)])
+        .unwrap();
+    assert_eq!(universe.render(), "◻◻◻◻◻\n◻◼◻◻◻\n◻◻◼◻◻\n◻◻◻◻◻\n◻◻◻◻◼\n");
+
+    // 越界时报告出错的下标，并且不修改任何 Cell
+    let err = universe
+        .set_cells_state(&[(3, 3, Cell::Alive), (0, 5, Cell::Alive)])
+        .unwrap_err();
+    assert!(err.to_string().contains("entry 1"), "{}", err);
+    assert_eq!(universe.population(), 3);
+
+    universe
+        .set_cells_state_js(&[2, 3], &[2, 3], &[0, 1])
+        .unwrap();
+    assert_eq!(universe[(2, 2)], Cell::Dead);
+    assert_eq!(universe[(3, 3)], Cell::Alive);
+    assert!(universe.set_cells_state_js(&[0], &[0, 1], &[1]).is_err());
+    assert!(universe.set_cells_state_js(&[0], &[0], &[2]).is_err());
+    assert_eq!(universe[(0, 0)], Cell::Dead);
+}
+
+#[wasm_bindgen_test]
+pub fn test_rule_string_and_masks() {
+    let mut universe = Universe::new_with_size(4, 4).unwrap();
+    assert_eq!(universe.rule_string(), "B3/S23");
+    assert_eq!(universe.rule_birth_mask(), [0b1000]);
+    assert_eq!(universe.rule_survive_mask(), [0b1100]);
+
+    for (input, canonical) in [
+        ("b63/s32", "B36/S23"),
+        ("B/S012345678", "B/S012345678"),
+        ("B2/S", "B2/S"),
+    ] {
+        universe.set_rule(input).unwrap();
+        assert_eq!(universe.rule_string(), canonical);
+        let parsed: wasm_game_of_life::Rule = universe.rule_string().parse().unwrap();
+        assert_eq!(parsed.to_string(), canonical);
+        assert_eq!(parsed.birth_mask(), universe.rule_birth_mask());
+        assert_eq!(parsed.survival_mask(), universe.rule_survive_mask());
+    }
+    assert_eq!(universe.rule_birth_mask(), [0b100]);
+    assert!(universe.rule_survive_mask().is_empty());
+    universe.set_rule("B/S012345678").unwrap();
+    assert_eq!(universe.rule_survive_mask(), [0xff, 0b1]);
+}
+
+#[wasm_bindgen_test]
+pub fn test_simulation_control() {
+    use wasm_game_of_life::{RunState, SimulationControl};
+
+    let mut universe = Universe::new_with_size(6, 6).unwrap();
+    universe.set_cells(&[(2, 1), (2, 2), (2, 3)]);
+    let mut control = SimulationControl::new();
+    assert_eq!(control.state(), "stopped");
+    assert!(!control.tick(&mut universe));
+
+    // 停止时暂停没有效果
+    let epoch = control.control_epoch();
+    control.pause();
+    assert_eq!(control.run_state(), RunState::Stopped);
+    assert_eq!(control.control_epoch(), epoch);
+
+    control.play();
+    assert!(control.control_epoch() > epoch);
+    assert!(control.tick(&mut universe));
+    assert!(control.tick(&mut universe));
+    // 运行时单步被忽略
+    assert!(!control.step_once());
+    assert_eq!(control.state(), "running");
+
+    control.pause();
+    assert!(!control.tick(&mut universe));
+    assert_eq!(universe.generation(), 2);
+
+    assert!(control.step_once());
+    assert_eq!(control.state(), "single-stepping");
+    assert!(control.tick(&mut universe));
+    assert_eq!(control.state(), "paused");
+    assert!(!control.tick(&mut universe));
+    assert_eq!(universe.generation(), 3);
+
+    // stop 只把代数归零，不改变 Cell
+    let cells = universe.get_cells().to_vec();
+    let epoch = control.control_epoch();
+    control.stop(&mut universe);
+    assert_eq!(control.state(), "stopped");
+    assert_eq!(universe.generation(), 0);
+    assert_eq!(universe.get_cells(), &cells[..]);
+    assert!(control.control_epoch() > epoch);
+
+    assert!(control.step_once());
+    assert!(control.tick(&mut universe));
+    assert_eq!(universe.generation(), 1);
+}
+
+#[wasm_bindgen_test]
+pub fn test_detect_still_lifes() {
+    let mut universe = Universe::new_with_size(20, 20).unwrap();
+    // 方块、蜂巢是静物；闪烁器和滑翔机都会变化
+    universe.set_cells(&[(1, 1), (1, 2), (2, 1), (2, 2)]);
+    universe.set_cells(&[(10, 11), (10, 12), (11, 10), (11, 13), (12, 11), (12, 12)]);
+    universe.set_cells(&[(5, 14), (5, 15), (5, 16)]);
+    universe.stamp_rle("bo$2bo$3o!", 15, 2).unwrap();
+
+    assert_eq!(
+        universe.detect_still_lifes(),
+        vec![(1, 1, 2, 2), (10, 10, 3, 4)]
+    );
+    assert_eq!(
+        universe.detect_still_lifes_js(),
+        vec![1, 1, 2, 2, 10, 10, 3, 4]
+    );
+    // 不修改原来的 Universe
+    assert_eq!(universe.generation(), 0);
+
+    assert!(Universe::new_with_size(8, 8)
+        .unwrap()
+        .detect_still_lifes()
+        .is_empty());
+}
+
+#[wasm_bindgen_test]
+pub fn test_record_and_replay() {
+    use wasm_game_of_life::{Recorder, Replay};
+
+    let mut recorder = Recorder::new(24, 24, 7).unwrap();
+    let mut checkpoints = vec![recorder.universe().get_cells().to_vec()];
+    let mut checkpoint = |recorder: &Recorder| {
+        checkpoints.push(recorder.universe().get_cells().to_vec());
+    };
+    recorder.start();
+    checkpoint(&recorder);
+    recorder.tick(5);
+    checkpoint(&recorder);
+    recorder.toggle_cell(3, 4).unwrap();
+    checkpoint(&recorder);
+    recorder.set_rule("B36/S23").unwrap();
+    checkpoint(&recorder);
+    recorder
+        .stamp_rle("#N Glider\nx = 3, y = 3\nbob$2bo$3o!", 10, 10)
+        .unwrap();
+    checkpoint(&recorder);
+    recorder
+        .set_cells_state(&[(0, 0, Cell::Alive), (0, 1, Cell::Dead)])
+        .unwrap();
+    checkpoint(&recorder);
+    recorder.tick(7);
+    checkpoint(&recorder);
+    recorder.perturb(10, 5);
+    checkpoint(&recorder);
+    recorder.clamp_population(100);
+    checkpoint(&recorder);
+    recorder.tick(3);
+    checkpoint(&recorder);
+    // 失败的操作不会被记录
+    assert!(recorder.toggle_cell(24, 0).is_err());
+    assert!(recorder.set_rule("B9/S").is_err());
+    assert_eq!(recorder.op_count(), 10);
+
+    let log = recorder.export();
+    assert!(log.starts_with("gol-log 1 24 24 7\n"));
+    let mut replay = Replay::from_log(&log).unwrap();
+    assert_eq!(replay.op_count(), 10);
+    for &op in &[0, 2, 5, 7, 9] {
+        replay.advance_to_op(op).unwrap();
+        assert_eq!(replay.universe().get_cells(), &checkpoints[op as usize][..]);
+    }
+
+    // 停在第二个 tick 操作的中间：第 5 代之后再 tick 3 次
+    let mut replay = Replay::from_log(&log).unwrap();
+    replay.advance_to_generation(8).unwrap();
+    assert_eq!(replay.universe().generation(), 8);
+    assert_eq!(replay.position(), 6);
+    let mut reference = Replay::from_log(&log).unwrap();
+    reference.advance_to_op(6).unwrap();
+    let mut expected = reference.universe().duplicate();
+    for _ in 0..3 {
+        expected.tick();
+    }
+    assert_eq!(replay.universe().get_cells(), expected.get_cells());
+
+    replay.finish().unwrap();
+    assert_eq!(replay.position(), 10);
+    assert_eq!(
+        replay.universe().get_cells(),
+        recorder.universe().get_cells()
+    );
+    assert_eq!(
+        replay.universe().generation(),
+        recorder.universe().generation()
+    );
+
+    assert!(Replay::from_log("not a log").is_err());
+    assert!(Replay::from_log("gol-log 1 4 4 0\n0 explode 1").is_err());
+}
+
+#[wasm_bindgen_test]
+pub fn test_explain_cell() {
+    let mut universe = Universe::new_with_size(6, 6).unwrap();
+    universe.set_cells(&[(1, 2), (2, 2), (3, 2)]);
+
+    // (2, 1) 是死亡的，恰好有 3 个存活邻居，下一代出生
+    let born = universe.explain_cell(2, 1).unwrap();
+    assert_eq!(born.state(), Cell::Dead);
+    assert_eq!(born.live_neighbors(), 3);
+    assert_eq!(born.next_state(), Cell::Alive);
+    assert!(!born.is_wall());
+
+    let dies = universe.explain_cell(1, 2).unwrap();
+    assert_eq!(
+        (dies.state(), dies.live_neighbors(), dies.next_state()),
+        (Cell::Alive, 1, Cell::Dead)
+    );
+
+    // 与实际 tick 的结果一致
+    let mut next = universe.duplicate();
+    next.tick();
+    for row in 0..6 {
+        for column in 0..6 {
+            assert_eq!(
+                universe.explain_cell(row, column).unwrap().next_state(),
+                next[(row, column)]
+            );
+        }
+    }
+    assert!(universe.explain_cell(6, 0).is_err());
+}
+
+#[wasm_bindgen_test]
+pub fn test_apply_rule_at() {
+    let mut universe = Universe::new_with_size(6, 6).unwrap();
+    universe.set_cells(&[(1, 2), (2, 2), (3, 2)]);
+
+    universe.apply_rule_at(2, 1).unwrap();
+    assert_eq!(universe[(2, 1)], Cell::Alive);
+    // 只有这一个 Cell 改变
+    assert_eq!(universe.population(), 4);
+    assert_eq!(universe[(1, 2)], Cell::Alive);
+    assert_eq!(universe.generation(), 0);
+
+    // 之后的 Cell 使用更新后的邻居数：(1, 2) 现在有 2 个邻居，继续存活
+    universe.apply_rule_at(1, 2).unwrap();
+    assert_eq!(universe[(1, 2)], Cell::Alive);
+    universe.apply_rule_at(5, 5).unwrap();
+    assert_eq!(universe[(5, 5)], Cell::Dead);
+
+    assert!(universe.apply_rule_at(0, 6).is_err());
+}
+
+#[wasm_bindgen_test]
+pub fn test_cells_buffer_is_stable() {
+    let mut universe = Universe::new_with_size(12, 10).unwrap();
+    assert_eq!(universe.cells_byte_len(), 12 * 10);
+
+    let ptr = universe.cells();
+    universe.start();
+    universe.tick();
+    universe.set_snapshot_capacity(4);
+    universe.tick();
+    universe.set_cells(&[(1, 1)]);
+    assert_eq!(universe.cells(), ptr);
+    let mut square = Universe::new_with_size(8, 8).unwrap();
+    let ptr = square.cells();
+    square.step_symmetric().unwrap();
+    assert_eq!(square.cells(), ptr);
+
+    // 只有改变尺寸时缓冲区才会重新分配
+    universe.resize(20, 20).unwrap();
+    assert_eq!(universe.cells_byte_len(), 400);
+}
+
+#[wasm_bindgen_test]
+pub fn test_universe_group() {
+    let mut group = UniverseGroup::new(3, 32, 32, 42).unwrap();
+    assert_eq!(group.len(), 3);
+    group.set_rule(2, "B36/S23").unwrap();
+
+    // 相同规则的成员永远不会分歧
+    for _ in 0..20 {
+        group.tick_all();
+        assert_eq!(group.divergence(0, 1).unwrap(), 0);
+    }
+    assert_eq!(group.generation(), 20);
+
+    // 随机初始状态中有 6 个邻居的死 Cell，第一代就会分歧
+    assert_eq!(group.first_divergence_generation(), Some(1));
+    let cells = group.first_divergence_cells();
+    assert!(!cells.is_empty());
+    assert_eq!(cells.len() % 2, 0);
+    assert_eq!(cells[..4], [0, 10, 0, 23]);
+    assert_eq!(group.divergence(2, 0), group.divergence(0, 2));
+
+    assert!(group.set_rule(3, "B3/S23").is_err());
+    assert!(group.divergence(0, 3).is_err());
+    assert!(UniverseGroup::new(0, 8, 8, 1).is_err());
+
+    // 改变尺寸时所有成员一起改变
+    group.resize(16, 8).unwrap();
+    assert!(group.first_divergence_generation().is_none());
+    for index in 0..group.len() {
+        let member = group.member(index).unwrap();
+        assert_eq!((member.width(), member.height()), (16, 8));
+    }
+    assert!(group.resize(0, 8).is_err());
+    assert_eq!(group.member(1).unwrap().width(), 16);
+}
+
+#[wasm_bindgen_test]
+pub fn test_parse_macrocell() {
+    let glider = wasm_game_of_life::parse_rle("bo$2bo$3o!").unwrap();
+    let single =
+        wasm_game_of_life::parse_macrocell("[M2] (golly 4.2)\n#R B3/S23\n.*$..*$***$\n").unwrap();
+    assert_eq!(single, glider);
+
+    // 两个滑翔机分别在 16x16 节点的 nw 和 se 象限，结果裁剪到外接矩形
+    let two = wasm_game_of_life::parse_macrocell("[M2]\n.*$..*$***$\n4 1 0 0 1\n").unwrap();
+    assert_eq!((two.width, two.height), (11, 11));
+    assert_eq!(two.cells.len(), 10);
+    assert!(two.cells.contains(&(8, 9)));
+    assert!(two.cells.contains(&(10, 10)));
+
+    // 2^40 x 2^40 的宇宙中只有一个滑翔机，只展开它所在的部分
+    let mut deep = "[M2]\n.*$..*$***$\n".to_string();
+    for level in 4..=40 {
+        deep.push_str(&format!("{} 0 0 0 {}\n", level, level - 3));
+    }
+    assert_eq!(wasm_game_of_life::parse_macrocell(&deep).unwrap(), glider);
+
+    // 两个相距 2^40 的滑翔机超过了 MAX_CELLS
+    let huge = format!("{}41 38 0 0 38\n", deep);
+    assert!(matches!(
+        wasm_game_of_life::parse_macrocell(&huge),
+        Err(UniverseError::ParseError { line: 40, .. })
+    ));
+
+    assert!(wasm_game_of_life::parse_macrocell("[M2]\n")
+        .unwrap()
+        .cells
+        .is_empty());
+    assert!(wasm_game_of_life::parse_macrocell("x = 3, y = 3\nbo$2bo$3o!").is_err());
+    let errors = [
+        "[M2]\n.*$..*$***$\n5 1 0 0 0\n",
+        "[M2]\n.*$..*$***$\n4 2 0 0 0\n",
+        "[M2]\n.........*$\n",
+        "[M2]\n$$$$$$$$*$\n",
+        "[M2]\n1 0 1 0 1\n",
+        "[M2]\n4 0 0 0\n",
+    ];
+    for mc in errors.iter() {
+        assert!(wasm_game_of_life::parse_macrocell(mc).is_err(), "{}", mc);
+    }
+    assert!(matches!(
+        wasm_game_of_life::parse_macrocell("[M2]\n.*$\n#C comment\n4 1 0 0 x\n"),
+        Err(UniverseError::ParseError { line: 4, .. })
+    ));
+
+    let mut from_rle = Universe::new_with_size(20, 20).unwrap();
+    from_rle.stamp_rle("bo$2bo$3o!", 18, 18).unwrap();
+    let mut from_mc = Universe::new_with_size(20, 20).unwrap();
+    from_mc
+        .stamp_macrocell("[M2]\n.*$..*$***$\n", 18, 18)
+        .unwrap();
+    assert_eq!(from_mc.get_cells(), from_rle.get_cells());
+}
+
+#[wasm_bindgen_test]
+pub fn test_census() {
+    let mut universe = Universe::new_with_size(64, 64).unwrap();
+    let objects = [
+        ("2o$2o!", 2, 2),
+        ("2o$2o!", 2, 12),
+        ("2o$2o!", 2, 22),
+        ("3o!", 12, 2),
+        ("o$o$o!", 12, 12),
+        ("b2o$o2bo$b2o!", 12, 22),
+        ("b2o$o2bo$bobo$2bo!", 22, 2),
+        ("bo$obo$2o!", 22, 12),
+        ("3o$o$bo!", 22, 22),
+        ("b2o$o2bo$o2bo$b2o!", 32, 2),
+        // R-pentomino 不是已知物体
+        ("b2o$2o$bo!", 32, 22),
+        // 跨越环面接缝的滑翔机
+        ("bo$2bo$3o!", 62, 40),
+    ];
+    for &(rle, row, column) in objects.iter() {
+        universe.stamp_rle(rle, row, column).unwrap();
+    }
+
+    let census = universe.census();
+    let counts: Vec<(&str, u32)> = census
+        .counts
+        .iter()
+        .map(|(name, &count)| (name.as_str(), count))
+        .collect();
+    assert_eq!(
+        counts,
+        [
+            ("beehive", 1),
+            ("blinker", 2),
+            ("block", 3),
+            ("boat", 1),
+            ("glider", 2),
+            ("loaf", 1),
+            ("pond", 1),
+        ]
+    );
+    assert_eq!(census.unidentified, [(32, 22, 3, 3)]);
+
+    assert_eq!(
+        Universe::new_with_size(8, 8).unwrap().census(),
+        Default::default()
+    );
+}
+
+#[wasm_bindgen_test]
+pub fn test_census_names_objects_only_under_conway() {
+    use wasm_game_of_life::Rule;
+
+    let mut universe = Universe::new_with_size(16, 16).unwrap();
+    universe.stamp_rle("2o$2o!", 2, 2).unwrap();
+    universe.stamp_rle("3o!", 10, 10).unwrap();
+    assert_eq!(universe.census().counts.len(), 2);
+
+    // B36/S23 中方块和闪烁器的行为与 B3/S23 相同，但名称只按 B3/S23 定义
+    for rule in ["B36/S23", "B3/S012345678"] {
+        universe.set_rule(rule).unwrap();
+        let census = universe.census();
+        assert!(census.counts.is_empty());
+        assert_eq!(census.unidentified, [(2, 2, 2, 2), (10, 10, 1, 3)]);
+    }
+
+    universe.set_rule("B3/S23").unwrap();
+    universe.set_life_without_death(true);
+    assert!(universe.census().counts.is_empty());
+    universe.set_life_without_death(false);
+    universe.set_rule_schedule(vec![Rule::CONWAY, Rule::CONWAY]);
+    assert_eq!(universe.census().counts.len(), 2);
+}
+
+#[wasm_bindgen_test]
+pub fn test_census_recognizes_pulsar_in_any_phase() {
+    let pulsar = "2b3o3b3o2$o4bobo4bo$o4bobo4bo$o4bobo4bo$2b3o3b3o2$2b3o3b3o$\
+                  o4bobo4bo$o4bobo4bo$o4bobo4bo2$2b3o3b3o!";
+    let mut universe = Universe::new_with_size(32, 32).unwrap();
+    universe.stamp_rle(pulsar, 9, 9).unwrap();
+    for _ in 0..3 {
+        let census = universe.census();
+        assert_eq!(census.counts.get("pulsar"), Some(&1));
+        assert_eq!(census.counts.len(), 1);
+        assert!(census.unidentified.is_empty());
+        universe.tick();
+    }
+}
+
+#[wasm_bindgen_test]
+pub fn test_compute_birth_locations_if_tick() {
+    let mut universe = input_spaceship();
+    let before = universe.get_cells().to_vec();
+    let births = universe.compute_birth_locations_if_tick();
+    assert_eq!(births, [2, 1, 4, 2]);
+    assert_eq!(universe.get_cells(), &before[..]);
+    assert_eq!(universe.generation(), 0);
+
+    // 与实际 tick 的结果一致
+    let alive_before: Vec<bool> = (0..6)
+        .flat_map(|r| (0..6).map(move |c| (r, c)))
+        .map(|(r, c)| universe[(r, c)].is_alive())
+        .collect();
+    universe.tick();
+    let mut born = vec![];
+    for r in 0..6 {
+        for c in 0..6 {
+            if !alive_before[(r * 6 + c) as usize] && universe[(r, c)].is_alive() {
+                born.extend([r, c]);
+            }
+        }
+    }
+    assert_eq!(born, births);
+
+    universe.set_rule("B/S23").unwrap();
+    assert!(universe.compute_birth_locations_if_tick().is_empty());
+}
+
+#[wasm_bindgen_test]
+pub fn test_sprinkle() {
+    let mut universe = input_spaceship();
+    let before = universe.get_cells().to_vec();
+    universe.sprinkle(10, 7);
+    assert_eq!(universe.population(), 5 + 10);
+    // 原来存活的 Cell 仍然存活
+    for (index, &cell) in before.iter().enumerate() {
+        if cell == Cell::Alive {
+            assert_eq!(universe.get_cells()[index], Cell::Alive);
+        }
+    }
+
+    // 死亡的 Cell 不足时全部变为存活，满了之后不再变化
+    universe.sprinkle(100, 7);
+    assert_eq!(universe.population(), 36);
+    universe.sprinkle(1, 7);
+    assert_eq!(universe.population(), 36);
+}
+
+#[wasm_bindgen_test]
+pub fn test_detect_gliders() {
+    let mut universe = Universe::new_with_size(40, 40).unwrap();
+    universe.stamp_rle("bo$2bo$3o!", 2, 2).unwrap();
+    universe.stamp_rle("3o$o$bo!", 2, 20).unwrap();
+    universe.stamp_rle("3o$2bo$bo!", 20, 5).unwrap();
+    // 紧挨着 block 的滑翔机形状不算
+    universe.stamp_rle("bo$2bo$3o$4b2o$4b2o!", 25, 25).unwrap();
+
+    let gliders = universe.detect_gliders();
+    let found: Vec<(u32, u32, Direction)> = gliders
+        .iter()
+        .map(|glider| (glider.row, glider.column, glider.heading))
+        .collect();
+    assert_eq!(
+        found,
+        [
+            (2, 2, Direction::SouthEast),
+            (2, 20, Direction::NorthWest),
+            (20, 5, Direction::NorthEast),
+        ]
+    );
+    assert_eq!(gliders[0].phase, 0);
+
+    // 一个周期之后都沿各自的方向移动了一格，相位相同
+    universe.tick();
+    assert_eq!(universe.detect_gliders()[0].phase, 1);
+    for _ in 1..4 {
+        universe.tick();
+    }
+    let moved: Vec<(u32, u32, u8)> = universe
+        .detect_gliders()
+        .iter()
+        .map(|glider| (glider.row, glider.column, glider.phase))
+        .collect();
+    assert_eq!(
+        moved,
+        [
+            (1, 19, gliders[1].phase),
+            (3, 3, gliders[0].phase),
+            (19, 6, gliders[2].phase),
+        ]
+    );
+
+    let mut blinker = Universe::new_with_size(8, 8).unwrap();
+    blinker.stamp_rle("3o!", 3, 2).unwrap();
+    assert!(blinker.detect_gliders().is_empty());
+}
+
+#[wasm_bindgen_test]
+pub fn test_compute_death_locations_if_tick() {
+    let mut universe = input_spaceship();
+    let before = universe.get_cells().to_vec();
+    let deaths = universe.compute_death_locations_if_tick();
+    assert_eq!(deaths, [1, 2, 3, 1]);
+    assert_eq!(universe.get_cells(), &before[..]);
+
+    // 出生和死亡合起来就是 tick 前后的全部变化
+    let births = universe.compute_birth_locations_if_tick();
+    universe.tick();
+    let mut changed: Vec<(u32, u32)> = births
+        .chunks(2)
+        .chain(deaths.chunks(2))
+        .map(|pair| (pair[0], pair[1]))
+        .collect();
+    changed.sort_unstable();
+    let mut actual = vec![];
+    for r in 0..6 {
+        for c in 0..6 {
+            if before[(r * 6 + c) as usize] != universe[(r, c)] {
+                actual.push((r, c));
+            }
+        }
+    }
+    assert_eq!(changed, actual);
+
+    // 所有 Cell 都能存活的规则下没有死亡
+    universe.set_rule("B3/S012345678").unwrap();
+    assert!(universe.compute_death_locations_if_tick().is_empty());
+}
+
+#[wasm_bindgen_test]
+pub fn test_to_rle_named() {
+    let mut universe = Universe::new_with_size(3, 3).unwrap();
+    universe.set_cells(&[(0, 1), (1, 2), (2, 0), (2, 1), (2, 2)]);
+    universe.set_rule("B36/S23").unwrap();
+    let rle = universe.to_rle_named("Glider", "The smallest spaceship\nFound in 1969");
+
+    let mut lines = rle.lines();
+    assert_eq!(lines.next(), Some("#N Glider"));
+    assert_eq!(lines.next(), Some("#C The smallest spaceship"));
+    assert_eq!(lines.next(), Some("#C Found in 1969"));
+    let header: Vec<(&str, &str)> = lines
+        .next()
+        .unwrap()
+        .split(',')
+        .map(|item| {
+            let (key, value) = item.split_once('=').unwrap();
+            (key.trim(), value.trim())
+        })
+        .collect();
+    assert_eq!(header, [("x", "3"), ("y", "3"), ("rule", "B36/S23")]);
+    assert_eq!(lines.next(), Some("bo$2bo$3o!"));
+
+    let pattern = wasm_game_of_life::parse_rle(&rle).unwrap();
+    assert_eq!(pattern.cells.len(), 5);
+
+    // 名称中的换行不会产生新行，空名称、空注释以及注释中的空行都被省略
+    assert!(universe
+        .to_rle_named("a\nb", "")
+        .starts_with("#N a b\nx = 3"));
+    assert!(universe
+        .to_rle_named("", "first\n\n  \nsecond\n")
+        .starts_with("#C first\n#C second\nx = 3"));
+    assert_eq!(universe.to_rle_named("", ""), universe.to_rle());
+}
+
+#[wasm_bindgen_test]
+pub fn test_life_without_death() {
+    let mut universe = Universe::new_with_size(16, 16).unwrap();
+    universe.stamp_rle("b2o$2o$bo!", 6, 6).unwrap();
+    universe.set_life_without_death(true);
+    assert!(universe.life_without_death());
+    assert_eq!(universe.rule(), "B3/S012345678");
+
+    // 存活的 Cell 永远不会死亡，存活数量只增不减
+    let mut population = universe.population();
+    for _ in 0..40 {
+        let before = universe.get_cells().to_vec();
+        universe.tick();
+        for (index, &cell) in before.iter().enumerate() {
+            if cell == Cell::Alive {
+                assert_eq!(universe.get_cells()[index], Cell::Alive);
+            }
+        }
+        assert!(universe.population() >= population);
+        population = universe.population();
+    }
+    assert!(population > 5);
+
+    // 关闭后恢复原来的存活条件，孤立的 Cell 会死亡
+    universe.set_life_without_death(false);
+    assert_eq!(universe.rule(), "B3/S23");
+    let mut lonely = Universe::new_with_size(5, 5).unwrap();
+    lonely.set_cells(&[(2, 2)]);
+    lonely.set_life_without_death(true);
+    lonely.tick();
+    assert_eq!(lonely.population(), 1);
+    lonely.set_rule("B3/S23").unwrap();
+    assert!(!lonely.life_without_death());
+    lonely.tick();
+    assert_eq!(lonely.population(), 0);
+}
+
+#[wasm_bindgen_test]
+pub fn test_live_components() {
+    let mut universe = Universe::new_with_size(10, 8).unwrap();
+    // 跨越左右接缝的横线、跨越上下接缝的方块，以及一个普通的闪烁器
+    universe.set_cells(&[(2, 9), (2, 0), (2, 1)]);
+    universe.set_cells(&[(0, 5), (7, 5), (0, 4), (7, 4)]);
+    universe.set_cells(&[(5, 2), (5, 3), (5, 4)]);
+
+    let components = universe.live_components();
+    let boxes: Vec<(u32, u32, u32, u32, u32)> = components
+        .iter()
+        .map(|c| (c.cell_count, c.row, c.column, c.height, c.width))
+        .collect();
+    assert_eq!(boxes, [(4, 7, 4, 2, 2), (3, 2, 9, 1, 3), (3, 5, 2, 1, 3)]);
+    let centroids: Vec<(f64, f64)> = components.iter().map(|c| c.centroid).collect();
+    assert_eq!(centroids, [(7.5, 4.5), (2.0, 0.0), (5.0, 3.0)]);
+    assert_eq!(
+        components.iter().map(|c| c.id).collect::<Vec<_>>(),
+        [4, 20, 52]
+    );
+
+    // 有限网格中接缝两侧是不同的簇
+    universe.set_boundary(Boundary::Dead);
+    assert_eq!(universe.live_components().len(), 5);
+    universe.set_boundary(Boundary::Toroidal);
+
+    // id 是簇中第一个 Cell 的编号，与点击的是哪个 Cell 无关
+    assert_eq!(universe.component_at(2, 0).unwrap(), Some(20));
+    assert_eq!(universe.component_at(2, 9).unwrap(), Some(20));
+    assert_eq!(universe.component_at(7, 5).unwrap(), Some(4));
+    assert_eq!(universe.component_at(3, 3).unwrap(), None);
+    assert!(universe.component_at(8, 0).is_err());
+
+    // 簇中的其它 Cell 不是合法的 id
+    assert!(universe.erase_component(21).is_err());
+    universe.erase_component(20).unwrap();
+    assert_eq!(universe.population(), 7);
+    assert_eq!(universe.component_at(2, 0).unwrap(), None);
+    // 其它簇的 id 不受影响
+    assert_eq!(universe.component_at(5, 3).unwrap(), Some(52));
+    assert!(universe.erase_component(20).is_err());
+    assert!(universe.erase_component(80).is_err());
+}
+
+#[wasm_bindgen_test]
+pub fn test_rule_presets() {
+    let mut universe = Universe::new_with_size(8, 8).unwrap();
+    universe.use_highlife_preset();
+    assert_eq!(universe.rule(), "B36/S23");
+    universe.use_daynight_preset();
+    assert_eq!(universe.rule(), "B3678/S34678");
+    universe.use_seeds_preset();
+    assert_eq!(universe.rule(), "B2/S");
+
+    // Seeds 中每个 Cell 只存活一代，两个相邻的 Cell 在两侧各生出两个
+    universe.set_cells(&[(3, 3), (3, 4)]);
+    universe.tick();
+    let alive: Vec<(u32, u32)> = (0..8)
+        .flat_map(|r| (0..8).map(move |c| (r, c)))
+        .filter(|&(r, c)| universe[(r, c)].is_alive())
+        .collect();
+    assert_eq!(alive, [(2, 3), (2, 4), (4, 3), (4, 4)]);
+
+    // 预设会关闭 Life without Death 模式
+    universe.set_life_without_death(true);
+    universe.use_highlife_preset();
+    assert!(!universe.life_without_death());
+    assert_eq!(universe.rule(), "B36/S23");
+}
+
+#[wasm_bindgen_test]
+pub fn test_merge() {
+    use wasm_game_of_life::MergeOp;
+
+    // a 是左边两列，b 是上面两行
+    let board = |cells: &[(u32, u32)]| {
+        let mut universe = Universe::new_with_size(3, 3).unwrap();
+        universe.set_cells(cells);
+        universe
+    };
+    let a = board(&[(0, 0), (0, 1), (1, 0), (1, 1), (2, 0), (2, 1)]);
+    let b = board(&[(0, 0), (0, 1), (0, 2), (1, 0), (1, 1), (1, 2)]);
+    let merged = |op| {
+        let mut universe = a.duplicate();
+        universe.merge(&b, op).unwrap();
+        universe.to_string()
+    };
+    assert_eq!(merged(MergeOp::Or), "◼◼◼\n◼◼◼\n◼◼◻\n");
+    assert_eq!(merged(MergeOp::And), "◼◼◻\n◼◼◻\n◻◻◻\n");
+    assert_eq!(merged(MergeOp::Xor), "◻◻◼\n◻◻◼\n◼◼◻\n");
+    assert_eq!(merged(MergeOp::AndNot), "◻◻◻\n◻◻◻\n◼◼◻\n");
+
+    let mut universe = a.duplicate();
+    let err = universe
+        .merge(&Universe::new_with_size(3, 4).unwrap(), MergeOp::Or)
+        .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "cannot merge a 3x4 universe into a 3x3 universe"
+    );
+    assert_eq!(universe.get_cells(), a.get_cells());
+}
+
+#[wasm_bindgen_test]
+pub fn test_auto_expand() {
+    let glider_near_edge = || {
+        let mut universe = Universe::new_with_size(10, 10).unwrap();
+        universe.set_boundary(Boundary::Dead);
+        universe.stamp_rle("bo$2bo$3o!", 5, 5).unwrap();
+        universe
+    };
+
+    // 不扩展时滑翔机撞到边界后变成方块
+    let mut fixed = glider_near_edge();
+    for _ in 0..20 {
+        fixed.tick();
+    }
+    assert_eq!(fixed.population(), 4);
+
+    let mut universe = glider_near_edge();
+    universe.set_auto_expand(true);
+    let mut reference = Universe::new_with_size(200, 200).unwrap();
+    reference.stamp_rle("bo$2bo$3o!", 5, 5).unwrap();
+    for _ in 0..40 {
+        universe.tick();
+        reference.tick();
+        assert_eq!(
+            universe.encode_pattern_fingerprint(),
+            reference.encode_pattern_fingerprint()
+        );
+    }
+    assert!(universe.width() > 10 && universe.width() == universe.height());
+    assert_eq!(universe.generation(), 40);
+    assert_eq!(universe.spaceship_direction(), Some(Direction::SouthEast));
+
+    // 扩展之后图案被移到中央，离最外一圈还有一段距离
+    let mut universe = glider_near_edge();
+    universe.set_auto_expand(true);
+    while universe.width() == 10 {
+        universe.tick();
+    }
+    assert_eq!(universe.width(), 42);
+    assert_eq!(universe.population_in(0, 0, 15, 42), 0);
+    assert_eq!(universe.population_in(15, 0, 12, 42), 5);
+}
+
+#[wasm_bindgen_test]
+pub fn test_rewind() {
+    let mut universe = Universe::new_with_size(64, 64).unwrap();
+    universe.set_random_alive_cells(1200, 7).unwrap();
+    universe.enable_rewind(20);
+    assert_eq!(universe.rewind_available(), 0);
+
+    let mut states = vec![universe.get_cells().to_vec()];
+    for _ in 0..10 {
+        universe.tick();
+        states.push(universe.get_cells().to_vec());
+    }
+    assert_eq!(universe.rewind_available(), 10);
+
+    universe.rewind(5).unwrap();
+    assert_eq!(universe.generation(), 5);
+    assert_eq!(universe.get_cells(), &states[5][..]);
+    assert_eq!(universe.rewind_available(), 5);
+    // 回退之后重新 tick 得到同样的状态
+    for state in &states[6..] {
+        universe.tick();
+        assert_eq!(universe.get_cells(), &state[..]);
+    }
+    assert_eq!(universe.rewind_available(), 10);
+    universe.rewind(10).unwrap();
+    assert_eq!(universe.get_cells(), &states[0][..]);
+    assert_eq!(universe.generation(), 0);
+
+    // 超出缓冲区时报错，状态不变
+    universe.tick();
+    assert!(matches!(
+        universe.rewind(2),
+        Err(UniverseError::InvalidArgument(_))
+    ));
+    assert_eq!(universe.get_cells(), &states[1][..]);
+    assert_eq!(universe.generation(), 1);
+
+    // 只保留最近 capacity 代
+    universe.enable_rewind(3);
+    for _ in 0..10 {
+        universe.tick();
+    }
+    assert_eq!(universe.rewind_available(), 3);
+    universe.enable_rewind(0);
+    assert_eq!(universe.rewind_available(), 0);
+    assert_eq!(universe.rewind_memory_bytes(), 0);
+
+    // 稀疏的图案只保存变化的 Cell
+    let mut universe = Universe::new_with_size(256, 256).unwrap();
+    universe.stamp_rle("bo$2bo$3o!", 10, 10).unwrap();
+    universe.enable_rewind(100);
+    for _ in 0..100 {
+        universe.tick();
+    }
+    assert_eq!(universe.rewind_available(), 100);
+    // 远小于每帧完整保存所需的 100 * 8 KB
+    assert!(universe.rewind_memory_bytes() < 32 * 1024);
+}
+
+#[wasm_bindgen_test]
+pub fn test_get_timeline() {
+    let mut universe = Universe::new_with_size(32, 32).unwrap();
+    universe.stamp_rle("b2o$2o$bo!", 14, 14).unwrap();
+    assert!(universe.get_timeline().is_empty());
+
+    universe.enable_rewind(8);
+    let mut populations = vec![];
+    for _ in 0..12 {
+        populations.push(universe.population());
+        universe.tick();
+        assert_eq!(universe.get_timeline().last(), populations.last());
+    }
+    // 只保留最近 8 代
+    assert_eq!(universe.get_timeline(), &populations[4..]);
+
+    universe.rewind(3).unwrap();
+    assert_eq!(universe.population(), populations[9]);
+    assert_eq!(universe.get_timeline(), &populations[4..9]);
+}
+
+#[wasm_bindgen_test]
+pub fn test_compare_engines() {
+    // 包括只有一两行、一两列这种绕回到自己的网格
+    for &(width, height) in [(48, 32), (1, 1), (2, 5), (7, 1)].iter() {
+        let results = compare_engines(width, height, 0.4, 11, 30).unwrap();
+        let names: Vec<&str> = results.iter().map(|r| r.engine.name()).collect();
+        assert_eq!(names, ["counting", "rule-table"]);
+        assert_eq!(results[0].state_hash, results[1].state_hash);
+        assert!(results
+            .iter()
+            .all(|r| !r.skipped && r.generations == 30 && r.generations_per_sec > 0.0));
+    }
+    assert!(matches!(
+        compare_engines(0, 10, 0.4, 11, 30),
+        Err(UniverseError::InvalidDimensions { .. })
+    ));
+}
+
+#[wasm_bindgen_test]
+pub fn test_undo_stack_memory() {
+    let mut universe = Universe::new_with_size(40, 40).unwrap();
+    universe.enable_rewind(10);
+    let mut states = vec![];
+    for i in 0..8 {
+        states.push(universe.get_cells().to_vec());
+        universe.set_cell(i, i, Cell::Alive).unwrap();
+    }
+    // 撤销栈只记录编辑，与回退缓冲区无关
+    assert_eq!(universe.undo_stack_depth(), 8);
+    assert_eq!(universe.rewind_available(), 0);
+
+    let before = universe.undo_memory_bytes();
+    universe.trim_undo_stack_to(3);
+    assert_eq!(universe.undo_stack_depth(), 3);
+    assert!(universe.undo_memory_bytes() < before);
+    // 剩下的是最近的 3 次编辑
+    for _ in 0..3 {
+        assert!(universe.undo());
+    }
+    assert!(!universe.undo());
+    assert_eq!(universe.get_cells(), &states[5][..]);
+
+    universe.set_undo_capacity(10);
+    universe.clear_undo_stack();
+    assert_eq!(universe.undo_stack_depth(), 0);
+    assert_eq!(universe.undo_memory_bytes(), 0);
+    // 容量不变，之后的编辑继续记录
+    for i in 0..12 {
+        universe.toggle_cell(0, i).unwrap();
+    }
+    assert_eq!(universe.undo_stack_depth(), 10);
+}
+
+#[wasm_bindgen_test]
+pub fn test_tick_many_with_callback() {
+    let mut universe = input_spaceship();
+    let mut generations = vec![];
+    universe.tick_many_with_callback(10, 3, |generation| generations.push(generation));
+    assert_eq!(generations, [3, 6, 9]);
+    assert_eq!(universe.generation(), 10);
+
+    let mut calls = 0;
+    universe.tick_many_with_callback(5, 0, |_| calls += 1);
+    assert_eq!(calls, 0);
+    assert_eq!(universe.generation(), 15);
+}
+
+#[wasm_bindgen_test]
+pub fn test_metrics() {
+    let mut universe = Universe::new_with_size(30, 30).unwrap();
+    assert_eq!(
+        universe.metrics(),
+        Metrics {
+            density: 0.0,
+            entropy: 0.0,
+            active_cells: 0
+        }
+    );
+
+    // 信号灯每一代有 4 个 Cell 变化
+    universe.set_cells(&[(10, 9), (10, 10), (10, 11)]);
+    universe.tick();
+    let metrics = universe.metrics();
+    assert_eq!(metrics.density, 3.0 / 900.0);
+    assert_eq!(metrics.active_cells, 4);
+    assert!(metrics.entropy > 0.0);
+
+    // 不足一个方块的边缘被忽略：这些 Cell 都在 28、29 行或列
+    let mut edge = Universe::new_with_size(30, 30).unwrap();
+    edge.set_cells(&[(29, 0), (0, 28), (29, 29)]);
+    assert_eq!(edge.block_entropy(), 0.0);
+
+    // 50% 的随机网格接近上限 log2(1024) = 10
+    let random = UniverseBuilder::new()
+        .width(128)
+        .height(128)
+        .density(0.5)
+        .seed(2024)
+        .build()
+        .unwrap();
+    let entropy = random.metrics().entropy;
+    assert!(entropy > 9.9 && entropy <= 10.0, "{}", entropy);
+    assert!((random.metrics().density - 0.5).abs() < 0.02);
+}
+
+#[wasm_bindgen_test]
+pub fn test_neighbor_counts_ptr() {
+    let mut universe = UniverseBuilder::new()
+        .width(24)
+        .height(16)
+        .density(0.4)
+        .seed(5)
+        .build()
+        .unwrap();
+    let read = |universe: &mut Universe| {
+        let ptr = universe.neighbor_counts_ptr();
+        unsafe { std::slice::from_raw_parts(ptr, universe.cells_byte_len()) }.to_vec()
+    };
+
+    let counts = read(&mut universe);
+    let mut histogram = vec![0; 9];
+    for &count in counts.iter() {
+        histogram[count as usize] += 1;
+    }
+    assert_eq!(histogram, universe.neighbor_histogram());
+    assert_eq!(histogram.iter().sum::<u32>(), 24 * 16);
+
+    // 与 cells() 的布局相同
+    universe.reset();
+    universe.set_cells(&[(5, 5)]);
+    let counts = read(&mut universe);
+    assert_eq!(counts.iter().map(|&c| c as u32).sum::<u32>(), 8);
+    assert_eq!(counts[4 * 24 + 4], 1);
+    assert_eq!(counts[5 * 24 + 5], 0);
+
+    // 修改之后重新计算
+    universe.toggle_cell(5, 6).unwrap();
+    let counts = read(&mut universe);
+    assert_eq!(counts[5 * 24 + 5], 1);
+    universe.set_boundary(Boundary::Alive);
+    let counts = read(&mut universe);
+    assert_eq!(counts[0], 5);
+}
+
+#[wasm_bindgen_test]
+pub fn test_swap() {
+    let mut before = input_spaceship();
+    let mut after = input_spaceship();
+    after.set_rule("B36/S23").unwrap();
+    after.tick();
+    let (before_cells, after_cells) = (before.get_cells().to_vec(), after.get_cells().to_vec());
+    let after_ptr = after.cells();
+
+    before.swap(&mut after).unwrap();
+    assert_eq!(before.get_cells(), &after_cells[..]);
+    assert_eq!(after.get_cells(), &before_cells[..]);
+    assert_eq!(before.cells(), after_ptr);
+    assert_eq!((before.generation(), after.generation()), (1, 0));
+    assert_eq!(
+        (before.rule(), after.rule()),
+        ("B36/S23".to_string(), "B3/S23".to_string())
+    );
+
+    let mut other = Universe::new_with_size(6, 7).unwrap();
+    assert!(matches!(
+        before.swap(&mut other),
+        Err(UniverseError::InvalidArgument(_))
+    ));
+    assert_eq!(before.get_cells(), &after_cells[..]);
+}
+
+#[wasm_bindgen_test]
+pub fn test_set_rng() {
+    let seeded = |kind: RngKind| {
+        let mut universe = Universe::new_with_size(32, 32).unwrap();
+        assert_eq!(universe.rng_kind(), RngKind::Std);
+        universe.set_rng(kind);
+        assert_eq!(universe.rng_kind(), kind);
+        universe.set_seed(9);
+        universe
+    };
+    let mut outputs = vec![];
+    for &kind in [RngKind::Std, RngKind::Small].iter() {
+        let (mut a, mut b) = (seeded(kind), seeded(kind));
+        a.start();
+        b.start();
+        assert_eq!(a.get_cells(), b.get_cells());
+        a.perturb(50, 3);
+        b.perturb(50, 3);
+        assert_eq!(a.get_cells(), b.get_cells());
+        outputs.push(a.get_cells().to_vec());
+
+        // 接受种子的操作也使用选择的生成器
+        a.set_random_alive_cells(100, 4).unwrap();
+        b.set_random_alive_cells(100, 4).unwrap();
+        assert_eq!(a.get_cells(), b.get_cells());
+        assert_eq!(a.population(), 100);
+        outputs.push(a.get_cells().to_vec());
+    }
+    assert_ne!(outputs[0], outputs[2]);
+    assert_ne!(outputs[1], outputs[3]);
+}
+
+#[wasm_bindgen_test]
+pub fn test_region_populations() {
+    let universe = UniverseBuilder::new()
+        .width(30)
+        .height(21)
+        .density(0.3)
+        .seed(8)
+        .build()
+        .unwrap();
+    assert_eq!(
+        universe.region_populations(1, 1).unwrap(),
+        [universe.population()]
+    );
+    // 4x4：每个区域 5 行 7 列，最后一行和最后一列的区域多 1 行、2 列
+    let regions = universe.region_populations(4, 4).unwrap();
+    assert_eq!(regions.len(), 16);
+    assert_eq!(regions[0], universe.population_in(0, 0, 5, 7));
+    assert_eq!(regions[15], universe.population_in(15, 21, 6, 9));
+    assert_eq!(regions.iter().sum::<u32>(), universe.population());
+
+    let mut universe = Universe::new_with_size(8, 8).unwrap();
+    universe.set_cells(&[(0, 0), (1, 2), (3, 3), (2, 1)]);
+    assert_eq!(universe.region_populations(2, 2).unwrap(), [4, 0, 0, 0]);
+
+    for &(rows, cols) in [(0, 2), (2, 0), (9, 1), (1, 9)].iter() {
+        assert!(matches!(
+            universe.region_populations(rows, cols),
+            Err(UniverseError::InvalidArgument(_))
+        ));
+    }
+}
+
+#[wasm_bindgen_test]
+pub fn test_compute_alive_density_over_time() {
+    // 飞船的密度不变
+    let mut universe = Universe::new_with_size(20, 20).unwrap();
+    universe.stamp_rle("bo$2bo$3o!", 2, 2).unwrap();
+    let densities = universe.compute_alive_density_over_time(12);
+    assert_eq!(densities, vec![5.0 / 400.0; 12]);
+    assert_eq!(universe.generation(), 12);
+
+    // 逐渐消亡的图案单调递减
+    let mut universe = Universe::new_with_size(20, 20).unwrap();
+    universe.set_cells(&[(5, 5), (6, 6), (7, 7), (8, 8), (10, 10)]);
+    let densities = universe.compute_alive_density_over_time(4);
+    assert!(densities.windows(2).all(|pair| pair[1] <= pair[0]));
+    assert_eq!(densities.last(), Some(&0.0));
+
+    // 随机汤收敛到较低的密度
+    let mut soup = UniverseBuilder::new()
+        .width(48)
+        .height(48)
+        .density(0.5)
+        .seed(1)
+        .build()
+        .unwrap();
+    let densities = soup.compute_alive_density_over_time(100);
+    assert_eq!(densities.len(), 100);
+    assert!(densities[99] < 0.25);
+}
+
+#[wasm_bindgen_test]
+pub fn test_run_until() {
+    let r_pentomino = || {
+        let mut universe = Universe::new_with_size(64, 64).unwrap();
+        universe.stamp_rle("b2o$2o$bo!", 30, 30).unwrap();
+        universe
+    };
+
+    // 64x64 的环面上，R-pentomino 在第 404 代第一次超过 300 个存活 Cell
+    let mut universe = r_pentomino();
+    let result = universe.run_until(StopCondition::population_above(300), 5000);
+    assert!(result.condition_met());
+    assert_eq!(result.generations(), 404);
+    assert_eq!(universe.generation(), 404);
+    let mut reference = r_pentomino();
+    let populations = reference.tick_many_stats(404);
+    assert!(populations[..403]
+        .iter()
+        .all(|&population| population <= 300));
+    assert_eq!(universe.population(), populations[403]);
+
+    // 已经满足的条件不执行 tick
+    let result = universe.run_until(StopCondition::population_above(300), 5000);
+    assert_eq!((result.condition_met(), result.generations()), (true, 0));
+    let result = universe.run_until(StopCondition::generation_reached(410), 5000);
+    assert_eq!((result.condition_met(), result.generations()), (true, 6));
+
+    // 条件一直不满足时达到上限
+    let mut universe = r_pentomino();
+    let result = universe.run_until(StopCondition::extinct(), 100);
+    assert_eq!((result.condition_met(), result.generations()), (false, 100));
+    assert_eq!(universe.generation(), 100);
+
+    // 方块第一次 tick 就稳定了；逐渐消亡的图案跌破阈值
+    let mut block = Universe::new_with_size(8, 8).unwrap();
+    block.set_cells(&[(2, 2), (2, 3), (3, 2), (3, 3)]);
+    let result = block.run_until(StopCondition::stable(), 10);
+    assert_eq!((result.condition_met(), result.generations()), (true, 1));
+    let mut diagonal = Universe::new_with_size(8, 8).unwrap();
+    diagonal.set_cells(&[(1, 1), (2, 2), (3, 3), (4, 4)]);
+    let result = diagonal.run_until(StopCondition::population_below(1), 10);
+    assert_eq!((result.condition_met(), result.generations()), (true, 2));
 }
@@ -1,9 +1,9 @@
 // Import the WebAssembly memory at the top of the file.
 // wasm-game-of-life/wasm_game_of_life_bg.wasm
-import { Cell, Universe } from "wasm-game-of-life";
+import { Cell, Universe, pinch_scale, pointer_distance } from "wasm-game-of-life";
 import { memory } from "wasm-game-of-life/wasm_game_of_life_bg";
 
-const CELL_SIZE = 5;
+const BASE_CELL_SIZE = 5;
 const GRID_COLOR = "#CCCCCC";
 const DEAD_COLOR = "#FFFFFF";
 const ALIVE_COLOR = "#000000";
@@ -21,9 +21,14 @@ const range = document.getElementById("range");
 const rangeValue = document.getElementById("range-value");
 let counter = 0;
 const ctx = canvas.getContext("2d");
+let cellSize = BASE_CELL_SIZE;
 
-canvas.height = (CELL_SIZE + 1) * height + 1;
-canvas.width = (CELL_SIZE + 1) * width + 1;
+function resizeCanvas() {
+  canvas.height = (cellSize + 1) * height + 1;
+  canvas.width = (cellSize + 1) * width + 1;
+}
+
+resizeCanvas();
 
 reset.addEventListener("click", function () {
   universe.reset();
@@ -133,21 +138,24 @@ function drawGrid() {
 
   // Vertical lines.
   for (let i = 0; i <= width; i++) {
-    ctx.moveTo(i * (CELL_SIZE + 1) + 1, 0);
-    ctx.lineTo(i * (CELL_SIZE + 1) + 1, (CELL_SIZE + 1) * height + 1);
+    ctx.moveTo(i * (cellSize + 1) + 1, 0);
+    ctx.lineTo(i * (cellSize + 1) + 1, (cellSize + 1) * height + 1);
   }
 
   // Horizontal lines.
   for (let j = 0; j <= height; j++) {
-    ctx.moveTo(0, j * (CELL_SIZE + 1) + 1);
-    ctx.lineTo((CELL_SIZE + 1) * width + 1, j * (CELL_SIZE + 1) + 1);
+    ctx.moveTo(0, j * (cellSize + 1) + 1);
+    ctx.lineTo((cellSize + 1) * width + 1, j * (cellSize + 1) + 1);
   }
 
   ctx.stroke();
 }
 
+// 与 Universe::get_index 一致，考虑平移产生的环形偏移
 function getIndex(row, column) {
-  return row * width + column;
+  const r = (row + universe.row_offset()) % height;
+  const c = (column + universe.col_offset()) % width;
+  return r * width + c;
 }
 
 function drawCells() {
@@ -162,7 +170,7 @@ function drawCells() {
       const idx = getIndex(row, col);
 
       if (cells[idx] === Cell.Alive) {
-        ctx.fillRect(col * (CELL_SIZE + 1) + 1, row * (CELL_SIZE + 1) + 1, CELL_SIZE, CELL_SIZE);
+        ctx.fillRect(col * (cellSize + 1) + 1, row * (cellSize + 1) + 1, cellSize, cellSize);
       }
     }
   }
@@ -173,7 +181,7 @@ function drawCells() {
       const idx = getIndex(row, col);
 
       if (cells[idx] === Cell.Dead) {
-        ctx.fillRect(col * (CELL_SIZE + 1) + 1, row * (CELL_SIZE + 1) + 1, CELL_SIZE, CELL_SIZE);
+        ctx.fillRect(col * (cellSize + 1) + 1, row * (cellSize + 1) + 1, cellSize, cellSize);
       }
     }
   }
@@ -181,7 +189,8 @@ function drawCells() {
   ctx.stroke();
 }
 
-canvas.addEventListener("click", function (event) {
+// 将页面坐标换算为 Cell 的行列
+function eventToCell(event) {
   const boundingRect = canvas.getBoundingClientRect();
 
   // 换算比例
@@ -193,8 +202,14 @@ canvas.addEventListener("click", function (event) {
   const canvasTop = (event.clientY - boundingRect.top) * scaleY;
 
   // 计算行列
-  const row = Math.min(Math.floor(canvasTop / (CELL_SIZE + 1)), height - 1);
-  const col = Math.min(Math.floor(canvasLeft / (CELL_SIZE + 1)), width - 1);
+  const row = Math.min(Math.floor(canvasTop / (cellSize + 1)), height - 1);
+  const col = Math.min(Math.floor(canvasLeft / (cellSize + 1)), width - 1);
+
+  return [row, col];
+}
+
+canvas.addEventListener("click", function (event) {
+  const [row, col] = eventToCell(event);
 
   // 判断点击时是否按下 Ctrl/Shift 键
   // TODO: 生成不同的图形
@@ -210,6 +225,96 @@ canvas.addEventListener("click", function (event) {
   drawCells();
 });
 
+// 触摸操作：单指拖动绘制，双指捏合缩放，双指拖动平移
+// 鼠标仍然使用上面的 click 事件
+canvas.style.touchAction = "none";
+const pointers = new Map();
+let lastPaintCell = null;
+let pinchStart = null;
+
+function activePointers() {
+  return Array.from(pointers.values());
+}
+
+function pinchState() {
+  const [a, b] = activePointers();
+  return {
+    distance: pointer_distance(a.clientX, a.clientY, b.clientX, b.clientY),
+    centerX: (a.clientX + b.clientX) / 2,
+    centerY: (a.clientY + b.clientY) / 2,
+    cellSize,
+  };
+}
+
+canvas.addEventListener("pointerdown", function (event) {
+  if (event.pointerType !== "touch") {
+    return;
+  }
+  event.preventDefault();
+  canvas.setPointerCapture(event.pointerId);
+  pointers.set(event.pointerId, event);
+
+  if (pointers.size === 1) {
+    const [row, col] = eventToCell(event);
+    universe.paint_line(row, col, row, col);
+    lastPaintCell = [row, col];
+  } else if (pointers.size === 2) {
+    lastPaintCell = null;
+    pinchStart = pinchState();
+  }
+
+  drawGrid();
+  drawCells();
+});
+
+canvas.addEventListener("pointermove", function (event) {
+  if (!pointers.has(event.pointerId)) {
+    return;
+  }
+  event.preventDefault();
+  pointers.set(event.pointerId, event);
+
+  if (pointers.size === 1 && lastPaintCell !== null) {
+    const [row, col] = eventToCell(event);
+    universe.paint_line(lastPaintCell[0], lastPaintCell[1], row, col);
+    lastPaintCell = [row, col];
+  } else if (pointers.size === 2 && pinchStart !== null) {
+    const current = pinchState();
+    const scale = pinch_scale(pinchStart.distance, current.distance);
+    const nextCellSize = Math.max(1, Math.round(pinchStart.cellSize * scale));
+    if (nextCellSize !== cellSize) {
+      cellSize = nextCellSize;
+      resizeCanvas();
+    }
+
+    // 平移的距离换算为 Cell 数
+    const dCol = Math.trunc((current.centerX - pinchStart.centerX) / (cellSize + 1));
+    const dRow = Math.trunc((current.centerY - pinchStart.centerY) / (cellSize + 1));
+    if (dCol !== 0 || dRow !== 0) {
+      universe.scroll(-dRow, -dCol);
+      pinchStart.centerX = current.centerX;
+      pinchStart.centerY = current.centerY;
+    }
+  }
+
+  drawGrid();
+  drawCells();
+});
+
+function pointerEnd(event) {
+  if (!pointers.delete(event.pointerId)) {
+    return;
+  }
+  event.preventDefault();
+  lastPaintCell = null;
+  if (pointers.size < 2) {
+    pinchStart = null;
+  }
+}
+
+canvas.addEventListener("pointerup", pointerEnd);
+canvas.addEventListener("pointercancel", pointerEnd);
+
 drawGrid();
 drawCells();
 requestAnimationFrame(renderLoop);
@@ -0,0 +1,22 @@
+// 在 Worker 中执行 tick 并绘制到 OffscreenCanvas，每一帧通过 ImageBitmap 转移给主线程
+const CELL_SIZE = 5;
+
+import("wasm-game-of-life").then(({ Universe }) => {
+  const universe = Universe.new();
+  const width = (CELL_SIZE + 1) * universe.width() + 1;
+  const height = (CELL_SIZE + 1) * universe.height() + 1;
+  const canvas = new OffscreenCanvas(width, height);
+  const ctx = canvas.getContext("2d");
+
+  function renderLoop() {
+    universe.tick();
+    universe.draw_to_offscreen(ctx, CELL_SIZE);
+
+    const bitmap = canvas.transferToImageBitmap();
+    self.postMessage({ bitmap, width, height }, [bitmap]);
+
+    requestAnimationFrame(renderLoop);
+  }
+
+  requestAnimationFrame(renderLoop);
+});
@@ -0,0 +1,12 @@
+// 模拟和绘制都在 Worker 中进行，主线程只负责显示 Worker 发回的 ImageBitmap
+// 需要使用 `wasm-pack build -- --features offscreen` 构建
+const canvas = document.getElementById("game-of-life-canvas");
+const ctx = canvas.getContext("bitmaprenderer");
+const worker = new Worker("./offscreen-worker.js");
+
+worker.onmessage = function (event) {
+  const { bitmap, width, height } = event.data;
+  canvas.width = width;
+  canvas.height = height;
+  ctx.transferFromImageBitmap(bitmap);
+};
@@ -0,0 +1,24 @@
+// 由 Universe.run_in_worker 启动，在 Worker 中执行 tick 后把结果发回主线程
+// 需要使用 `wasm-pack build -- --features worker` 构建
+//
+// onmessage 在加载 Wasm 模块之前同步设置，run_in_worker 在创建 Worker 之后立即发送的消息
+// 会等到模块加载完成再处理，不会丢失；任何错误都以 { error } 发回，让 Promise 被拒绝
+const wasm = import("wasm-game-of-life");
+
+self.onmessage = function (event) {
+  wasm
+    .then(({ Universe }) => {
+      const job = event.data;
+      const universe = Universe.from_worker_job(job);
+      for (let i = 0; i < job.ticks; i++) {
+        universe.tick();
+      }
+      const reply = universe.worker_reply(job.ticks);
+      universe.free();
+
+      self.postMessage(reply, [reply.cells.buffer]);
+    })
+    .catch((error) => {
+      self.postMessage({ error: String(error) });
+    });
+};